@@ -0,0 +1,69 @@
+use crate::auth::local_provider::LocalAuthProvider;
+use crate::auth::supabase_provider::SupabaseAuthProvider;
+use crate::dtos::auth_dto::SessionTokens;
+use crate::dtos::user_dto::{InviteUserDto, LoginUserDto, RegisterUserDto};
+use crate::models::user::User;
+use crate::notifications::notifier::Notifiers;
+use crate::services::totp_service;
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// What every provider returns for a wrong email or password. Only this error counts
+// towards the login lockout; outages and unverified or deactivated accounts don't.
+pub const INVALID_LOGIN: &str = "Email atau password salah";
+
+// Where accounts, passwords and verification live. Either way the `users` row and the
+// `auth_sessions` row are ours and the access token is checked by JwtVerifier.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    // Creates the account and its `users` row and sends the email verification code
+    async fn register(&self, user_data: &RegisterUserDto) -> Result<User, String>;
+    // Starts a session; its id must be the `session_id` claim of the access token
+    async fn login(&self, login_data: &LoginUserDto) -> Result<SessionTokens, String>;
+    // Issues a new token pair for the session. The caller has already checked `refresh_token`
+    // against the stored hash and rotates it afterwards.
+    async fn refresh(&self, session_id: Uuid, user_id: Uuid, refresh_token: &str) -> Result<SessionTokens, String>;
+    // Provider-side cleanup when a session ends; our own session row is revoked by the caller
+    async fn logout(&self, access_token: &str) -> Result<(), String>;
+    async fn verify_email(&self, email: &str, code: &str) -> Result<(), String>;
+    async fn resend_verification(&self, email: &str) -> Result<(), String>;
+    // Must not reveal whether the email is registered
+    async fn send_password_reset(&self, email: &str) -> Result<(), String>;
+    // `token` is whatever the reset email carried: a recovery session for Supabase,
+    // a one-time reset token for local auth
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String>;
+    // Creates an account for staff added by an admin and emails them a link to set a password
+    async fn invite(&self, invite_data: &InviteUserDto) -> Result<User, String>;
+    // Makes the current password stop working and emails a reset link
+    async fn force_password_reset(&self, user: &User) -> Result<(), String>;
+
+    // Two-factor login needs the password check and token issuance as separate steps, so a
+    // second factor can sit in between. Providers that can't split them keep the defaults.
+    fn supports_two_factor(&self) -> bool {
+        false
+    }
+    // Checks the credentials like `login` but issues nothing; returns the user id
+    async fn check_password(&self, _login_data: &LoginUserDto) -> Result<Uuid, String> {
+        Err("Two-factor login is not supported by this auth provider".to_string())
+    }
+    // Issues tokens for a new session of a user whose credentials were already checked
+    async fn start_session(&self, _user_id: Uuid) -> Result<SessionTokens, String> {
+        Err("Two-factor login is not supported by this auth provider".to_string())
+    }
+}
+
+// Picks the provider from AUTH_PROVIDER ("supabase" or "local"), defaulting to Supabase.
+// Enforcing 2FA (TOTP_REQUIRED_POSITIONS) needs a provider that supports it.
+pub fn from_env(notifiers: Arc<Notifiers>) -> Result<Arc<dyn AuthProvider>, String> {
+    let provider: Arc<dyn AuthProvider> = match env::var("AUTH_PROVIDER").unwrap_or_else(|_| "supabase".to_string()).as_str() {
+        "supabase" => Arc::new(SupabaseAuthProvider),
+        "local" => Arc::new(LocalAuthProvider::from_env(notifiers)?),
+        other => return Err(format!("Unknown AUTH_PROVIDER: {}", other)),
+    };
+    if !provider.supports_two_factor() && !totp_service::required_positions().is_empty() {
+        return Err("TOTP_REQUIRED_POSITIONS needs AUTH_PROVIDER=local".to_string());
+    }
+    Ok(provider)
+}
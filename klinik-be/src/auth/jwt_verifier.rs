@@ -0,0 +1,177 @@
+use jsonwebtoken::jwk::{Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, errors::ErrorKind, Algorithm, DecodingKey, Validation};
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::env;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+const DEFAULT_AUDIENCE: &str = "authenticated";
+const DEFAULT_JWKS_CACHE_SECONDS: u64 = 600;
+// An unknown `kid` triggers a refetch (the provider may have rotated keys), but not more
+// often than this, so garbage tokens can't make us hammer the JWKS endpoint
+const MIN_JWKS_REFRESH: Duration = Duration::from_secs(30);
+const LEEWAY_SECONDS: u64 = 30;
+
+enum JwksSource {
+    Static(JwkSet),
+    Remote { url: String, client: Client, ttl: Duration, cache: RwLock<Option<CachedJwks>> },
+}
+
+struct CachedJwks {
+    fetched_at: Instant,
+    keys: JwkSet,
+}
+
+// Verifies access tokens. Built once at startup from the environment:
+//   JWT_SECRET                 HS256 shared secret (SUPABASE_JWT_SECRET is read as a fallback)
+//   JWT_JWKS_URL / JWT_JWKS_FILE  public keys for RS256 and ES256, cached for JWT_JWKS_CACHE_SECONDS
+//   JWT_AUDIENCE               expected `aud`, defaults to Supabase's "authenticated"; empty disables
+//   JWT_ISSUER                 expected `iss`, unchecked when unset
+// At least one of the secret or a JWKS source must be configured.
+pub struct JwtVerifier {
+    secret: Option<DecodingKey>,
+    jwks: Option<JwksSource>,
+    audience: Option<String>,
+    issuer: Option<String>,
+}
+
+fn non_empty(key: &str) -> Option<String> {
+    env::var(key).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+// The same settings are used by the local auth provider to issue tokens this verifier accepts
+pub fn hs256_secret() -> Option<String> {
+    non_empty("JWT_SECRET").or_else(|| non_empty("SUPABASE_JWT_SECRET"))
+}
+
+pub fn audience() -> Option<String> {
+    match env::var("JWT_AUDIENCE") {
+        Ok(audience) => Some(audience.trim().to_string()).filter(|a| !a.is_empty()),
+        Err(_) => Some(DEFAULT_AUDIENCE.to_string()),
+    }
+}
+
+pub fn issuer() -> Option<String> {
+    non_empty("JWT_ISSUER")
+}
+
+impl JwtVerifier {
+    pub fn from_env() -> Result<JwtVerifier, String> {
+        let secret = hs256_secret().map(|secret| DecodingKey::from_secret(secret.as_bytes()));
+        let jwks = match (non_empty("JWT_JWKS_URL"), non_empty("JWT_JWKS_FILE")) {
+            (Some(_), Some(_)) => return Err("Set only one of JWT_JWKS_URL and JWT_JWKS_FILE".to_string()),
+            (Some(url), None) => Some(JwksSource::Remote {
+                url,
+                client: Client::new(),
+                ttl: Duration::from_secs(
+                    non_empty("JWT_JWKS_CACHE_SECONDS")
+                        .and_then(|v| v.parse().ok())
+                        .unwrap_or(DEFAULT_JWKS_CACHE_SECONDS),
+                ),
+                cache: RwLock::new(None),
+            }),
+            (None, Some(path)) => {
+                let contents = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read JWT_JWKS_FILE {}: {}", path, e))?;
+                let keys: JwkSet = serde_json::from_str(&contents)
+                    .map_err(|e| format!("Failed to parse JWT_JWKS_FILE {}: {}", path, e))?;
+                Some(JwksSource::Static(keys))
+            }
+            (None, None) => None,
+        };
+        if secret.is_none() && jwks.is_none() {
+            return Err("Configure JWT_SECRET or a JWKS source (JWT_JWKS_URL / JWT_JWKS_FILE)".to_string());
+        }
+        Ok(JwtVerifier { secret, jwks, audience: audience(), issuer: issuer() })
+    }
+
+    // Checks signature, expiry, audience and issuer. The error is a short reason that is
+    // safe to return to the client.
+    pub async fn verify<T: DeserializeOwned>(&self, token: &str) -> Result<T, String> {
+        let header = decode_header(token).map_err(|_| "Malformed token".to_string())?;
+        let key = match header.alg {
+            Algorithm::HS256 => self.secret.clone().ok_or_else(|| "HS256 tokens are not accepted".to_string())?,
+            Algorithm::RS256 | Algorithm::ES256 => self.public_key(header.kid.as_deref()).await?,
+            other => return Err(format!("Unsupported token algorithm {:?}", other)),
+        };
+
+        let mut validation = Validation::new(header.alg);
+        validation.leeway = LEEWAY_SECONDS;
+        if let Some(audience) = &self.audience {
+            validation.set_audience(&[audience]);
+        }
+        if let Some(issuer) = &self.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+        decode::<T>(token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => "Token expired".to_string(),
+                ErrorKind::ImmatureSignature => "Token not yet valid".to_string(),
+                ErrorKind::InvalidAudience => "Invalid token audience".to_string(),
+                ErrorKind::InvalidIssuer => "Invalid token issuer".to_string(),
+                ErrorKind::InvalidSignature => "Invalid token signature".to_string(),
+                ErrorKind::MissingRequiredClaim(claim) => format!("Token is missing the {} claim", claim),
+                _ => "Invalid token".to_string(),
+            })
+    }
+
+    async fn public_key(&self, kid: Option<&str>) -> Result<DecodingKey, String> {
+        let no_key = || "Unknown signing key".to_string();
+        match self.jwks.as_ref().ok_or_else(|| "Asymmetric tokens are not accepted".to_string())? {
+            JwksSource::Static(keys) => find_key(keys, kid).ok_or_else(no_key),
+            JwksSource::Remote { url, client, ttl, cache } => {
+                let (cached, age) = {
+                    let cache = cache.read().unwrap_or_else(|e| e.into_inner());
+                    match cache.as_ref() {
+                        Some(cached) => (find_key(&cached.keys, kid), Some(cached.fetched_at.elapsed())),
+                        None => (None, None),
+                    }
+                };
+                let stale = age.is_none_or(|age| age >= *ttl);
+                if let Some(key) = cached.as_ref().filter(|_| !stale) {
+                    return Ok(key.clone());
+                }
+                if age.is_some_and(|age| age < MIN_JWKS_REFRESH) {
+                    return cached.ok_or_else(no_key);
+                }
+                match fetch_jwks(client, url).await {
+                    Ok(keys) => {
+                        let key = find_key(&keys, kid);
+                        *cache.write().unwrap_or_else(|e| e.into_inner()) =
+                            Some(CachedJwks { fetched_at: Instant::now(), keys });
+                        key.ok_or_else(no_key)
+                    }
+                    // Keep serving the last known keys while the provider is unreachable
+                    Err(e) => {
+                        println!("{}", e);
+                        cached.ok_or_else(|| "Signing keys are unavailable".to_string())
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Without a `kid` the token can only be matched when the set has a single key
+fn find_key(keys: &JwkSet, kid: Option<&str>) -> Option<DecodingKey> {
+    let jwk: &Jwk = match kid {
+        Some(kid) => keys.find(kid)?,
+        None if keys.keys.len() == 1 => &keys.keys[0],
+        None => return None,
+    };
+    DecodingKey::from_jwk(jwk).ok()
+}
+
+async fn fetch_jwks(client: &Client, url: &str) -> Result<JwkSet, String> {
+    let res = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch JWKS: {}", e))?;
+    if !res.status().is_success() {
+        return Err(format!("JWKS endpoint returned {}", res.status()));
+    }
+    res.json::<JwkSet>().await.map_err(|e| format!("Failed to parse JWKS: {}", e))
+}
@@ -0,0 +1,361 @@
+use crate::auth::auth_provider::{AuthProvider, INVALID_LOGIN};
+use crate::auth::jwt_verifier;
+use crate::dtos::auth_dto::{CreateAuthCodeDto, CreateAuthCredentialDto, SessionTokens};
+use crate::dtos::user_dto::{InviteUserDto, LoginUserDto, RegisterUserDto};
+use crate::models::auth_code::AuthCode;
+use crate::models::user::User;
+use crate::notifications::notifier::{Notification, Notifiers};
+use crate::repositories::{auth_code_repo, auth_credential_repo, auth_session_repo, user_repo};
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use async_trait::async_trait;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use serde::Serialize;
+use sha2::Sha256;
+use std::env;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const EMAIL_VERIFICATION: &str = "email_verification";
+const PASSWORD_RESET: &str = "password_reset";
+const VERIFICATION_TTL_MINUTES: i64 = 30;
+const VERIFICATION_MAX_ATTEMPTS: i32 = 5;
+const RESEND_COOLDOWN_SECONDS: i64 = 60;
+const RESET_TTL_MINUTES: i64 = 60;
+const INVITE_TTL_MINUTES: i64 = 72 * 60;
+const DEFAULT_ACCESS_TOKEN_MINUTES: i64 = 60;
+
+#[derive(Serialize)]
+struct AccessClaims {
+    sub: String,
+    session_id: String,
+    role: String,
+    aud: Option<String>,
+    iss: Option<String>,
+    iat: usize,
+    exp: usize,
+}
+
+// Accounts kept in our own database: Argon2id password hashes in `auth_credentials`,
+// verification codes and reset tokens (HMAC only) in `auth_codes`, and HS256 access tokens
+// signed with JWT_SECRET so JwtVerifier accepts them. Refresh tokens are random strings;
+// session_service keeps their hashes. Codes go out through the email notifier.
+pub struct LocalAuthProvider {
+    notifiers: Arc<Notifiers>,
+    secret: String,
+    access_token_minutes: i64,
+}
+
+fn invalid_login() -> String {
+    INVALID_LOGIN.to_string()
+}
+
+fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+async fn hash_password(password: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let salt = SaltString::encode_b64(Uuid::new_v4().as_bytes()).map_err(|e| format!("Failed to create salt: {}", e))?;
+        Argon2::default()
+            .hash_password(password.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+            .map_err(|e| format!("Failed to hash password: {}", e))
+    })
+    .await
+    .map_err(|e| format!("Failed to hash password: {}", e))?
+}
+
+// Argon2 is deliberately slow, so both hashing and verifying run off the async workers
+async fn verify_password(password: String, password_hash: String) -> bool {
+    tokio::task::spawn_blocking(move || {
+        PasswordHash::new(&password_hash)
+            .is_ok_and(|hash| Argon2::default().verify_password(password.as_bytes(), &hash).is_ok())
+    })
+    .await
+    .unwrap_or(false)
+}
+
+impl LocalAuthProvider {
+    pub fn from_env(notifiers: Arc<Notifiers>) -> Result<LocalAuthProvider, String> {
+        let secret = jwt_verifier::hs256_secret().ok_or_else(|| "AUTH_PROVIDER=local requires JWT_SECRET".to_string())?;
+        let access_token_minutes = env::var("AUTH_ACCESS_TOKEN_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|minutes| *minutes > 0)
+            .unwrap_or(DEFAULT_ACCESS_TOKEN_MINUTES);
+        Ok(LocalAuthProvider { notifiers, secret, access_token_minutes })
+    }
+
+    fn keyed_hash(&self, value: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes()).expect("HMAC accepts any key length");
+        mac.update(value.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn issue_tokens(&self, session_id: Uuid, user_id: Uuid) -> Result<SessionTokens, String> {
+        let now = Utc::now();
+        let claims = AccessClaims {
+            sub: user_id.to_string(),
+            session_id: session_id.to_string(),
+            role: "authenticated".to_string(),
+            aud: jwt_verifier::audience(),
+            iss: jwt_verifier::issuer(),
+            iat: now.timestamp() as usize,
+            exp: (now + Duration::minutes(self.access_token_minutes)).timestamp() as usize,
+        };
+        let access_token = encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(self.secret.as_bytes()))
+            .map_err(|e| format!("Failed to issue access token: {}", e))?;
+        Ok(SessionTokens {
+            user_id,
+            session_id,
+            access_token,
+            refresh_token: format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple()),
+            expires_in: self.access_token_minutes * 60,
+        })
+    }
+
+    async fn send_email(&self, recipient: &str, subject: &str, body: String) -> Result<(), String> {
+        let notification = Notification { recipient: recipient.to_string(), subject: subject.to_string(), body };
+        self.notifiers.email.send(&notification).await
+    }
+
+    async fn send_verification_code(&self, user: &User) -> Result<(), String> {
+        let code = format!("{:06}", Uuid::new_v4().as_u128() % 1_000_000);
+        auth_code_repo::create_auth_code(&CreateAuthCodeDto {
+            user_id: user.id,
+            purpose: EMAIL_VERIFICATION.to_string(),
+            code_hash: self.keyed_hash(&format!("{}:{}", user.id, code)),
+            expires_at: Utc::now() + Duration::minutes(VERIFICATION_TTL_MINUTES),
+        })
+        .await?;
+        self.send_email(
+            &user.email,
+            "Kode verifikasi email",
+            format!(
+                "Halo {}, kode verifikasi Anda adalah {}. Kode berlaku {} menit.",
+                user.name, code, VERIFICATION_TTL_MINUTES
+            ),
+        )
+        .await
+    }
+
+    // Emails a one-time link to the reset page; `body` gets the link and wraps it in the message
+    async fn send_reset_link(
+        &self,
+        user: &User,
+        ttl_minutes: i64,
+        subject: &str,
+        body: impl FnOnce(&str) -> String + Send,
+    ) -> Result<(), String> {
+        let token = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        auth_code_repo::create_auth_code(&CreateAuthCodeDto {
+            user_id: user.id,
+            purpose: PASSWORD_RESET.to_string(),
+            code_hash: self.keyed_hash(&token),
+            expires_at: Utc::now() + Duration::minutes(ttl_minutes),
+        })
+        .await?;
+        let link = format!("{}?token={}", user_repo::reset_redirect_url(), token);
+        self.send_email(&user.email, subject, body(&link)).await
+    }
+
+    // The newest unexpired, unused code of this purpose
+    async fn active_code(&self, user_id: Uuid, purpose: &str, ttl_minutes: i64) -> Result<Option<AuthCode>, String> {
+        let now = Utc::now();
+        Ok(auth_code_repo::get_auth_codes_since(user_id, purpose, now - Duration::minutes(ttl_minutes))
+            .await?
+            .into_iter()
+            .find(|code| code.consumed_at.is_none() && code.expires_at > now))
+    }
+}
+
+#[async_trait]
+impl AuthProvider for LocalAuthProvider {
+    async fn register(&self, user_data: &RegisterUserDto) -> Result<User, String> {
+        let email = normalize_email(&user_data.email);
+        if user_repo::get_user_by_email(&email).await?.is_some() {
+            return Err("Email ini sudah terdaftar. Silakan login atau gunakan email lain.".to_string());
+        }
+        let password_hash = hash_password(user_data.password.clone()).await?;
+        let user = user_repo::create_user(&User {
+            id: Uuid::new_v4(),
+            name: user_data.name.clone(),
+            email,
+            position: user_data.position.clone(),
+            deactivated_at: None,
+        })
+        .await?;
+        auth_credential_repo::create_auth_credential(&CreateAuthCredentialDto { user_id: user.id, password_hash }).await?;
+        if let Err(e) = self.send_verification_code(&user).await {
+            // The account exists; the user can ask for a new code
+            println!("Failed to send verification code to {}: {}", user.email, e);
+        }
+        Ok(user)
+    }
+
+    async fn login(&self, login_data: &LoginUserDto) -> Result<SessionTokens, String> {
+        let user_id = self.check_password(login_data).await?;
+        self.start_session(user_id).await
+    }
+
+    fn supports_two_factor(&self) -> bool {
+        true
+    }
+
+    async fn check_password(&self, login_data: &LoginUserDto) -> Result<Uuid, String> {
+        let user = user_repo::get_user_by_email(&normalize_email(&login_data.email))
+            .await?
+            .ok_or_else(invalid_login)?;
+        let credential = auth_credential_repo::get_auth_credential(user.id)
+            .await?
+            .ok_or_else(invalid_login)?;
+        if !verify_password(login_data.password.clone(), credential.password_hash).await {
+            return Err(invalid_login());
+        }
+        if credential.email_verified_at.is_none() {
+            return Err("Email belum diverifikasi. Masukkan kode yang dikirim ke email Anda.".to_string());
+        }
+        Ok(user.id)
+    }
+
+    async fn start_session(&self, user_id: Uuid) -> Result<SessionTokens, String> {
+        self.issue_tokens(Uuid::new_v4(), user_id)
+    }
+
+    async fn refresh(&self, session_id: Uuid, user_id: Uuid, _refresh_token: &str) -> Result<SessionTokens, String> {
+        self.issue_tokens(session_id, user_id)
+    }
+
+    async fn logout(&self, _access_token: &str) -> Result<(), String> {
+        Ok(())
+    }
+
+    async fn verify_email(&self, email: &str, code: &str) -> Result<(), String> {
+        let invalid = || "Kode verifikasi salah atau sudah kedaluwarsa".to_string();
+        let user = user_repo::get_user_by_email(&normalize_email(email)).await?.ok_or_else(invalid)?;
+        let pending = self
+            .active_code(user.id, EMAIL_VERIFICATION, VERIFICATION_TTL_MINUTES)
+            .await?
+            .ok_or_else(invalid)?;
+        if pending.attempts >= VERIFICATION_MAX_ATTEMPTS {
+            return Err("Terlalu banyak percobaan. Minta kode baru.".to_string());
+        }
+        // Every comparison, right or wrong, uses up an attempt first
+        if !auth_code_repo::claim_attempt(pending.id, pending.attempts).await? {
+            return Err(invalid());
+        }
+        if self.keyed_hash(&format!("{}:{}", user.id, code.trim())) != pending.code_hash {
+            return Err(invalid());
+        }
+        if !auth_code_repo::consume_auth_code(pending.id).await? {
+            return Err(invalid());
+        }
+        auth_credential_repo::mark_email_verified(user.id).await
+    }
+
+    async fn resend_verification(&self, email: &str) -> Result<(), String> {
+        let Some(user) = user_repo::get_user_by_email(&normalize_email(email)).await? else {
+            return Ok(());
+        };
+        let Some(credential) = auth_credential_repo::get_auth_credential(user.id).await? else {
+            return Ok(());
+        };
+        if credential.email_verified_at.is_some() {
+            return Ok(());
+        }
+        let recent = auth_code_repo::get_auth_codes_since(
+            user.id,
+            EMAIL_VERIFICATION,
+            Utc::now() - Duration::seconds(RESEND_COOLDOWN_SECONDS),
+        )
+        .await?;
+        if !recent.is_empty() {
+            return Err(format!("Tunggu {} detik sebelum meminta kode baru", RESEND_COOLDOWN_SECONDS));
+        }
+        self.send_verification_code(&user).await
+    }
+
+    async fn send_password_reset(&self, email: &str) -> Result<(), String> {
+        let Some(user) = user_repo::get_user_by_email(&normalize_email(email)).await? else {
+            return Ok(());
+        };
+        if auth_credential_repo::get_auth_credential(user.id).await?.is_none() {
+            return Ok(());
+        }
+        self.send_reset_link(&user, RESET_TTL_MINUTES, "Reset password", |link| {
+            format!(
+                "Halo {}, buka tautan berikut untuk mengganti password Anda: {}\nTautan berlaku {} menit. Abaikan email ini jika Anda tidak memintanya.",
+                user.name, link, RESET_TTL_MINUTES
+            )
+        })
+        .await
+    }
+
+    // The account starts without a usable password; the emailed link sets one and, like any
+    // reset, marks the address verified
+    async fn invite(&self, invite_data: &InviteUserDto) -> Result<User, String> {
+        let email = normalize_email(&invite_data.email);
+        if user_repo::get_user_by_email(&email).await?.is_some() {
+            return Err("Email ini sudah terdaftar.".to_string());
+        }
+        let user = user_repo::create_user(&User {
+            id: Uuid::new_v4(),
+            name: invite_data.name.clone(),
+            email,
+            position: invite_data.position.clone(),
+            deactivated_at: None,
+        })
+        .await?;
+        auth_credential_repo::create_auth_credential(&CreateAuthCredentialDto { user_id: user.id, password_hash: String::new() })
+            .await?;
+        self.send_reset_link(&user, INVITE_TTL_MINUTES, "Undangan akun klinik", |link| {
+            format!(
+                "Halo {}, Anda diundang sebagai {}. Buat password Anda melalui tautan berikut: {}\nTautan berlaku {} jam.",
+                user.name,
+                user.position,
+                link,
+                INVITE_TTL_MINUTES / 60
+            )
+        })
+        .await?;
+        Ok(user)
+    }
+
+    // Clearing the hash makes the old password fail right away
+    async fn force_password_reset(&self, user: &User) -> Result<(), String> {
+        match auth_credential_repo::get_auth_credential(user.id).await? {
+            Some(_) => auth_credential_repo::set_password_hash(user.id, "").await?,
+            None => {
+                auth_credential_repo::create_auth_credential(&CreateAuthCredentialDto { user_id: user.id, password_hash: String::new() })
+                    .await?
+            }
+        }
+        self.send_reset_link(user, RESET_TTL_MINUTES, "Reset password diwajibkan", |link| {
+            format!(
+                "Halo {}, admin meminta Anda membuat password baru. Buka tautan berikut: {}\nTautan berlaku {} menit.",
+                user.name, link, RESET_TTL_MINUTES
+            )
+        })
+        .await
+    }
+
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String> {
+        let invalid = || "Token reset tidak valid atau sudah kedaluwarsa".to_string();
+        let reset = auth_code_repo::get_auth_code_by_hash(PASSWORD_RESET, &self.keyed_hash(token.trim()))
+            .await?
+            .filter(|reset| reset.consumed_at.is_none() && reset.expires_at > Utc::now())
+            .ok_or_else(invalid)?;
+        let password_hash = hash_password(new_password.to_string()).await?;
+        if !auth_code_repo::consume_auth_code(reset.id).await? {
+            return Err(invalid());
+        }
+        auth_credential_repo::set_password_hash(reset.user_id, &password_hash).await?;
+        // Whoever knew the old password is logged out everywhere
+        auth_session_repo::revoke_sessions_by_user(reset.user_id).await?;
+        // Following the emailed link proves the address as well
+        auth_credential_repo::mark_email_verified(reset.user_id).await
+    }
+}
@@ -0,0 +1,158 @@
+use crate::middlewares::rate_limit_middleware::RateLimit;
+use std::collections::HashMap;
+use std::env;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_MAX_FAILURES: u32 = 5;
+const DEFAULT_FAILURE_WINDOW_MINUTES: u64 = 15;
+const DEFAULT_LOCKOUT_MINUTES: u64 = 15;
+// Above this many tracked accounts, stale entries are swept on the next failure
+const SWEEP_THRESHOLD: usize = 10_000;
+
+struct Failures {
+    first: Instant,
+    count: u32,
+    locked_until: Option<Instant>,
+}
+
+// Per-account throttling for the auth endpoints; the RateLimit middleware on those routes
+// covers the per-IP side. Accounts are locked for LOGIN_LOCKOUT_MINUTES after
+// LOGIN_LOCKOUT_MAX_FAILURES failed logins within LOGIN_LOCKOUT_WINDOW_MINUTES, and emails
+// sent on request (reset links, verification codes) are capped per address so the endpoints
+// can't be used to flood a mailbox. Kept in memory like RateLimit, so it is per instance.
+pub struct LoginGuard {
+    login_attempts: RateLimit,
+    emails: RateLimit,
+    max_failures: u32,
+    failure_window: Duration,
+    lockout: Duration,
+    failures: Mutex<HashMap<String, Failures>>,
+}
+
+fn minutes_from_env(key: &str, default: u64) -> Duration {
+    Duration::from_secs(env::var(key).ok().and_then(|v| v.parse().ok()).filter(|m| *m > 0).unwrap_or(default) * 60)
+}
+
+// Accounts are keyed the way users type them, so "Ana@x.id " and "ana@x.id" share a bucket
+pub fn account_key(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+impl LoginGuard {
+    pub fn from_env() -> LoginGuard {
+        LoginGuard {
+            login_attempts: RateLimit::from_env("LOGIN_ACCOUNT", 10, 15 * 60),
+            emails: RateLimit::from_env("EMAIL_ACCOUNT", 3, 60 * 60),
+            max_failures: env::var("LOGIN_LOCKOUT_MAX_FAILURES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|max| *max > 0)
+                .unwrap_or(DEFAULT_MAX_FAILURES),
+            failure_window: minutes_from_env("LOGIN_LOCKOUT_WINDOW_MINUTES", DEFAULT_FAILURE_WINDOW_MINUTES),
+            lockout: minutes_from_env("LOGIN_LOCKOUT_MINUTES", DEFAULT_LOCKOUT_MINUTES),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Seconds until the account may try again, or None if this attempt may go ahead
+    pub fn check_login(&self, account: &str) -> Option<u64> {
+        let locked_until = self
+            .failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(account)
+            .and_then(|f| f.locked_until);
+        if let Some(remaining) = locked_until.and_then(|until| until.checked_duration_since(Instant::now())) {
+            return Some(remaining.as_secs().max(1));
+        }
+        self.login_attempts.check(account)
+    }
+
+    pub fn record_failure(&self, account: &str) {
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        if failures.len() > SWEEP_THRESHOLD {
+            failures.retain(|_, f| {
+                now.duration_since(f.first) < self.failure_window || f.locked_until.is_some_and(|until| until > now)
+            });
+        }
+        let entry = failures.entry(account.to_string()).or_insert(Failures { first: now, count: 0, locked_until: None });
+        // A finished lockout or an old streak starts over
+        if entry.locked_until.is_some_and(|until| until <= now)
+            || (entry.locked_until.is_none() && now.duration_since(entry.first) >= self.failure_window)
+        {
+            *entry = Failures { first: now, count: 0, locked_until: None };
+        }
+        entry.count += 1;
+        if entry.count >= self.max_failures && entry.locked_until.is_none() {
+            entry.locked_until = Some(now + self.lockout);
+            println!("Login locked for {} after {} failed attempts", account, entry.count);
+        }
+    }
+
+    pub fn record_success(&self, account: &str) {
+        self.failures.lock().unwrap_or_else(|e| e.into_inner()).remove(account);
+    }
+
+    // Counts an email the caller is about to send to `email` for `purpose`
+    pub fn check_email(&self, purpose: &str, email: &str) -> Option<u64> {
+        self.emails.check(&format!("{}:{}", purpose, account_key(email)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guard(max_failures: u32, lockout: Duration) -> LoginGuard {
+        LoginGuard {
+            login_attempts: RateLimit::new(100, Duration::from_secs(60)),
+            emails: RateLimit::new(2, Duration::from_secs(60)),
+            max_failures,
+            failure_window: Duration::from_secs(60),
+            lockout,
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+
+    #[test]
+    fn locks_the_account_after_max_failures() {
+        let guard = guard(3, Duration::from_secs(60));
+        for _ in 0..2 {
+            assert_eq!(guard.check_login("ana@x.id"), None);
+            guard.record_failure("ana@x.id");
+        }
+        assert_eq!(guard.check_login("ana@x.id"), None);
+        guard.record_failure("ana@x.id");
+        assert!(guard.check_login("ana@x.id").is_some());
+        assert_eq!(guard.check_login("budi@x.id"), None);
+    }
+
+    #[test]
+    fn success_clears_the_streak() {
+        let guard = guard(2, Duration::from_secs(60));
+        guard.record_failure("ana@x.id");
+        guard.record_success("ana@x.id");
+        guard.record_failure("ana@x.id");
+        assert_eq!(guard.check_login("ana@x.id"), None);
+    }
+
+    #[test]
+    fn lockout_expires() {
+        let guard = guard(1, Duration::from_millis(20));
+        guard.record_failure("ana@x.id");
+        assert!(guard.check_login("ana@x.id").is_some());
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(guard.check_login("ana@x.id"), None);
+    }
+
+    #[test]
+    fn emails_are_capped_per_purpose_and_address() {
+        let guard = guard(5, Duration::from_secs(60));
+        assert_eq!(guard.check_email("reset", "Ana@x.id "), None);
+        assert_eq!(guard.check_email("reset", "ana@x.id"), None);
+        assert!(guard.check_email("reset", "ana@x.id").is_some());
+        assert_eq!(guard.check_email("verify", "ana@x.id"), None);
+    }
+}
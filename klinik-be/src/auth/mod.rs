@@ -0,0 +1,7 @@
+pub mod jwt_verifier;
+pub mod auth_provider;
+pub mod supabase_provider;
+pub mod local_provider;
+pub mod totp;
+pub mod login_guard;
+pub mod stream_tickets;
@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+pub const TICKET_TTL_SECONDS: u64 = 30;
+
+struct Ticket {
+    access_token: String,
+    expires_at: Instant,
+}
+
+// Short-lived, single-use tickets for opening an EventSource stream, which can't send the
+// Authorization header. A ticket stands in for the access token it was issued with, so the
+// token itself never ends up in URLs and access logs. Kept in memory like LoginGuard, so a
+// ticket only works on the instance that issued it.
+pub struct StreamTickets {
+    ttl: Duration,
+    tickets: Mutex<HashMap<String, Ticket>>,
+}
+
+impl StreamTickets {
+    pub fn new() -> StreamTickets {
+        StreamTickets { ttl: Duration::from_secs(TICKET_TTL_SECONDS), tickets: Mutex::new(HashMap::new()) }
+    }
+
+    pub fn issue(&self, access_token: &str) -> String {
+        let now = Instant::now();
+        let ticket = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        let mut tickets = self.tickets.lock().unwrap_or_else(|e| e.into_inner());
+        // Tickets nobody redeemed are dropped here rather than on a timer
+        tickets.retain(|_, t| t.expires_at > now);
+        tickets.insert(ticket.clone(), Ticket { access_token: access_token.to_string(), expires_at: now + self.ttl });
+        ticket
+    }
+
+    // The access token the ticket was issued with. The ticket is used up either way.
+    pub fn redeem(&self, ticket: &str) -> Option<String> {
+        let ticket = self.tickets.lock().unwrap_or_else(|e| e.into_inner()).remove(ticket)?;
+        (ticket.expires_at > Instant::now()).then_some(ticket.access_token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_ticket_works_once() {
+        let tickets = StreamTickets::new();
+        let ticket = tickets.issue("token");
+        assert_eq!(tickets.redeem(&ticket).as_deref(), Some("token"));
+        assert_eq!(tickets.redeem(&ticket), None);
+        assert_eq!(tickets.redeem("unknown"), None);
+    }
+
+    #[test]
+    fn expired_tickets_are_refused() {
+        let tickets = StreamTickets { ttl: Duration::ZERO, tickets: Mutex::new(HashMap::new()) };
+        let ticket = tickets.issue("token");
+        assert_eq!(tickets.redeem(&ticket), None);
+    }
+}
@@ -0,0 +1,70 @@
+use crate::auth::auth_provider::AuthProvider;
+use crate::dtos::auth_dto::SessionTokens;
+use crate::dtos::user_dto::{ForgotPasswordDto, InviteUserDto, LoginUserDto, RegisterUserDto};
+use crate::models::user::User;
+use crate::repositories::user_repo;
+use async_trait::async_trait;
+use uuid::Uuid;
+
+// Supabase Auth (/auth/v1) holds the accounts; confirmation and recovery emails link back
+// to AUTH_VERIFY_REDIRECT_URL and AUTH_RESET_REDIRECT_URL
+pub struct SupabaseAuthProvider;
+
+#[async_trait]
+impl AuthProvider for SupabaseAuthProvider {
+    async fn register(&self, user_data: &RegisterUserDto) -> Result<User, String> {
+        user_repo::register_user(user_data).await
+    }
+
+    async fn login(&self, login_data: &LoginUserDto) -> Result<SessionTokens, String> {
+        user_repo::login_user(login_data).await
+    }
+
+    async fn refresh(&self, session_id: Uuid, _user_id: Uuid, refresh_token: &str) -> Result<SessionTokens, String> {
+        let tokens = user_repo::refresh_session(refresh_token).await?;
+        if tokens.session_id != session_id {
+            return Err("Refresh token does not belong to this session".to_string());
+        }
+        Ok(tokens)
+    }
+
+    async fn logout(&self, access_token: &str) -> Result<(), String> {
+        user_repo::logout_session(access_token).await
+    }
+
+    async fn verify_email(&self, email: &str, code: &str) -> Result<(), String> {
+        user_repo::verify_email_code(email, code).await
+    }
+
+    async fn resend_verification(&self, email: &str) -> Result<(), String> {
+        user_repo::resend_verification_email(email).await
+    }
+
+    async fn send_password_reset(&self, email: &str) -> Result<(), String> {
+        user_repo::send_password_reset_link(&ForgotPasswordDto { email: email.to_string() }).await
+    }
+
+    async fn reset_password(&self, token: &str, new_password: &str) -> Result<(), String> {
+        user_repo::reset_password(token, new_password).await
+    }
+
+    async fn invite(&self, invite_data: &InviteUserDto) -> Result<User, String> {
+        let id = user_repo::invite_auth_user(&invite_data.email).await?;
+        user_repo::create_user(&User {
+            id,
+            name: invite_data.name.clone(),
+            email: invite_data.email.clone(),
+            position: invite_data.position.clone(),
+            deactivated_at: None,
+        })
+        .await
+    }
+
+    // A random password the user never sees replaces the old one, then the recovery email
+    // lets them choose a new one
+    async fn force_password_reset(&self, user: &User) -> Result<(), String> {
+        let password = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        user_repo::set_auth_user_password(user.id, &password).await?;
+        user_repo::send_password_reset_link(&ForgotPasswordDto { email: user.email.clone() }).await
+    }
+}
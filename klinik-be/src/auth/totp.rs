@@ -0,0 +1,142 @@
+use base32::Alphabet;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use uuid::Uuid;
+
+// RFC 6238 with the parameters every authenticator app supports
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+const SECRET_BYTES: usize = 20;
+// Codes from one step before or after are accepted to absorb clock drift
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const BASE32: Alphabet = Alphabet::Rfc4648 { padding: false };
+
+// A new shared secret, base32-encoded as authenticator apps expect
+pub fn generate_secret() -> String {
+    let bytes: Vec<u8> = [Uuid::new_v4(), Uuid::new_v4()]
+        .iter()
+        .flat_map(|uuid| uuid.as_bytes().to_vec())
+        .take(SECRET_BYTES)
+        .collect();
+    base32::encode(BASE32, &bytes)
+}
+
+// RFC 3986 unreserved characters pass through, everything else is percent-encoded
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+// The otpauth:// URI shown as a QR code during enrolment
+pub fn provisioning_uri(secret: &str, issuer: &str, account: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account),
+        secret,
+        percent_encode(issuer),
+        DIGITS,
+        STEP_SECONDS
+    )
+}
+
+fn code_at(key: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(key).expect("HMAC accepts any key length");
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([hash[offset] & 0x7f, hash[offset + 1], hash[offset + 2], hash[offset + 3]]);
+    binary % 10u32.pow(DIGITS)
+}
+
+// The time step `code` belongs to, if it is valid around `unix_time`. Callers store the step
+// and refuse it (or an earlier one) next time, so a code can't be replayed.
+pub fn verify(secret: &str, code: &str, unix_time: i64) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let expected: u32 = code.parse().ok()?;
+    let key = base32::decode(BASE32, secret)?;
+    let current = unix_time / STEP_SECONDS;
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS).find(|step| code_at(&key, *step) == expected)
+}
+
+// `verify`, refusing the step stored from the last accepted code and anything before it
+pub fn verify_unused(secret: &str, code: &str, unix_time: i64, last_used_step: Option<i64>) -> Option<i64> {
+    verify(secret, code, unix_time).filter(|step| last_used_step.is_none_or(|last| *step > last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC 6238 Appendix B, SHA-1: the ASCII secret "12345678901234567890"
+    const RFC_KEY: &[u8] = b"12345678901234567890";
+
+    fn rfc_secret() -> String {
+        base32::encode(BASE32, RFC_KEY)
+    }
+
+    #[test]
+    fn matches_the_rfc_6238_sha1_vectors() {
+        // The RFC lists 8-digit codes; 6-digit codes are their last six digits
+        let vectors = [
+            (59, 287082),
+            (1111111109, 81804),
+            (1111111111, 50471),
+            (1234567890, 5924),
+            (2000000000, 279037),
+            (20000000000, 353130),
+        ];
+        for (unix_time, expected) in vectors {
+            assert_eq!(code_at(RFC_KEY, unix_time / STEP_SECONDS), expected, "T = {}", unix_time);
+        }
+        assert_eq!(rfc_secret(), "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ");
+        assert_eq!(verify(&rfc_secret(), "287082", 59), Some(1));
+        assert_eq!(verify(&rfc_secret(), " 081804 ", 1111111109), Some(37037036));
+    }
+
+    #[test]
+    fn accepts_one_step_of_drift_either_way() {
+        let secret = rfc_secret();
+        assert_eq!(verify(&secret, "287082", 59 + STEP_SECONDS), Some(1));
+        assert_eq!(verify(&secret, "287082", 59 - STEP_SECONDS), Some(1));
+        assert_eq!(verify(&secret, "287082", 59 + 2 * STEP_SECONDS), None);
+        assert_eq!(verify(&secret, "287082", 0 - STEP_SECONDS), None);
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        let secret = rfc_secret();
+        assert_eq!(verify(&secret, "287083", 59), None);
+        assert_eq!(verify(&secret, "28708", 59), None);
+        assert_eq!(verify(&secret, "94287082", 59), None);
+        assert_eq!(verify(&secret, "28708a", 59), None);
+        assert_eq!(verify("not base32!", "287082", 59), None);
+    }
+
+    #[test]
+    fn refuses_a_step_that_was_already_used() {
+        let secret = rfc_secret();
+        assert_eq!(verify_unused(&secret, "287082", 59, None), Some(1));
+        assert_eq!(verify_unused(&secret, "287082", 59, Some(0)), Some(1));
+        assert_eq!(verify_unused(&secret, "287082", 59 + STEP_SECONDS, Some(1)), None);
+        assert_eq!(verify_unused(&secret, "287082", 59, Some(2)), None);
+    }
+
+    #[test]
+    fn provisioning_uri_encodes_issuer_and_account() {
+        let uri = provisioning_uri("ABC", "Klinik Ayu", "ana+1@x.id");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Klinik%20Ayu:ana%2B1%40x.id?secret=ABC&issuer=Klinik%20Ayu&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
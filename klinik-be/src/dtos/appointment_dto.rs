@@ -13,9 +13,22 @@ pub struct CreateAppointmentDto {
     pub is_initial_skin_analysis: Option<bool>,
     pub skin_analysis_id: Option<Uuid>,
     pub treatment_progress_id: Option<Uuid>,
+    // Allocated by the server, never taken from the request
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub resource_ids: Option<Vec<Uuid>>,
+    // Set when the appointment is created as part of a series
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<Uuid>,
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub series_index: Option<i32>,
+    // Not columns: the caller's acknowledgement of safety warnings for this request
+    #[serde(default, skip_serializing)]
+    pub acknowledge_warnings: bool,
+    #[serde(default, skip_serializing)]
+    pub safety_override_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct UpdateAppointmentDto {
     pub pasien_id: Option<Uuid>,
     pub dokter_id: Option<Uuid>,
@@ -26,4 +39,13 @@ pub struct UpdateAppointmentDto {
     pub is_initial_skin_analysis: Option<bool>,
     pub skin_analysis_id: Option<Uuid>,
     pub treatment_progress_id: Option<Uuid>,
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub resource_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub needs_reschedule: Option<bool>,
+    // Not columns: the caller's acknowledgement of safety warnings for this request
+    #[serde(default, skip_serializing)]
+    pub acknowledge_warnings: bool,
+    #[serde(default, skip_serializing)]
+    pub safety_override_reason: Option<String>,
 }
\ No newline at end of file
@@ -0,0 +1,68 @@
+use crate::models::appointment::Appointment;
+use crate::models::appointment_series::AppointmentSeries;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateAppointmentSeriesDto {
+    pub pasien_id: Uuid,
+    pub dokter_id: Uuid,
+    pub treatment_ids: Vec<Uuid>,
+    pub start_tanggal: String,
+    pub waktu: String,
+    pub interval_days: i32, // e.g. 14 for every 2 weeks
+    pub occurrences: i32,
+    pub notes: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_by: String,
+    // Not columns: book the free occurrences and report the rest instead of booking nothing
+    #[serde(default, skip_serializing)]
+    pub skip_conflicts: bool,
+    #[serde(default, skip_serializing)]
+    pub acknowledge_warnings: bool,
+    #[serde(default, skip_serializing)]
+    pub safety_override_reason: Option<String>,
+}
+
+// Changes applied to one occurrence or to it and every later one
+#[derive(Debug, Deserialize)]
+pub struct UpdateSeriesOccurrencesDto {
+    pub dokter_id: Option<Uuid>,
+    pub waktu: Option<String>,
+    pub treatment_ids: Option<Vec<Uuid>>,
+    pub shift_days: Option<i64>, // moves each occurrence by this many days
+    #[serde(default)]
+    pub skip_conflicts: bool,
+    #[serde(default)]
+    pub acknowledge_warnings: bool,
+    pub safety_override_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesScopeQueryDto {
+    pub scope: Option<String>, // "this" (default) or "following"
+}
+
+#[derive(Debug, Serialize)]
+pub struct OccurrenceResultDto {
+    pub series_index: i32,
+    pub tanggal: String,
+    pub waktu: String,
+    pub appointment_id: Option<Uuid>,
+    pub conflict: Option<String>, // why this occurrence was not booked or changed
+}
+
+// `applied` is false when conflicts stopped the whole request; nothing was written then
+#[derive(Debug, Serialize)]
+pub struct SeriesResultDto {
+    pub series: Option<AppointmentSeries>,
+    pub applied: bool,
+    pub occurrences: Vec<OccurrenceResultDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SeriesDetailDto {
+    #[serde(flatten)]
+    pub series: AppointmentSeries,
+    pub appointments: Vec<Appointment>,
+}
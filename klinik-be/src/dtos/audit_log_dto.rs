@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateAuditLogDto {
+    pub actor_id: Option<String>,
+    pub actor_position: Option<String>,
+    pub resource: String,
+    pub record_id: Uuid,
+    pub action: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditLogQueryDto {
+    pub resource: Option<String>,
+    pub record_id: Option<Uuid>,
+    pub actor_id: Option<Uuid>,
+    pub limit: Option<u32>,
+}
+
+// One audit entry without its snapshots, for working out when a record changed
+#[derive(Debug, Deserialize)]
+pub struct AuditChangeDto {
+    pub record_id: Uuid,
+    pub action: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use crate::dtos::totp_dto::MfaChallengeDto;
+use crate::models::auth_session::AuthSession;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct CreateAuthCredentialDto {
+    pub user_id: Uuid,
+    pub password_hash: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateAuthCodeDto {
+    pub user_id: Uuid,
+    pub purpose: String,
+    pub code_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateAuthSessionDto {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub refresh_token_hash: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+// What an auth provider hands back after a login or refresh
+pub struct SessionTokens {
+    pub user_id: Uuid,
+    pub session_id: Uuid,
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64, // seconds until the access token expires
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenPairDto {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub session_id: Uuid,
+}
+
+// What /login answers: tokens, or a challenge for the second factor
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum LoginResultDto {
+    Tokens(TokenPairDto),
+    MfaRequired(MfaChallengeDto),
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RefreshTokenDto {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionQueryDto {
+    pub user_id: Option<Uuid>, // admin only; defaults to the caller
+}
+
+#[derive(Debug, Serialize)]
+pub struct SessionDto {
+    #[serde(flatten)]
+    pub session: AuthSession,
+    pub current: bool,
+}
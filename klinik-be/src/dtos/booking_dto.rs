@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct RequestOtpDto {
+    pub no_telepon: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyOtpDto {
+    pub no_telepon: String,
+    pub code: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateBookingOtpDto {
+    pub no_telepon: String,
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Returned after a verified OTP; `token` authorizes bookings for that phone number
+#[derive(Debug, Serialize)]
+pub struct BookingSessionDto {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_registered: bool, // false: the booking must include `pasien` to register
+}
+
+// The minimal CreatePasienDto fields a new patient fills in online
+#[derive(Debug, Deserialize)]
+pub struct PublicPasienDto {
+    pub nama_lengkap: String,
+    pub email: Option<String>,
+    pub tanggal_lahir: Option<String>,
+    pub jenis_kelamin: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreatePublicBookingDto {
+    pub dokter_id: Uuid,
+    pub treatment_ids: Vec<Uuid>,
+    pub tanggal: String,
+    pub waktu: String,
+    pub pasien: Option<PublicPasienDto>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RescheduleBookingDto {
+    pub tanggal: String,
+    pub waktu: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SlotQueryDto {
+    pub tanggal: String,
+    pub treatment_ids: Option<String>, // comma separated
+    pub dokter_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AvailableSlotDto {
+    pub dokter_id: Uuid,
+    pub dokter_nama: String,
+    pub waktu: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PublicTreatmentDto {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub price: f64,
+    pub estimated_time: i32,
+}
+
+// What a patient sees of their own booking
+#[derive(Debug, Serialize)]
+pub struct PublicBookingDto {
+    pub appointment_id: Uuid,
+    pub status: String,
+    pub tanggal: String,
+    pub waktu: String,
+    pub dokter_nama: String,
+    pub treatments: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BookingCreatedDto {
+    #[serde(flatten)]
+    pub booking: PublicBookingDto,
+    pub manage_token: String,
+    pub manage_url: Option<String>, // set when PUBLIC_BOOKING_URL is configured
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateBookingLinkDto {
+    pub appointment_id: Uuid,
+    pub token_hash: String,
+}
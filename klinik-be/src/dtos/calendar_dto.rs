@@ -0,0 +1,18 @@
+use serde::Serialize;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct CreateCalendarFeedDto {
+    pub dokter_id: Uuid,
+    pub token_hash: String,
+    pub created_by: String,
+}
+
+// Shown once when the feed is created; the token can't be recovered afterwards
+#[derive(Debug, Serialize)]
+pub struct CalendarFeedCreatedDto {
+    pub id: Uuid,
+    pub dokter_id: Uuid,
+    pub token: String,
+    pub url: String,
+}
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+// Query string shared by list endpoints of soft-deletable resources
+#[derive(Debug, Deserialize)]
+pub struct ListQueryDto {
+    pub include_deleted: Option<bool>,
+}
@@ -0,0 +1,63 @@
+use crate::models::consent_signature::ConsentSignature;
+use crate::models::consent_template::ConsentTemplate;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateConsentTemplateDto {
+    pub code: String,
+    pub title: String,
+    pub body: String,
+    pub treatment_ids: Vec<Uuid>,
+}
+
+// Publishes a new version of a template; omitted fields are carried over
+#[derive(Debug, Deserialize)]
+pub struct CreateConsentTemplateVersionDto {
+    pub title: Option<String>,
+    pub body: Option<String>,
+    pub treatment_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConsentTemplateRecordDto {
+    pub code: String,
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub treatment_ids: Vec<Uuid>,
+    pub is_active: bool,
+    pub created_by: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SignConsentDto {
+    pub template_id: Uuid,
+    pub signer_name: String,
+    pub signer_relation: Option<String>,
+    pub signature_image: Option<String>, // base64 PNG/JPEG, a `data:` URL is accepted
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateConsentSignatureDto {
+    pub id: Uuid,
+    pub appointment_id: Uuid,
+    pub pasien_id: Uuid,
+    pub template_id: Uuid,
+    pub template_code: String,
+    pub template_version: i32,
+    pub signer_name: String,
+    pub signer_relation: Option<String>,
+    pub signature_method: String,
+    pub signature_image_key: Option<String>,
+    pub witnessed_by: String,
+    pub signed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AppointmentConsentStatusDto {
+    pub required: Vec<ConsentTemplate>,
+    pub signatures: Vec<ConsentSignature>,
+    pub missing: Vec<ConsentTemplate>,
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQueryDto {
+    pub topics: Option<String>, // comma separated, e.g. "appointments,invoices"; all topics when omitted
+    // EventSource can't set headers, so browsers open the stream with a ticket from
+    // POST /api/events/ticket instead
+    pub ticket: Option<String>,
+}
@@ -15,6 +15,11 @@ pub struct CreateInvoiceDto {
     pub status: Option<String>,
     pub kasir_name: Option<String>,
     pub appointment_id: Option<Uuid>,
+    // Not columns: the caller's acknowledgement of safety warnings for this request
+    #[serde(default, skip_serializing)]
+    pub acknowledge_warnings: bool,
+    #[serde(default, skip_serializing)]
+    pub safety_override_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -30,4 +35,9 @@ pub struct UpdateInvoiceDto {
     pub status: Option<String>,
     pub kasir_name: Option<String>,
     pub appointment_id: Option<Uuid>,
+    // Not columns: the caller's acknowledgement of safety warnings for this request
+    #[serde(default, skip_serializing)]
+    pub acknowledge_warnings: bool,
+    #[serde(default, skip_serializing)]
+    pub safety_override_reason: Option<String>,
 }
\ No newline at end of file
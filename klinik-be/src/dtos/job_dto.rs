@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize)]
+pub struct CreateJobDto {
+    pub name: String,
+    pub schedule: String,
+    pub is_enabled: bool,
+    pub next_run_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateJobDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_enabled: Option<bool>,
+    // Set by the server when the schedule changes or a run is triggered
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateJobRunDto {
+    pub job_name: String,
+    pub attempt: i32,
+    pub status: String,
+    pub started_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FinishJobRunDto {
+    pub status: String,
+    pub finished_at: DateTime<Utc>,
+    pub error: Option<String>,
+    pub output: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobRunQueryDto {
+    pub limit: Option<u32>,
+}
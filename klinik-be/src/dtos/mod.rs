@@ -6,4 +6,25 @@ pub mod pasien_dto;
 pub mod appointment_dto;
 pub mod treatment_progress_dto;
 pub mod skin_analysis_dto;
-pub mod invoice_dto;
\ No newline at end of file
+pub mod invoice_dto;
+pub mod common_dto;
+pub mod audit_log_dto;
+pub mod timeline_dto;
+pub mod photo_dto;
+pub mod recommendation_rule_dto;
+pub mod recommendation_suggestion_dto;
+pub mod safety_dto;
+pub mod consent_dto;
+pub mod notification_dto;
+pub mod job_dto;
+pub mod report_dto;
+pub mod event_dto;
+pub mod schedule_dto;
+pub mod clinic_resource_dto;
+pub mod queue_dto;
+pub mod booking_dto;
+pub mod waitlist_dto;
+pub mod appointment_series_dto;
+pub mod calendar_dto;
+pub mod auth_dto;
+pub mod totp_dto;
\ No newline at end of file
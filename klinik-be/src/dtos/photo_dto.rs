@@ -0,0 +1,24 @@
+use crate::models::photo::Photo;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreatePhotoDto {
+    pub id: Uuid, // generated up front because the storage keys are derived from it
+    pub pasien_id: Uuid,
+    pub skin_analysis_id: Option<Uuid>,
+    pub treatment_progress_id: Option<Uuid>,
+    pub tanggal: String,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub uploaded_by: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BeforeAfterPhotosDto {
+    pub before: Option<Photo>,
+    pub after: Option<Photo>,
+}
@@ -6,6 +6,12 @@ pub struct CreateProductDto {
     pub description: String,
     pub price: f64,
     pub stock: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredients: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
 }
 
 // DTO untuk update, semua field opsional
@@ -15,4 +21,10 @@ pub struct UpdateProductDto {
     pub description: Option<String>,
     pub price: Option<f64>,
     pub stock: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expiry_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredients: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
 }
\ No newline at end of file
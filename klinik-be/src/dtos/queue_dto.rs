@@ -0,0 +1,67 @@
+use crate::models::queue_entry::QueueEntry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// Check-in at the front desk. A booked patient passes appointment_id, and the doctor and
+// treatments default to the booking's; otherwise the least busy doctor on duty is assigned.
+#[derive(Debug, Deserialize)]
+pub struct CheckInDto {
+    pub pasien_id: Uuid,
+    pub appointment_id: Option<Uuid>,
+    pub dokter_id: Option<Uuid>,
+    pub treatment_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateQueueEntryDto {
+    pub tanggal: String,
+    pub queue_number: i32,
+    pub pasien_id: Uuid,
+    pub dokter_id: Uuid,
+    pub appointment_id: Option<Uuid>,
+    pub treatment_ids: Vec<Uuid>,
+    pub status: String,
+    pub checked_in_at: DateTime<Utc>,
+    pub created_by: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateQueueStatusDto {
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueStatusChangeDto {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub called_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQueryDto {
+    pub tanggal: Option<String>, // defaults to today
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QueueDisplayQueryDto {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueEntryViewDto {
+    #[serde(flatten)]
+    pub entry: QueueEntry,
+    pub estimated_wait_minutes: Option<i64>, // only for entries still waiting
+}
+
+// What the waiting-room screen shows: no patient names or medical details
+#[derive(Debug, Serialize)]
+pub struct QueueDisplayEntryDto {
+    pub queue_number: i32,
+    pub dokter_nama: String,
+    pub status: String,
+    pub estimated_wait_minutes: Option<i64>,
+}
@@ -0,0 +1,40 @@
+use crate::models::recommendation_rule::RuleCondition;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateRecommendationRuleDto {
+    pub name: String,
+    pub conditions: Vec<RuleCondition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub treatment_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_ids: Option<Vec<Uuid>>,
+    pub rationale: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateRecommendationRuleDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<RuleCondition>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub treatment_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_ids: Option<Vec<Uuid>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rationale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_active: Option<bool>,
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateRecommendationSuggestionDto {
+    pub skin_analysis_id: Uuid,
+    pub pasien_id: Uuid,
+    pub rule_ids: Vec<Uuid>,
+    pub item_type: String,
+    pub item_id: Uuid,
+    pub rationale: String,
+    pub status: String,
+}
+
+// The doctor's decision on the pending suggestions of one analysis. Suggestions not
+// listed in `accepted_suggestion_ids` are rejected. Rejecting a suggestion or adding
+// items of one's own is an override and needs `override_reason`.
+#[derive(Debug, Deserialize)]
+pub struct RecommendationDecisionDto {
+    pub accepted_suggestion_ids: Vec<Uuid>,
+    pub additional_treatment_ids: Option<Vec<Uuid>>,
+    pub additional_product_ids: Option<Vec<Uuid>>,
+    pub override_reason: Option<String>,
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Serialize)]
+pub struct CreateReportSnapshotDto {
+    pub report_date: String,
+    pub metrics: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReportQueryDto {
+    pub from: Option<String>, // YYYY-MM-DD, inclusive
+    pub to: Option<String>,
+}
@@ -0,0 +1,21 @@
+use crate::models::safety_override::SafetyFinding;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct SafetyCheckDto {
+    pub pasien_id: Uuid,
+    pub treatment_ids: Option<Vec<Uuid>>,
+    pub product_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateSafetyOverrideDto {
+    pub pasien_id: Uuid,
+    pub resource: String,
+    pub record_id: Uuid,
+    pub findings: Vec<SafetyFinding>,
+    pub reason: String,
+    pub overridden_by: String,
+    pub overridden_by_position: String,
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateScheduleExceptionDto {
+    #[serde(skip_deserializing)]
+    pub dokter_id: Uuid, // taken from the path
+    pub tanggal: String,
+    pub kind: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub reason: Option<String>,
+    #[serde(skip_deserializing)]
+    pub created_by: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateClinicHolidayDto {
+    pub tanggal: String,
+    pub nama: String,
+    #[serde(skip_deserializing)]
+    pub created_by: String,
+}
+
+// Date range filter (YYYY-MM-DD, inclusive) for schedule exceptions and holidays
+#[derive(Debug, Deserialize)]
+pub struct ScheduleQueryDto {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub include_deleted: Option<bool>,
+}
+
+// A newly blocked date together with the appointments it flagged for rescheduling
+#[derive(Debug, Serialize)]
+pub struct BlockedDateResultDto<T: Serialize> {
+    #[serde(flatten)]
+    pub record: T,
+    pub flagged_appointment_ids: Vec<Uuid>,
+}
@@ -1,3 +1,4 @@
+use crate::models::skin_analysis::{DeviceMeasurement, SkinScores, SkinType};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
@@ -9,6 +10,10 @@ pub struct CreateSkinAnalysisDto {
     pub tanggal_analisis: String, // Consider using chrono::NaiveDate
     pub hasil_visual: Option<String>,
     pub hasil_alat: Option<String>,
+    pub skin_type: Option<SkinType>,
+    pub fitzpatrick_type: Option<i16>, // 1 (I) sampai 6 (VI)
+    pub scores: Option<SkinScores>,
+    pub device_readings: Option<Vec<DeviceMeasurement>>,
     pub rekomendasi_treatment: Option<Value>, // JSON array of strings
     pub rekomendasi_produk: Option<Value>, // JSON array of strings
     pub catatan_tambahan: Option<String>,
@@ -21,7 +26,33 @@ pub struct UpdateSkinAnalysisDto {
     pub tanggal_analisis: Option<String>,
     pub hasil_visual: Option<String>,
     pub hasil_alat: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skin_type: Option<SkinType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fitzpatrick_type: Option<i16>,
+    // Merged into the stored scores: metrics left out keep their value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scores: Option<SkinScores>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_readings: Option<Vec<DeviceMeasurement>>,
     pub rekomendasi_treatment: Option<Value>,
     pub rekomendasi_produk: Option<Value>,
     pub catatan_tambahan: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkinMetricPointDto {
+    pub skin_analysis_id: Uuid,
+    pub tanggal_analisis: String,
+    pub value: f64,
+}
+
+// One metric's values across a patient's analyses, oldest first
+#[derive(Debug, Serialize)]
+pub struct SkinMetricTrendDto {
+    pub metric: String,
+    pub unit: Option<String>,
+    pub higher_is_better: Option<bool>,
+    pub points: Vec<SkinMetricPointDto>,
+    pub change: Option<f64>, // latest minus first
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct TimelineQueryDto {
+    pub from: Option<String>, // YYYY-MM-DD, inclusive
+    pub to: Option<String>,   // YYYY-MM-DD, inclusive
+    pub types: Option<String>, // comma separated, e.g. "appointment,invoice"
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineEventKind {
+    Appointment,
+    SkinAnalysis,
+    TreatmentProgress,
+    Invoice,
+}
+
+impl TimelineEventKind {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value.trim() {
+            "appointment" => Ok(Self::Appointment),
+            "skin_analysis" => Ok(Self::SkinAnalysis),
+            "treatment_progress" => Ok(Self::TreatmentProgress),
+            "invoice" => Ok(Self::Invoice),
+            other => Err(format!("Unknown timeline event type: {}", other)),
+        }
+    }
+}
+
+// IDs of the source record and the records it is connected to
+#[derive(Debug, Serialize, Default)]
+pub struct TimelineLinks {
+    pub appointment_id: Option<Uuid>,
+    pub skin_analysis_id: Option<Uuid>,
+    pub treatment_progress_id: Option<Uuid>,
+    pub invoice_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimelineEvent {
+    pub kind: TimelineEventKind,
+    pub source_id: Uuid,
+    pub tanggal: String,
+    pub waktu: Option<String>,
+    pub summary: String,
+    pub links: TimelineLinks,
+    pub data: Value,
+}
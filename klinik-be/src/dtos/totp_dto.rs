@@ -0,0 +1,70 @@
+use crate::dtos::auth_dto::TokenPairDto;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct CreateUserTotpDto {
+    pub user_id: Uuid,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateTotpRecoveryCodeDto {
+    pub user_id: Uuid,
+    pub code_hash: String,
+}
+
+// Shown once when enrolment starts; the app is set up from `provisioning_uri` (as a QR code)
+// or by typing `secret`
+#[derive(Debug, Serialize)]
+pub struct TotpEnrolmentDto {
+    pub secret: String,
+    pub provisioning_uri: String,
+}
+
+// Shown once; only hashes are kept
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TotpCodeDto {
+    pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaTokenDto {
+    pub mfa_token: String,
+}
+
+// Second login step: a code from the app, or one of the recovery codes
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginDto {
+    pub mfa_token: String,
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MfaEnrolmentConfirmDto {
+    pub mfa_token: String,
+    pub code: String,
+}
+
+// Returned by /login instead of tokens when a second step is needed. With
+// `enrolment_required` the account must set up 2FA first (/login/mfa/enroll).
+#[derive(Debug, Serialize)]
+pub struct MfaChallengeDto {
+    pub mfa_required: bool,
+    pub enrolment_required: bool,
+    pub mfa_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MfaEnrolmentCompletedDto {
+    #[serde(flatten)]
+    pub tokens: TokenPairDto,
+    pub recovery_codes: Vec<String>,
+}
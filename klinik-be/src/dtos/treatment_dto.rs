@@ -6,6 +6,12 @@ pub struct CreateTreatmentDto {
     pub description: String,
     pub price: f64,
     pub estimated_time: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredients: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_resources: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -14,4 +20,10 @@ pub struct UpdateTreatmentDto {
     pub description: Option<String>,
     pub price: Option<f64>,
     pub estimated_time: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingredients: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contraindications: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required_resources: Option<Vec<String>>,
 }
\ No newline at end of file
@@ -24,8 +24,33 @@ pub struct ResetPasswordDto {
     pub password: String
 }
 
-#[derive(Deserialize)]
-pub struct UpdatePasswordDto {
-    pub access_token: String,
-    pub password: String,
-}
\ No newline at end of file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct VerifyEmailDto {
+    pub email: String,
+    pub code: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResendVerificationDto {
+    pub email: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InviteUserDto {
+    pub name: String,
+    pub email: String,
+    pub position: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateUserDto {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UserQueryDto {
+    pub include_deactivated: Option<bool>,
+}
@@ -0,0 +1,56 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWaitlistEntryDto {
+    pub pasien_id: Uuid,
+    pub dokter_id: Option<Uuid>,
+    pub treatment_ids: Vec<Uuid>,
+    pub date_from: String,
+    pub date_to: String,
+    pub notes: Option<String>,
+    #[serde(skip_deserializing)]
+    pub status: String,
+    #[serde(skip_deserializing, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+}
+
+// Joining the waitlist online, with a booking session from /public/otp/verify
+#[derive(Debug, Deserialize)]
+pub struct JoinWaitlistDto {
+    pub dokter_id: Option<Uuid>,
+    pub treatment_ids: Vec<Uuid>,
+    pub date_from: String,
+    pub date_to: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WaitlistQueryDto {
+    pub status: Option<String>,
+    pub dokter_id: Option<Uuid>,
+    pub include_deleted: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateWaitlistOfferDto {
+    pub waitlist_entry_id: Uuid,
+    pub appointment_id: Uuid,
+    pub dokter_id: Uuid,
+    pub tanggal: String,
+    pub waktu: String,
+    pub status: String,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+// What the patient sees behind the offer link
+#[derive(Debug, Serialize)]
+pub struct PublicWaitlistOfferDto {
+    pub status: String,
+    pub tanggal: String,
+    pub waktu: String,
+    pub dokter_nama: String,
+    pub treatments: Vec<String>,
+    pub expires_at: DateTime<Utc>,
+}
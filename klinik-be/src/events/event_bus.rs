@@ -0,0 +1,79 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::env;
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+const DEFAULT_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    Appointments,
+    Invoices,
+    Products,
+    Queue,
+}
+
+impl Topic {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Topic::Appointments => "appointments",
+            Topic::Invoices => "invoices",
+            Topic::Products => "products",
+            Topic::Queue => "queue",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Topic> {
+        match value.trim().to_lowercase().as_str() {
+            "appointments" => Some(Topic::Appointments),
+            "invoices" => Some(Topic::Invoices),
+            "products" => Some(Topic::Products),
+            "queue" => Some(Topic::Queue),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainEvent {
+    pub id: Uuid,
+    pub topic: &'static str,
+    pub event_type: String, // e.g. "appointment.booked", "invoice.paid"
+    pub record_id: Uuid,
+    pub payload: Value,
+    pub occurred_at: DateTime<Utc>,
+}
+
+// In-process fan-out: every open stream holds a receiver. Events are not persisted, so a
+// client that reconnects should reload its lists rather than expect a replay.
+fn sender() -> &'static broadcast::Sender<DomainEvent> {
+    static SENDER: OnceLock<broadcast::Sender<DomainEvent>> = OnceLock::new();
+    SENDER.get_or_init(|| {
+        let capacity = env::var("EVENT_BUS_CAPACITY")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|capacity| *capacity > 0)
+            .unwrap_or(DEFAULT_CAPACITY);
+        broadcast::channel(capacity).0
+    })
+}
+
+// Publishing never fails the mutation that caused it; with no subscribers the event is dropped
+pub fn publish<T: Serialize>(topic: Topic, event_type: &str, record_id: Uuid, payload: &T) {
+    let event = DomainEvent {
+        id: Uuid::new_v4(),
+        topic: topic.as_str(),
+        event_type: event_type.to_string(),
+        record_id,
+        payload: serde_json::to_value(payload).unwrap_or(Value::Null),
+        occurred_at: Utc::now(),
+    };
+    let _ = sender().send(event);
+}
+
+pub fn subscribe() -> broadcast::Receiver<DomainEvent> {
+    sender().subscribe()
+}
@@ -1,37 +1,67 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::appointment_dto::{CreateAppointmentDto, UpdateAppointmentDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::appointment_service;
 use uuid::Uuid;
 
-pub async fn get_all_appointments_handler() -> HttpResponse {
-    match appointment_service::handle_get_all_appointments().await {
+pub async fn get_all_appointments_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match appointment_service::handle_get_all_appointments(include_deleted).await {
         Ok(appointments) => HttpResponse::Ok().json(appointments),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_appointment_handler(appointment_data: web::Json<CreateAppointmentDto>) -> HttpResponse {
-    match appointment_service::handle_create_appointment(appointment_data.into_inner()).await {
+pub async fn create_appointment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    appointment_data: web::Json<CreateAppointmentDto>,
+) -> HttpResponse {
+    match appointment_service::handle_create_appointment(appointment_data.into_inner(), &auth_user).await {
         Ok(appointment) => HttpResponse::Created().json(appointment),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_appointment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     appointment_data: web::Json<UpdateAppointmentDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match appointment_service::handle_update_appointment(id, appointment_data.into_inner()).await {
+    match appointment_service::handle_update_appointment(id, appointment_data.into_inner(), &auth_user).await {
         Ok(appointment) => HttpResponse::Ok().json(appointment),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_appointment_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_appointment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match appointment_service::handle_delete_appointment(id).await {
+    match appointment_service::handle_delete_appointment(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_appointment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match appointment_service::handle_restore_appointment(id, &auth_user).await {
+        Ok(appointment) => HttpResponse::Ok().json(appointment),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::appointment_series_dto::{CreateAppointmentSeriesDto, SeriesScopeQueryDto, UpdateSeriesOccurrencesDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::appointment_series_service;
+use uuid::Uuid;
+
+pub async fn get_appointment_series_handler(path: web::Path<Uuid>) -> HttpResponse {
+    match appointment_series_service::handle_get_appointment_series(path.into_inner()).await {
+        Ok(series) => HttpResponse::Ok().json(series),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
+
+// 409 with the per-occurrence report when conflicts stopped the request
+pub async fn create_appointment_series_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    series_data: web::Json<CreateAppointmentSeriesDto>,
+) -> HttpResponse {
+    match appointment_series_service::handle_create_appointment_series(series_data.into_inner(), &auth_user).await {
+        Ok(result) if result.applied => HttpResponse::Created().json(result),
+        Ok(result) => HttpResponse::Conflict().json(result),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn update_series_occurrences_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<(Uuid, i32)>,
+    query: web::Query<SeriesScopeQueryDto>,
+    update_data: web::Json<UpdateSeriesOccurrencesDto>,
+) -> HttpResponse {
+    let (series_id, series_index) = path.into_inner();
+    match appointment_series_service::handle_update_series_occurrences(
+        series_id,
+        series_index,
+        query.scope.as_deref(),
+        update_data.into_inner(),
+        &auth_user,
+    )
+    .await
+    {
+        Ok(result) if result.applied => HttpResponse::Ok().json(result),
+        Ok(result) => HttpResponse::Conflict().json(result),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn cancel_series_occurrences_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<(Uuid, i32)>,
+    query: web::Query<SeriesScopeQueryDto>,
+) -> HttpResponse {
+    let (series_id, series_index) = path.into_inner();
+    match appointment_series_service::handle_cancel_series_occurrences(series_id, series_index, query.scope.as_deref(), &auth_user).await {
+        Ok(result) => HttpResponse::Ok().json(result),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
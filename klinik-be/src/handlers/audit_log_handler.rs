@@ -0,0 +1,17 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::audit_log_dto::AuditLogQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::audit_service;
+
+pub async fn get_audit_logs_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<AuditLogQueryDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat audit log.");
+    }
+    match audit_service::handle_get_audit_logs(query.into_inner()).await {
+        Ok(logs) => HttpResponse::Ok().json(logs),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
@@ -0,0 +1,82 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::dtos::booking_dto::{CreatePublicBookingDto, RequestOtpDto, RescheduleBookingDto, SlotQueryDto, VerifyOtpDto};
+use crate::notifications::notifier::Notifiers;
+use crate::services::booking_service;
+
+// Public self-booking: none of these routes use AuthMiddleware. Creating a booking needs
+// the session token from /otp/verify; managing one needs the token from its confirmation.
+
+pub async fn get_public_treatments_handler() -> HttpResponse {
+    match booking_service::handle_get_public_treatments().await {
+        Ok(treatments) => HttpResponse::Ok().json(treatments),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn get_available_slots_handler(query: web::Query<SlotQueryDto>) -> HttpResponse {
+    match booking_service::handle_get_available_slots(query.into_inner()).await {
+        Ok(slots) => HttpResponse::Ok().json(slots),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn request_otp_handler(
+    notifiers: web::Data<Notifiers>,
+    otp_data: web::Json<RequestOtpDto>,
+) -> HttpResponse {
+    match booking_service::handle_request_otp(otp_data.into_inner(), &notifiers).await {
+        Ok(()) => HttpResponse::Accepted().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn verify_otp_handler(verify_data: web::Json<VerifyOtpDto>) -> HttpResponse {
+    match booking_service::handle_verify_otp(verify_data.into_inner()).await {
+        Ok(session) => HttpResponse::Ok().json(session),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn create_booking_handler(
+    req: HttpRequest,
+    notifiers: web::Data<Notifiers>,
+    booking_data: web::Json<CreatePublicBookingDto>,
+) -> HttpResponse {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "));
+    let phone = match token.map(booking_service::verify_session) {
+        Some(Ok(phone)) => phone,
+        _ => return HttpResponse::Unauthorized().body("Verifikasi nomor telepon diperlukan"),
+    };
+    match booking_service::handle_create_booking(&phone, booking_data.into_inner(), &notifiers).await {
+        Ok(booking) => HttpResponse::Created().json(booking),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_booking_handler(path: web::Path<String>) -> HttpResponse {
+    match booking_service::handle_get_booking(&path.into_inner()).await {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(_) => HttpResponse::NotFound().body("Booking tidak ditemukan"),
+    }
+}
+
+pub async fn cancel_booking_handler(path: web::Path<String>) -> HttpResponse {
+    match booking_service::handle_cancel_booking(&path.into_inner()).await {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn reschedule_booking_handler(
+    path: web::Path<String>,
+    reschedule_data: web::Json<RescheduleBookingDto>,
+) -> HttpResponse {
+    match booking_service::handle_reschedule_booking(&path.into_inner(), reschedule_data.into_inner()).await {
+        Ok(booking) => HttpResponse::Ok().json(booking),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,43 @@
+use actix_web::{web, HttpResponse};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::calendar_service;
+use uuid::Uuid;
+
+pub async fn create_calendar_feed_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat membuat kalender dokter.");
+    }
+    match calendar_service::handle_create_calendar_feed(path.into_inner(), &auth_user).await {
+        Ok(feed) => HttpResponse::Created().json(feed),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn revoke_calendar_feed_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mencabut kalender dokter.");
+    }
+    match calendar_service::handle_revoke_calendar_feed(path.into_inner(), &auth_user).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+// Public: calendar apps subscribe without logging in, the token in the URL is the credential
+pub async fn get_calendar_handler(path: web::Path<String>) -> HttpResponse {
+    let token = path.into_inner();
+    let token = token.strip_suffix(".ics").unwrap_or(&token);
+    match calendar_service::handle_get_calendar(token).await {
+        Ok(calendar) => HttpResponse::Ok()
+            .content_type("text/calendar; charset=utf-8")
+            .insert_header(("Cache-Control", "no-store"))
+            .body(calendar),
+        Err(_) => HttpResponse::NotFound().finish(),
+    }
+}
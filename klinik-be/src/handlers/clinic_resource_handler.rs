@@ -0,0 +1,67 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::clinic_resource_dto::{CreateClinicResourceDto, UpdateClinicResourceDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::clinic_resource_service;
+use uuid::Uuid;
+
+pub async fn get_all_clinic_resources_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match clinic_resource_service::handle_get_all_clinic_resources(include_deleted).await {
+        Ok(resources) => HttpResponse::Ok().json(resources),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_clinic_resource_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    resource_data: web::Json<CreateClinicResourceDto>,
+) -> HttpResponse {
+    match clinic_resource_service::handle_create_clinic_resource(resource_data.into_inner(), &auth_user).await {
+        Ok(clinic_resource) => HttpResponse::Created().json(clinic_resource),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn update_clinic_resource_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    resource_data: web::Json<UpdateClinicResourceDto>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    match clinic_resource_service::handle_update_clinic_resource(id, resource_data.into_inner(), &auth_user).await {
+        Ok(clinic_resource) => HttpResponse::Ok().json(clinic_resource),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_clinic_resource_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    match clinic_resource_service::handle_delete_clinic_resource(id, &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_clinic_resource_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match clinic_resource_service::handle_restore_clinic_resource(id, &auth_user).await {
+        Ok(clinic_resource) => HttpResponse::Ok().json(clinic_resource),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,118 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::dtos::consent_dto::{CreateConsentTemplateDto, CreateConsentTemplateVersionDto, SignConsentDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::consent_service;
+use crate::storage::photo_storage::PhotoStorage;
+use uuid::Uuid;
+
+pub async fn get_all_consent_templates_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match consent_service::handle_get_all_consent_templates(include_deleted).await {
+        Ok(templates) => HttpResponse::Ok().json(templates),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_consent_template_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    template_data: web::Json<CreateConsentTemplateDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola template consent.");
+    }
+    match consent_service::handle_create_consent_template(template_data.into_inner(), &auth_user).await {
+        Ok(template) => HttpResponse::Created().json(template),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn create_consent_template_version_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    version_data: web::Json<CreateConsentTemplateVersionDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola template consent.");
+    }
+    let id = path.into_inner();
+    match consent_service::handle_create_consent_template_version(id, version_data.into_inner(), &auth_user).await {
+        Ok(template) => HttpResponse::Created().json(template),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_consent_template_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola template consent.");
+    }
+    let id = path.into_inner();
+    match consent_service::handle_delete_consent_template(id, &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_consent_template_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match consent_service::handle_restore_consent_template(id, &auth_user).await {
+        Ok(template) => HttpResponse::Ok().json(template),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_appointment_consents_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let appointment_id = path.into_inner();
+    match consent_service::handle_get_appointment_consents(appointment_id).await {
+        Ok(status) => HttpResponse::Ok().json(status),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn sign_consent_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    storage: web::Data<dyn PhotoStorage>,
+    path: web::Path<Uuid>,
+    sign_data: web::Json<SignConsentDto>,
+) -> HttpResponse {
+    let mut sign_data = sign_data.into_inner();
+    let signature_image = match sign_data.signature_image.take() {
+        Some(data) => match web::block(move || consent_service::process_signature_image(&data)).await {
+            Ok(Ok(bytes)) => Some(bytes),
+            Ok(Err(e)) => return HttpResponse::BadRequest().body(e),
+            Err(e) => return HttpResponse::InternalServerError().body(e.to_string()),
+        },
+        None => None,
+    };
+    let appointment_id = path.into_inner();
+    match consent_service::handle_sign_consent(storage.get_ref(), appointment_id, sign_data, signature_image, &auth_user).await {
+        Ok(signature) => HttpResponse::Created().json(signature),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_consent_signature_image_handler(
+    storage: web::Data<dyn PhotoStorage>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    match consent_service::handle_get_signature_image(storage.get_ref(), id).await {
+        Ok(bytes) => HttpResponse::Ok().content_type("image/png").body(bytes),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
@@ -1,37 +1,67 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::dokter_dto::{CreateDokterDto, UpdateDokterDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::dokter_service;
 use uuid::Uuid;
 
-pub async fn get_all_dokters_handler() -> HttpResponse {
-    match dokter_service::handle_get_all_dokters().await {
+pub async fn get_all_dokters_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match dokter_service::handle_get_all_dokters(include_deleted).await {
         Ok(dokters) => HttpResponse::Ok().json(dokters),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_dokter_handler(dokter_data: web::Json<CreateDokterDto>) -> HttpResponse {
-    match dokter_service::handle_create_dokter(dokter_data.into_inner()).await {
+pub async fn create_dokter_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    dokter_data: web::Json<CreateDokterDto>,
+) -> HttpResponse {
+    match dokter_service::handle_create_dokter(dokter_data.into_inner(), &auth_user).await {
         Ok(dokter) => HttpResponse::Created().json(dokter),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_dokter_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     dokter_data: web::Json<UpdateDokterDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match dokter_service::handle_update_dokter(id, dokter_data.into_inner()).await {
+    match dokter_service::handle_update_dokter(id, dokter_data.into_inner(), &auth_user).await {
         Ok(dokter) => HttpResponse::Ok().json(dokter),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_dokter_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_dokter_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match dokter_service::handle_delete_dokter(id).await {
+    match dokter_service::handle_delete_dokter(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_dokter_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match dokter_service::handle_restore_dokter(id, &auth_user).await {
+        Ok(dokter) => HttpResponse::Ok().json(dokter),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,109 @@
+use actix_web::{web, Error, HttpRequest, HttpResponse};
+use crate::dtos::event_dto::EventStreamQueryDto;
+use crate::events::event_bus::{self, DomainEvent, Topic};
+use crate::auth::jwt_verifier::JwtVerifier;
+use crate::auth::stream_tickets::{StreamTickets, TICKET_TTL_SECONDS};
+use crate::middlewares::auth_middleware;
+use futures_util::stream;
+use serde_json::json;
+use std::time::Duration;
+use tokio::sync::broadcast::error::RecvError;
+
+const KEEP_ALIVE_SECONDS: u64 = 15;
+
+fn parse_topics(topics: Option<&str>) -> Result<Vec<Topic>, String> {
+    let Some(topics) = topics.filter(|t| !t.trim().is_empty()) else {
+        return Ok(vec![Topic::Appointments, Topic::Invoices, Topic::Products, Topic::Queue]);
+    };
+    topics
+        .split(',')
+        .map(|topic| Topic::parse(topic).ok_or_else(|| format!("Topik {} tidak dikenal", topic.trim())))
+        .collect()
+}
+
+fn format_event(event: &DomainEvent) -> web::Bytes {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    web::Bytes::from(format!("id: {}\nevent: {}\ndata: {}\n\n", event.id, event.topic, data))
+}
+
+fn bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .map(|s| s.to_string())
+}
+
+// Behind the auth middleware: trades the caller's access token for a ticket to open the
+// stream with from an EventSource
+pub async fn create_stream_ticket_handler(req: HttpRequest, tickets: web::Data<StreamTickets>) -> HttpResponse {
+    let Some(token) = bearer_token(&req) else {
+        return auth_middleware::unauthorized("Missing bearer token");
+    };
+    HttpResponse::Ok().json(json!({ "ticket": tickets.issue(&token), "expires_in": TICKET_TTL_SECONDS }))
+}
+
+// Server-sent events for the selected topics. Each event is named after its topic and
+// carries the DomainEvent as JSON; a `lagged` event means some were missed and lists
+// should be reloaded. The caller is authenticated again on every keep-alive, so logging
+// out, revoking the session or the access token expiring closes the stream.
+pub async fn event_stream_handler(
+    req: HttpRequest,
+    verifier: web::Data<JwtVerifier>,
+    tickets: web::Data<StreamTickets>,
+    query: web::Query<EventStreamQueryDto>,
+) -> HttpResponse {
+    let query = query.into_inner();
+    let token = match (bearer_token(&req), query.ticket) {
+        (Some(token), _) => token,
+        (None, Some(ticket)) => match tickets.redeem(&ticket) {
+            Some(token) => token,
+            None => return auth_middleware::unauthorized("Unknown or expired stream ticket"),
+        },
+        (None, None) => return auth_middleware::unauthorized("Missing bearer token"),
+    };
+    if let Err(e) = auth_middleware::authenticate(&verifier, &token).await {
+        return auth_middleware::unauthorized(&e);
+    }
+    let topics = match parse_topics(query.topics.as_deref()) {
+        Ok(topics) => topics,
+        Err(e) => return HttpResponse::BadRequest().body(e),
+    };
+
+    let mut keep_alive = tokio::time::interval(Duration::from_secs(KEEP_ALIVE_SECONDS));
+    keep_alive.reset();
+    let state = (event_bus::subscribe(), keep_alive);
+    let events = stream::unfold(state, move |(mut receiver, mut keep_alive)| {
+        let topics = topics.clone();
+        let verifier = verifier.clone();
+        let token = token.clone();
+        async move {
+            loop {
+                let chunk = tokio::select! {
+                    received = receiver.recv() => match received {
+                        Ok(event) if topics.iter().any(|t| t.as_str() == event.topic) => format_event(&event),
+                        Ok(_) => continue,
+                        Err(RecvError::Lagged(missed)) => {
+                            web::Bytes::from(format!("event: lagged\ndata: {{\"missed\":{}}}\n\n", missed))
+                        }
+                        Err(RecvError::Closed) => return None,
+                    },
+                    _ = keep_alive.tick() => {
+                        if let Err(e) = auth_middleware::authenticate(&verifier, &token).await {
+                            println!("Closing event stream: {}", e);
+                            return None;
+                        }
+                        web::Bytes::from_static(b": keep-alive\n\n")
+                    }
+                };
+                return Some((Ok::<_, Error>(chunk), (receiver, keep_alive)));
+            }
+        }
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events)
+}
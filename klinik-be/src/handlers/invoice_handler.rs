@@ -1,37 +1,67 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::invoice_dto::{CreateInvoiceDto, UpdateInvoiceDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::invoice_service;
 use uuid::Uuid;
 
-pub async fn get_all_invoices_handler() -> HttpResponse {
-    match invoice_service::handle_get_all_invoices().await {
+pub async fn get_all_invoices_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match invoice_service::handle_get_all_invoices(include_deleted).await {
         Ok(invoices) => HttpResponse::Ok().json(invoices),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_invoice_handler(invoice_data: web::Json<CreateInvoiceDto>) -> HttpResponse {
-    match invoice_service::handle_create_invoice(invoice_data.into_inner()).await {
+pub async fn create_invoice_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    invoice_data: web::Json<CreateInvoiceDto>,
+) -> HttpResponse {
+    match invoice_service::handle_create_invoice(invoice_data.into_inner(), &auth_user).await {
         Ok(invoice) => HttpResponse::Created().json(invoice),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_invoice_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     invoice_data: web::Json<UpdateInvoiceDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match invoice_service::handle_update_invoice(id, invoice_data.into_inner()).await {
+    match invoice_service::handle_update_invoice(id, invoice_data.into_inner(), &auth_user).await {
         Ok(invoice) => HttpResponse::Ok().json(invoice),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_invoice_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_invoice_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match invoice_service::handle_delete_invoice(id).await {
+    match invoice_service::handle_delete_invoice(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_invoice_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match invoice_service::handle_restore_invoice(id, &auth_user).await {
+        Ok(invoice) => HttpResponse::Ok().json(invoice),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
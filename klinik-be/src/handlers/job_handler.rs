@@ -0,0 +1,55 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::job_dto::{JobRunQueryDto, UpdateJobDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::job_service;
+
+pub async fn get_all_jobs_handler(auth_user: web::ReqData<AuthenticatedUser>) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat job.");
+    }
+    match job_service::handle_get_all_jobs().await {
+        Ok(jobs) => HttpResponse::Ok().json(jobs),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn get_job_runs_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<String>,
+    query: web::Query<JobRunQueryDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat job.");
+    }
+    match job_service::handle_get_job_runs(&path.into_inner(), query.into_inner()).await {
+        Ok(runs) => HttpResponse::Ok().json(runs),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn update_job_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<String>,
+    job_data: web::Json<UpdateJobDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengubah job.");
+    }
+    match job_service::handle_update_job(&path.into_inner(), job_data.into_inner(), &auth_user).await {
+        Ok(job) => HttpResponse::Ok().json(job),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn trigger_job_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<String>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat menjalankan job.");
+    }
+    match job_service::handle_trigger_job(&path.into_inner(), &auth_user).await {
+        Ok(job) => HttpResponse::Accepted().json(job),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -7,4 +7,22 @@ pub mod pasien_handler;
 pub mod appointment_handler;
 pub mod treatment_progress_handler;
 pub mod skin_analysis_handler;
-pub mod invoice_handler;
\ No newline at end of file
+pub mod invoice_handler;
+pub mod audit_log_handler;
+pub mod photo_handler;
+pub mod recommendation_handler;
+pub mod safety_handler;
+pub mod consent_handler;
+pub mod notification_handler;
+pub mod job_handler;
+pub mod report_handler;
+pub mod event_handler;
+pub mod schedule_handler;
+pub mod clinic_resource_handler;
+pub mod queue_handler;
+pub mod booking_handler;
+pub mod waitlist_handler;
+pub mod appointment_series_handler;
+pub mod calendar_handler;
+pub mod session_handler;
+pub mod totp_handler;
\ No newline at end of file
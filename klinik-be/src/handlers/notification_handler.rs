@@ -0,0 +1,11 @@
+use actix_web::{web, HttpResponse};
+use crate::services::reminder_service;
+use uuid::Uuid;
+
+pub async fn get_appointment_notifications_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let appointment_id = path.into_inner();
+    match reminder_service::handle_get_appointment_notifications(appointment_id).await {
+        Ok(deliveries) => HttpResponse::Ok().json(deliveries),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
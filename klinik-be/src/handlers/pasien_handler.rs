@@ -1,38 +1,80 @@
 //src/handlers/pasien_handler.rs
 use actix_web::{web, HttpResponse};
 use crate::dtos::pasien_dto::{CreatePasienDto, UpdatePasienDto};
-use crate::services::pasien_service;
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::dtos::timeline_dto::TimelineQueryDto;
+use crate::services::{pasien_service, timeline_service};
 use uuid::Uuid;
 
-pub async fn get_all_pasiens_handler() -> HttpResponse {
-    match pasien_service::handle_get_all_pasiens().await {
+pub async fn get_all_pasiens_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match pasien_service::handle_get_all_pasiens(include_deleted).await {
         Ok(pasiens) => HttpResponse::Ok().json(pasiens),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_pasien_handler(pasien_data: web::Json<CreatePasienDto>) -> HttpResponse {
-    match pasien_service::handle_create_pasien(pasien_data.into_inner()).await {
+pub async fn create_pasien_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    pasien_data: web::Json<CreatePasienDto>,
+) -> HttpResponse {
+    match pasien_service::handle_create_pasien(pasien_data.into_inner(), &auth_user).await {
         Ok(pasien) => HttpResponse::Created().json(pasien),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_pasien_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     pasien_data: web::Json<UpdatePasienDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match pasien_service::handle_update_pasien(id, pasien_data.into_inner()).await {
+    match pasien_service::handle_update_pasien(id, pasien_data.into_inner(), &auth_user).await {
         Ok(pasien) => HttpResponse::Ok().json(pasien),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_pasien_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_pasien_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match pasien_service::handle_delete_pasien(id).await {
+    match pasien_service::handle_delete_pasien(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_pasien_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match pasien_service::handle_restore_pasien(id, &auth_user).await {
+        Ok(pasien) => HttpResponse::Ok().json(pasien),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_pasien_timeline_handler(
+    path: web::Path<Uuid>,
+    query: web::Query<TimelineQueryDto>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    match timeline_service::handle_get_pasien_timeline(id, query.into_inner()).await {
+        Ok(events) => HttpResponse::Ok().json(events),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,134 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use futures_util::TryStreamExt;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::photo_service::{self, ProcessedPhoto};
+use crate::storage::photo_storage::PhotoStorage;
+use uuid::Uuid;
+
+// Reads the multipart `file` field and runs it through the image pipeline
+async fn read_photo_upload(mut payload: Multipart) -> Result<ProcessedPhoto, HttpResponse> {
+    let max_bytes = photo_service::max_upload_bytes();
+    let mut bytes: Option<Vec<u8>> = None;
+
+    while let Some(mut field) = payload
+        .try_next()
+        .await
+        .map_err(|e| HttpResponse::BadRequest().body(format!("Invalid multipart body: {}", e)))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let is_image = field
+            .content_type()
+            .map(|mime| mime.type_() == actix_web::mime::IMAGE)
+            .unwrap_or(false);
+        if !is_image {
+            return Err(HttpResponse::UnsupportedMediaType().body("File harus berupa gambar (JPEG, PNG atau WebP)"));
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field
+            .try_next()
+            .await
+            .map_err(|e| HttpResponse::BadRequest().body(format!("Failed to read upload: {}", e)))?
+        {
+            if data.len() + chunk.len() > max_bytes {
+                return Err(HttpResponse::PayloadTooLarge().body(format!("Ukuran file maksimal {} byte", max_bytes)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        bytes = Some(data);
+    }
+
+    let bytes = bytes.ok_or_else(|| HttpResponse::BadRequest().body("Field `file` is required"))?;
+    web::block(move || photo_service::process_photo(&bytes))
+        .await
+        .map_err(|e| HttpResponse::InternalServerError().body(e.to_string()))?
+        .map_err(|e| HttpResponse::BadRequest().body(e))
+}
+
+pub async fn upload_skin_analysis_photo_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    storage: web::Data<dyn PhotoStorage>,
+    path: web::Path<Uuid>,
+    payload: Multipart,
+) -> HttpResponse {
+    let processed = match read_photo_upload(payload).await {
+        Ok(processed) => processed,
+        Err(response) => return response,
+    };
+    let id = path.into_inner();
+    match photo_service::handle_upload_skin_analysis_photo(storage.get_ref(), id, processed, &auth_user).await {
+        Ok(photo) => HttpResponse::Created().json(photo),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn upload_treatment_progress_photo_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    storage: web::Data<dyn PhotoStorage>,
+    path: web::Path<Uuid>,
+    payload: Multipart,
+) -> HttpResponse {
+    let processed = match read_photo_upload(payload).await {
+        Ok(processed) => processed,
+        Err(response) => return response,
+    };
+    let id = path.into_inner();
+    match photo_service::handle_upload_treatment_progress_photo(storage.get_ref(), id, processed, &auth_user).await {
+        Ok(photo) => HttpResponse::Created().json(photo),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_skin_analysis_photos_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_get_skin_analysis_photos(id).await {
+        Ok(photos) => HttpResponse::Ok().json(photos),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn get_treatment_progress_photos_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_get_treatment_progress_photos(id).await {
+        Ok(photos) => HttpResponse::Ok().json(photos),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn get_photo_content_handler(storage: web::Data<dyn PhotoStorage>, path: web::Path<Uuid>) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_get_photo_content(storage.get_ref(), id, false).await {
+        Ok((bytes, content_type)) => HttpResponse::Ok().content_type(content_type).body(bytes),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
+
+pub async fn get_photo_thumbnail_handler(storage: web::Data<dyn PhotoStorage>, path: web::Path<Uuid>) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_get_photo_content(storage.get_ref(), id, true).await {
+        Ok((bytes, content_type)) => HttpResponse::Ok().content_type(content_type).body(bytes),
+        Err(e) => HttpResponse::NotFound().body(e),
+    }
+}
+
+pub async fn delete_photo_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_delete_photo(id, &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_before_after_photos_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let id = path.into_inner();
+    match photo_service::handle_get_before_after(id).await {
+        Ok(pair) => HttpResponse::Ok().json(pair),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
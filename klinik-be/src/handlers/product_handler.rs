@@ -2,20 +2,32 @@
 
 use actix_web::{web, HttpResponse};
 use crate::dtos::product_dto::{CreateProductDto, UpdateProductDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::product_service;
 use uuid::Uuid;
 
 // GET: Handler to get all products
-pub async fn get_all_products_handler() -> HttpResponse {
-    match product_service::handle_get_all_products().await {
+pub async fn get_all_products_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match product_service::handle_get_all_products(include_deleted).await {
         Ok(products) => HttpResponse::Ok().json(products),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
 // POST: Handler to create a new product
-pub async fn create_product_handler(product_data: web::Json<CreateProductDto>) -> HttpResponse {
-    match product_service::handle_create_product(product_data.into_inner()).await {
+pub async fn create_product_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    product_data: web::Json<CreateProductDto>,
+) -> HttpResponse {
+    match product_service::handle_create_product(product_data.into_inner(), &auth_user).await {
         Ok(product) => HttpResponse::Created().json(product),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
@@ -23,21 +35,40 @@ pub async fn create_product_handler(product_data: web::Json<CreateProductDto>) -
 
 // PATCH: Handler to update an existing product by ID
 pub async fn update_product_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     product_data: web::Json<UpdateProductDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match product_service::handle_update_product(id, product_data.into_inner()).await {
+    match product_service::handle_update_product(id, product_data.into_inner(), &auth_user).await {
         Ok(product) => HttpResponse::Ok().json(product),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 // DELETE: Handler to delete a product by ID
-pub async fn delete_product_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_product_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match product_service::handle_delete_product(id).await {
+    match product_service::handle_delete_product(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+// POST: Handler to restore a soft-deleted product by ID (admin only)
+pub async fn restore_product_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match product_service::handle_restore_product(id, &auth_user).await {
+        Ok(product) => HttpResponse::Ok().json(product),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
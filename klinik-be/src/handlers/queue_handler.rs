@@ -0,0 +1,49 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::queue_dto::{CheckInDto, QueueDisplayQueryDto, QueueQueryDto, UpdateQueueStatusDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::queue_service;
+use std::env;
+use uuid::Uuid;
+
+pub async fn get_queue_handler(query: web::Query<QueueQueryDto>) -> HttpResponse {
+    match queue_service::handle_get_queue(query.into_inner()).await {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn check_in_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    check_in_data: web::Json<CheckInDto>,
+) -> HttpResponse {
+    match queue_service::handle_check_in(check_in_data.into_inner(), &auth_user).await {
+        Ok(entry) => HttpResponse::Created().json(entry),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn update_queue_status_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    status_data: web::Json<UpdateQueueStatusDto>,
+) -> HttpResponse {
+    match queue_service::handle_update_queue_status(path.into_inner(), &status_data.status, &auth_user).await {
+        Ok(entry) => HttpResponse::Ok().json(entry),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+// Public so a waiting-room screen can poll it without logging in. It carries no patient
+// data; set QUEUE_DISPLAY_TOKEN to require `?token=` anyway.
+pub async fn get_queue_display_handler(query: web::Query<QueueDisplayQueryDto>) -> HttpResponse {
+    if let Ok(expected) = env::var("QUEUE_DISPLAY_TOKEN")
+        && !expected.is_empty()
+        && query.token.as_deref() != Some(expected.as_str())
+    {
+        return HttpResponse::Unauthorized().finish();
+    }
+    match queue_service::handle_get_queue_display().await {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
@@ -0,0 +1,108 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::dtos::recommendation_rule_dto::{CreateRecommendationRuleDto, UpdateRecommendationRuleDto};
+use crate::dtos::recommendation_suggestion_dto::RecommendationDecisionDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::recommendation_service;
+use uuid::Uuid;
+
+pub async fn get_all_recommendation_rules_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match recommendation_service::handle_get_all_recommendation_rules(include_deleted).await {
+        Ok(rules) => HttpResponse::Ok().json(rules),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_recommendation_rule_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    rule_data: web::Json<CreateRecommendationRuleDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola rule rekomendasi.");
+    }
+    match recommendation_service::handle_create_recommendation_rule(rule_data.into_inner(), &auth_user).await {
+        Ok(rule) => HttpResponse::Created().json(rule),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn update_recommendation_rule_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    rule_data: web::Json<UpdateRecommendationRuleDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola rule rekomendasi.");
+    }
+    let id = path.into_inner();
+    match recommendation_service::handle_update_recommendation_rule(id, rule_data.into_inner(), &auth_user).await {
+        Ok(rule) => HttpResponse::Ok().json(rule),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_recommendation_rule_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengelola rule rekomendasi.");
+    }
+    let id = path.into_inner();
+    match recommendation_service::handle_delete_recommendation_rule(id, &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_recommendation_rule_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match recommendation_service::handle_restore_recommendation_rule(id, &auth_user).await {
+        Ok(rule) => HttpResponse::Ok().json(rule),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn generate_recommendations_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let skin_analysis_id = path.into_inner();
+    match recommendation_service::handle_generate_recommendations(skin_analysis_id).await {
+        Ok(suggestions) => HttpResponse::Created().json(suggestions),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_recommendations_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let skin_analysis_id = path.into_inner();
+    match recommendation_service::handle_get_recommendations(skin_analysis_id).await {
+        Ok(suggestions) => HttpResponse::Ok().json(suggestions),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn decide_recommendations_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    decision: web::Json<RecommendationDecisionDto>,
+) -> HttpResponse {
+    if auth_user.position != "dokter" && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya dokter yang dapat memutuskan rekomendasi.");
+    }
+    let skin_analysis_id = path.into_inner();
+    match recommendation_service::handle_decide_recommendations(skin_analysis_id, decision.into_inner(), &auth_user).await {
+        Ok(analysis) => HttpResponse::Ok().json(analysis),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,17 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::report_dto::ReportQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::report_service;
+
+pub async fn get_daily_reports_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ReportQueryDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat laporan.");
+    }
+    match report_service::handle_get_report_snapshots(query.into_inner()).await {
+        Ok(snapshots) => HttpResponse::Ok().json(snapshots),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
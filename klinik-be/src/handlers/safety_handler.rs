@@ -0,0 +1,20 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::safety_dto::SafetyCheckDto;
+use crate::services::safety_service;
+use uuid::Uuid;
+
+// Lets the booking and cashier screens show findings before they submit
+pub async fn safety_check_handler(check_data: web::Json<SafetyCheckDto>) -> HttpResponse {
+    match safety_service::handle_safety_check(check_data.into_inner()).await {
+        Ok(findings) => HttpResponse::Ok().json(findings),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_pasien_safety_overrides_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let pasien_id = path.into_inner();
+    match safety_service::handle_get_safety_overrides(pasien_id).await {
+        Ok(overrides) => HttpResponse::Ok().json(overrides),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
@@ -0,0 +1,105 @@
+use actix_web::{web, HttpResponse};
+use crate::dtos::schedule_dto::{CreateClinicHolidayDto, CreateScheduleExceptionDto, ScheduleQueryDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::schedule_service;
+use uuid::Uuid;
+
+pub async fn get_schedule_exceptions_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    query: web::Query<ScheduleQueryDto>,
+) -> HttpResponse {
+    if query.include_deleted.unwrap_or(false) && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match schedule_service::handle_get_schedule_exceptions(path.into_inner(), query.into_inner()).await {
+        Ok(exceptions) => HttpResponse::Ok().json(exceptions),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_schedule_exception_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    exception_data: web::Json<CreateScheduleExceptionDto>,
+) -> HttpResponse {
+    match schedule_service::handle_create_schedule_exception(path.into_inner(), exception_data.into_inner(), &auth_user).await {
+        Ok(result) => HttpResponse::Created().json(result),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_schedule_exception_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    match schedule_service::handle_delete_schedule_exception(path.into_inner(), &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_schedule_exception_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    match schedule_service::handle_restore_schedule_exception(path.into_inner(), &auth_user).await {
+        Ok(exception) => HttpResponse::Ok().json(exception),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_clinic_holidays_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ScheduleQueryDto>,
+) -> HttpResponse {
+    if query.include_deleted.unwrap_or(false) && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match schedule_service::handle_get_clinic_holidays(query.into_inner()).await {
+        Ok(holidays) => HttpResponse::Ok().json(holidays),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_clinic_holiday_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    holiday_data: web::Json<CreateClinicHolidayDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengatur hari libur klinik.");
+    }
+    match schedule_service::handle_create_clinic_holiday(holiday_data.into_inner(), &auth_user).await {
+        Ok(result) => HttpResponse::Created().json(result),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_clinic_holiday_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengatur hari libur klinik.");
+    }
+    match schedule_service::handle_delete_clinic_holiday(path.into_inner(), &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_clinic_holiday_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    match schedule_service::handle_restore_clinic_holiday(path.into_inner(), &auth_user).await {
+        Ok(holiday) => HttpResponse::Ok().json(holiday),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
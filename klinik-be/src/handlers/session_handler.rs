@@ -0,0 +1,79 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::auth::auth_provider::AuthProvider;
+use crate::dtos::auth_dto::{RefreshTokenDto, SessionQueryDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::session_service;
+use uuid::Uuid;
+
+// Public: the access token may already have expired, the refresh token is the credential
+pub async fn refresh_token_handler(
+    auth: web::Data<dyn AuthProvider>,
+    refresh_data: web::Json<RefreshTokenDto>,
+) -> HttpResponse {
+    match session_service::handle_refresh(auth.get_ref(), &refresh_data.refresh_token).await {
+        Ok(tokens) => HttpResponse::Ok().json(tokens),
+        Err(e) => HttpResponse::Unauthorized().body(e),
+    }
+}
+
+pub async fn logout_handler(
+    req: HttpRequest,
+    auth: web::Data<dyn AuthProvider>,
+    auth_user: web::ReqData<AuthenticatedUser>,
+) -> HttpResponse {
+    let access_token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    match session_service::handle_logout(auth.get_ref(), &auth_user, access_token).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_sessions_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<SessionQueryDto>,
+) -> HttpResponse {
+    let user_id = match query.user_id {
+        Some(user_id) if user_id.to_string() != auth_user.id => {
+            if !auth_user.is_admin() {
+                return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat sesi pengguna lain.");
+            }
+            user_id
+        }
+        _ => match auth_user.id.parse() {
+            Ok(user_id) => user_id,
+            Err(_) => return HttpResponse::BadRequest().body("ID pengguna tidak valid"),
+        },
+    };
+    match session_service::handle_get_sessions(user_id, &auth_user).await {
+        Ok(sessions) => HttpResponse::Ok().json(sessions),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn revoke_session_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    match session_service::handle_revoke_session(path.into_inner(), &auth_user).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn revoke_user_sessions_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mencabut sesi pengguna lain.");
+    }
+    match session_service::handle_revoke_user_sessions(path.into_inner(), &auth_user).await {
+        Ok(revoked) => HttpResponse::Ok().json(serde_json::json!({ "revoked": revoked })),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -1,37 +1,75 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::skin_analysis_dto::{CreateSkinAnalysisDto, UpdateSkinAnalysisDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::skin_analysis_service;
 use uuid::Uuid;
 
-pub async fn get_all_skin_analyses_handler() -> HttpResponse {
-    match skin_analysis_service::handle_get_all_skin_analyses().await {
+pub async fn get_all_skin_analyses_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match skin_analysis_service::handle_get_all_skin_analyses(include_deleted).await {
         Ok(analyses) => HttpResponse::Ok().json(analyses),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_skin_analysis_handler(analysis_data: web::Json<CreateSkinAnalysisDto>) -> HttpResponse {
-    match skin_analysis_service::handle_create_skin_analysis(analysis_data.into_inner()).await {
+pub async fn create_skin_analysis_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    analysis_data: web::Json<CreateSkinAnalysisDto>,
+) -> HttpResponse {
+    match skin_analysis_service::handle_create_skin_analysis(analysis_data.into_inner(), &auth_user).await {
         Ok(analysis) => HttpResponse::Created().json(analysis),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_skin_analysis_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     analysis_data: web::Json<UpdateSkinAnalysisDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match skin_analysis_service::handle_update_skin_analysis(id, analysis_data.into_inner()).await {
+    match skin_analysis_service::handle_update_skin_analysis(id, analysis_data.into_inner(), &auth_user).await {
         Ok(analysis) => HttpResponse::Ok().json(analysis),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_skin_analysis_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_skin_analysis_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match skin_analysis_service::handle_delete_skin_analysis(id).await {
+    match skin_analysis_service::handle_delete_skin_analysis(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_skin_analysis_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match skin_analysis_service::handle_restore_skin_analysis(id, &auth_user).await {
+        Ok(analysis) => HttpResponse::Ok().json(analysis),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_skin_metric_trends_handler(path: web::Path<Uuid>) -> HttpResponse {
+    let pasien_id = path.into_inner();
+    match skin_analysis_service::handle_get_skin_metric_trends(pasien_id).await {
+        Ok(trends) => HttpResponse::Ok().json(trends),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
@@ -0,0 +1,123 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::auth::auth_provider::AuthProvider;
+use crate::auth::login_guard::LoginGuard;
+use crate::dtos::totp_dto::{MfaEnrolmentConfirmDto, MfaLoginDto, MfaTokenDto, TotpCodeDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::middlewares::rate_limit_middleware::{client_address, too_many_requests};
+use crate::services::session_service::ClientInfo;
+use crate::services::totp_service;
+use uuid::Uuid;
+
+fn client_info(req: &HttpRequest) -> ClientInfo {
+    ClientInfo {
+        user_agent: req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string()),
+        ip_address: Some(client_address(req)),
+    }
+}
+
+// Langkah kedua login (publik, memakai mfa_token dari /login)
+
+// Wrong codes count towards a lockout of their own, so the six digits can't be guessed by
+// logging in again for fresh MFA tokens
+pub async fn mfa_login_handler(
+    req: HttpRequest,
+    auth: web::Data<dyn AuthProvider>,
+    guard: web::Data<LoginGuard>,
+    login_data: web::Json<MfaLoginDto>,
+) -> HttpResponse {
+    let account = totp_service::mfa_token_user(&login_data.mfa_token).map(|user_id| format!("mfa:{}", user_id));
+    if let Some(account) = &account
+        && let Some(retry_after) = guard.check_login(account)
+    {
+        return too_many_requests(retry_after, "Terlalu banyak kode yang salah. Silakan coba lagi nanti.");
+    }
+    match totp_service::handle_mfa_login(auth.get_ref(), login_data.into_inner(), client_info(&req)).await {
+        Ok(tokens) => {
+            if let Some(account) = &account {
+                guard.record_success(account);
+            }
+            HttpResponse::Ok().json(tokens)
+        }
+        Err(e) => {
+            if let Some(account) = &account
+                && e == totp_service::INVALID_CODE
+            {
+                guard.record_failure(account);
+            }
+            HttpResponse::Unauthorized().body(e)
+        }
+    }
+}
+
+pub async fn mfa_enrolment_handler(enrolment_data: web::Json<MfaTokenDto>) -> HttpResponse {
+    match totp_service::handle_mfa_enrolment(&enrolment_data.mfa_token).await {
+        Ok(enrolment) => HttpResponse::Ok().json(enrolment),
+        Err(e) => HttpResponse::Unauthorized().body(e),
+    }
+}
+
+pub async fn mfa_enrolment_confirm_handler(
+    req: HttpRequest,
+    auth: web::Data<dyn AuthProvider>,
+    confirm_data: web::Json<MfaEnrolmentConfirmDto>,
+) -> HttpResponse {
+    match totp_service::handle_mfa_enrolment_confirm(auth.get_ref(), confirm_data.into_inner(), client_info(&req)).await {
+        Ok(completed) => HttpResponse::Ok().json(completed),
+        Err(e) => HttpResponse::Unauthorized().body(e),
+    }
+}
+
+// Pengaturan 2FA akun sendiri
+
+pub async fn start_enrolment_handler(
+    auth: web::Data<dyn AuthProvider>,
+    auth_user: web::ReqData<AuthenticatedUser>,
+) -> HttpResponse {
+    match totp_service::handle_start_enrolment(auth.get_ref(), &auth_user).await {
+        Ok(enrolment) => HttpResponse::Ok().json(enrolment),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn confirm_enrolment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    code_data: web::Json<TotpCodeDto>,
+) -> HttpResponse {
+    match totp_service::handle_confirm_enrolment(&auth_user, &code_data.code).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(recovery_codes),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn disable_totp_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    code_data: web::Json<TotpCodeDto>,
+) -> HttpResponse {
+    match totp_service::handle_disable(&auth_user, &code_data.code).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn regenerate_recovery_codes_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    code_data: web::Json<TotpCodeDto>,
+) -> HttpResponse {
+    match totp_service::handle_regenerate_recovery_codes(&auth_user, &code_data.code).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(recovery_codes),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn reset_user_totp_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mereset autentikasi dua faktor.");
+    }
+    match totp_service::handle_reset(path.into_inner(), &auth_user).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -1,37 +1,67 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::treatment_dto::{CreateTreatmentDto, UpdateTreatmentDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::treatment_service;
 use uuid::Uuid;
 
-pub async fn get_all_treatments_handler() -> HttpResponse {
-    match treatment_service::handle_get_all_treatments().await {
+pub async fn get_all_treatments_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match treatment_service::handle_get_all_treatments(include_deleted).await {
         Ok(treatments) => HttpResponse::Ok().json(treatments),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_treatment_handler(treatment_data: web::Json<CreateTreatmentDto>) -> HttpResponse {
-    match treatment_service::handle_create_treatment(treatment_data.into_inner()).await {
+pub async fn create_treatment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    treatment_data: web::Json<CreateTreatmentDto>,
+) -> HttpResponse {
+    match treatment_service::handle_create_treatment(treatment_data.into_inner(), &auth_user).await {
         Ok(treatment) => HttpResponse::Created().json(treatment),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_treatment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     treatment_data: web::Json<UpdateTreatmentDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match treatment_service::handle_update_treatment(id, treatment_data.into_inner()).await {
+    match treatment_service::handle_update_treatment(id, treatment_data.into_inner(), &auth_user).await {
         Ok(treatment) => HttpResponse::Ok().json(treatment),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_treatment_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_treatment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match treatment_service::handle_delete_treatment(id).await {
+    match treatment_service::handle_delete_treatment(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_treatment_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match treatment_service::handle_restore_treatment(id, &auth_user).await {
+        Ok(treatment) => HttpResponse::Ok().json(treatment),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
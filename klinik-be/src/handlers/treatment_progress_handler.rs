@@ -1,37 +1,67 @@
 use actix_web::{web, HttpResponse};
 use crate::dtos::treatment_progress_dto::{CreateTreatmentProgressDto, UpdateTreatmentProgressDto};
+use crate::dtos::common_dto::ListQueryDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::services::treatment_progress_service;
 use uuid::Uuid;
 
-pub async fn get_all_treatment_progress_handler() -> HttpResponse {
-    match treatment_progress_service::handle_get_all_treatment_progress().await {
+pub async fn get_all_treatment_progress_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<ListQueryDto>,
+) -> HttpResponse {
+    let include_deleted = query.include_deleted.unwrap_or(false);
+    if include_deleted && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match treatment_progress_service::handle_get_all_treatment_progress(include_deleted).await {
         Ok(progress) => HttpResponse::Ok().json(progress),
         Err(e) => HttpResponse::InternalServerError().body(e),
     }
 }
 
-pub async fn create_treatment_progress_handler(progress_data: web::Json<CreateTreatmentProgressDto>) -> HttpResponse {
-    match treatment_progress_service::handle_create_treatment_progress(progress_data.into_inner()).await {
+pub async fn create_treatment_progress_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    progress_data: web::Json<CreateTreatmentProgressDto>,
+) -> HttpResponse {
+    match treatment_progress_service::handle_create_treatment_progress(progress_data.into_inner(), &auth_user).await {
         Ok(progress) => HttpResponse::Created().json(progress),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
 pub async fn update_treatment_progress_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
     path: web::Path<Uuid>,
     progress_data: web::Json<UpdateTreatmentProgressDto>,
 ) -> HttpResponse {
     let id = path.into_inner();
-    match treatment_progress_service::handle_update_treatment_progress(id, progress_data.into_inner()).await {
+    match treatment_progress_service::handle_update_treatment_progress(id, progress_data.into_inner(), &auth_user).await {
         Ok(progress) => HttpResponse::Ok().json(progress),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn delete_treatment_progress_handler(path: web::Path<Uuid>) -> HttpResponse {
+pub async fn delete_treatment_progress_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
     let id = path.into_inner();
-    match treatment_progress_service::handle_delete_treatment_progress(id).await {
+    match treatment_progress_service::handle_delete_treatment_progress(id, &auth_user).await {
         Ok(_) => HttpResponse::NoContent().finish(),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_treatment_progress_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    let id = path.into_inner();
+    match treatment_progress_service::handle_restore_treatment_progress(id, &auth_user).await {
+        Ok(progress) => HttpResponse::Ok().json(progress),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -1,18 +1,73 @@
 // src/handlers/user_handler.rs
 
 use actix_web::{web, HttpResponse, HttpRequest};
-use crate::dtos::user_dto::{RegisterUserDto, ForgotPasswordDto, LoginUserDto, ResetPasswordDto};
+use crate::auth::auth_provider::{AuthProvider, INVALID_LOGIN};
+use crate::auth::login_guard::{account_key, LoginGuard};
+use crate::dtos::user_dto::{
+    RegisterUserDto, ForgotPasswordDto, LoginUserDto, ResetPasswordDto, VerifyEmailDto, ResendVerificationDto,
+    InviteUserDto, UpdateUserDto, UserQueryDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::middlewares::rate_limit_middleware::{client_address, too_many_requests};
+use crate::services::session_service::{self, ClientInfo};
 use crate::services::user_service;
+use uuid::Uuid;
 
-pub async fn register(user_data: web::Json<RegisterUserDto>) -> HttpResponse {
-    match user_service::handle_user_registration(user_data.into_inner()).await {
+fn too_many_emails(retry_after: u64) -> HttpResponse {
+    too_many_requests(retry_after, "Terlalu banyak permintaan untuk email ini, silakan coba lagi nanti.")
+}
+
+pub async fn register(
+    auth: web::Data<dyn AuthProvider>,
+    guard: web::Data<LoginGuard>,
+    user_data: web::Json<RegisterUserDto>,
+) -> HttpResponse {
+    if let Some(retry_after) = guard.check_email("register", &user_data.email) {
+        return too_many_emails(retry_after);
+    }
+    let first_user = match user_service::is_first_user().await {
+        Ok(first_user) => first_user,
+        Err(e) => return HttpResponse::InternalServerError().body(e),
+    };
+    if !first_user && !user_service::self_registration_enabled() {
+        return HttpResponse::Forbidden().body("Registrasi mandiri dinonaktifkan. Minta admin untuk mengundang Anda.");
+    }
+    match user_service::handle_user_registration(auth.get_ref(), user_data.into_inner(), first_user).await {
         Ok(_) => HttpResponse::Created().body("User registered successfully"),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
 }
 
-pub async fn forgot_password(forgot_data: web::Json<ForgotPasswordDto>) -> HttpResponse {
-    match user_service::handle_forgot_password(forgot_data.into_inner()).await {
+pub async fn verify_email(auth: web::Data<dyn AuthProvider>, verify_data: web::Json<VerifyEmailDto>) -> HttpResponse {
+    match user_service::handle_verify_email(auth.get_ref(), verify_data.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().body("Email verified successfully."),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn resend_verification(
+    auth: web::Data<dyn AuthProvider>,
+    guard: web::Data<LoginGuard>,
+    resend_data: web::Json<ResendVerificationDto>,
+) -> HttpResponse {
+    if let Some(retry_after) = guard.check_email("verification", &resend_data.email) {
+        return too_many_emails(retry_after);
+    }
+    match user_service::handle_resend_verification(auth.get_ref(), resend_data.into_inner()).await {
+        Ok(_) => HttpResponse::Ok().body("If the account is awaiting verification, a new code has been sent."),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn forgot_password(
+    auth: web::Data<dyn AuthProvider>,
+    guard: web::Data<LoginGuard>,
+    forgot_data: web::Json<ForgotPasswordDto>,
+) -> HttpResponse {
+    if let Some(retry_after) = guard.check_email("password_reset", &forgot_data.email) {
+        return too_many_emails(retry_after);
+    }
+    match user_service::handle_forgot_password(auth.get_ref(), forgot_data.into_inner()).await {
         Ok(_) => HttpResponse::Ok().body("Password reset email sent successfully. Please check your inbox."),
         Err(e) => HttpResponse::BadRequest().body(e),
     }
@@ -21,6 +76,7 @@ pub async fn forgot_password(forgot_data: web::Json<ForgotPasswordDto>) -> HttpR
 // Handler ini akan menerima token dari frontend dan password baru
 pub async fn reset_password(
     req: HttpRequest, // <-- Menerima HttpRequest
+    auth: web::Data<dyn AuthProvider>,
     password_data: web::Json<ResetPasswordDto>,
 ) -> HttpResponse {
     // 1. Ambil header "Authorization" secara manual
@@ -36,16 +92,121 @@ pub async fn reset_password(
     }
     
     // 3. Panggil service dengan token dan password baru
-    match user_service::handle_password_reset(token, password_data.into_inner().password).await {
+    match user_service::handle_password_reset(auth.get_ref(), token, password_data.into_inner().password).await {
         Ok(_) => HttpResponse::Ok().body("Password updated successfully."),
         Err(e) => HttpResponse::Unauthorized().body(e),
     }
 }
 
 // Tambahkan handler login berikut
-pub async fn login(login_data: web::Json<LoginUserDto>) -> HttpResponse {
-    match user_service::handle_user_login(login_data.into_inner()).await {
-        Ok(token) => HttpResponse::Ok().body(token),
-        Err(e) => HttpResponse::Unauthorized().body(e),
+// Every rejected attempt counts towards the account's lockout, a successful one clears it
+pub async fn login(
+    req: HttpRequest,
+    auth: web::Data<dyn AuthProvider>,
+    guard: web::Data<LoginGuard>,
+    login_data: web::Json<LoginUserDto>,
+) -> HttpResponse {
+    let account = account_key(&login_data.email);
+    if let Some(retry_after) = guard.check_login(&account) {
+        return too_many_requests(retry_after, "Terlalu banyak percobaan login. Akun dikunci sementara, silakan coba lagi nanti.");
+    }
+    let client = ClientInfo {
+        user_agent: req.headers().get("User-Agent").and_then(|h| h.to_str().ok()).map(|s| s.to_string()),
+        ip_address: Some(client_address(&req)),
+    };
+    match session_service::handle_login(auth.get_ref(), login_data.into_inner(), client).await {
+        Ok(result) => {
+            guard.record_success(&account);
+            HttpResponse::Ok().json(result)
+        }
+        Err(e) => {
+            if e == INVALID_LOGIN {
+                guard.record_failure(&account);
+            }
+            HttpResponse::Unauthorized().body(e)
+        }
     }
-}
\ No newline at end of file
+}
+
+// Manajemen staf: semua rute di bawah ini khusus admin
+
+pub async fn get_users_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<UserQueryDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat daftar pengguna.");
+    }
+    match user_service::handle_get_users(query.include_deactivated.unwrap_or(false)).await {
+        Ok(users) => HttpResponse::Ok().json(users),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn invite_user_handler(
+    auth: web::Data<dyn AuthProvider>,
+    auth_user: web::ReqData<AuthenticatedUser>,
+    invite_data: web::Json<InviteUserDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengundang staf.");
+    }
+    match user_service::handle_invite_user(auth.get_ref(), invite_data.into_inner(), &auth_user).await {
+        Ok(user) => HttpResponse::Created().json(user),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn update_user_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+    user_data: web::Json<UpdateUserDto>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengubah data staf.");
+    }
+    match user_service::handle_update_user(path.into_inner(), user_data.into_inner(), &auth_user).await {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn deactivate_user_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat menonaktifkan akun.");
+    }
+    match user_service::handle_deactivate_user(path.into_inner(), &auth_user).await {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn reactivate_user_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mengaktifkan kembali akun.");
+    }
+    match user_service::handle_reactivate_user(path.into_inner(), &auth_user).await {
+        Ok(user) => HttpResponse::Ok().json(user),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn force_password_reset_handler(
+    auth: web::Data<dyn AuthProvider>,
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat mewajibkan reset password.");
+    }
+    match user_service::handle_force_password_reset(auth.get_ref(), path.into_inner(), &auth_user).await {
+        Ok(()) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
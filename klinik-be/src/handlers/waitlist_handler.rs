@@ -0,0 +1,116 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use crate::dtos::waitlist_dto::{CreateWaitlistEntryDto, JoinWaitlistDto, WaitlistQueryDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::services::{booking_service, waitlist_service};
+use uuid::Uuid;
+
+pub async fn get_waitlist_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    query: web::Query<WaitlistQueryDto>,
+) -> HttpResponse {
+    if query.include_deleted.unwrap_or(false) && !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat melihat data terhapus.");
+    }
+    match waitlist_service::handle_get_waitlist(query.into_inner()).await {
+        Ok(entries) => HttpResponse::Ok().json(entries),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+pub async fn create_waitlist_entry_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    entry_data: web::Json<CreateWaitlistEntryDto>,
+) -> HttpResponse {
+    match waitlist_service::handle_create_waitlist_entry(entry_data.into_inner(), &auth_user).await {
+        Ok(entry) => HttpResponse::Created().json(entry),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn delete_waitlist_entry_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    match waitlist_service::handle_delete_waitlist_entry(path.into_inner(), &auth_user).await {
+        Ok(_) => HttpResponse::NoContent().finish(),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn restore_waitlist_entry_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    if !auth_user.is_admin() {
+        return HttpResponse::Forbidden().body("Akses ditolak. Hanya admin yang dapat memulihkan data.");
+    }
+    match waitlist_service::handle_restore_waitlist_entry(path.into_inner(), &auth_user).await {
+        Ok(entry) => HttpResponse::Ok().json(entry),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_waitlist_entry_offers_handler(path: web::Path<Uuid>) -> HttpResponse {
+    match waitlist_service::handle_get_waitlist_entry_offers(path.into_inner()).await {
+        Ok(offers) => HttpResponse::Ok().json(offers),
+        Err(e) => HttpResponse::InternalServerError().body(e),
+    }
+}
+
+// Staff answering for a patient who replied by phone
+pub async fn accept_waitlist_offer_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    match waitlist_service::handle_accept_waitlist_offer(path.into_inner(), &auth_user).await {
+        Ok(offer) => HttpResponse::Ok().json(offer),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn decline_waitlist_offer_handler(
+    auth_user: web::ReqData<AuthenticatedUser>,
+    path: web::Path<Uuid>,
+) -> HttpResponse {
+    match waitlist_service::handle_decline_waitlist_offer(path.into_inner(), &auth_user).await {
+        Ok(offer) => HttpResponse::Ok().json(offer),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn join_waitlist_handler(req: HttpRequest, join_data: web::Json<JoinWaitlistDto>) -> HttpResponse {
+    let token = req
+        .headers()
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| s.strip_prefix("Bearer "));
+    let phone = match token.map(booking_service::verify_session) {
+        Some(Ok(phone)) => phone,
+        _ => return HttpResponse::Unauthorized().body("Verifikasi nomor telepon diperlukan"),
+    };
+    match waitlist_service::handle_join_waitlist(&phone, join_data.into_inner()).await {
+        Ok(entry) => HttpResponse::Created().json(entry),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn get_public_offer_handler(path: web::Path<String>) -> HttpResponse {
+    match waitlist_service::handle_get_public_offer(&path.into_inner()).await {
+        Ok(offer) => HttpResponse::Ok().json(offer),
+        Err(_) => HttpResponse::NotFound().body("Tawaran tidak ditemukan"),
+    }
+}
+
+pub async fn accept_public_offer_handler(path: web::Path<String>) -> HttpResponse {
+    match waitlist_service::handle_accept_public_offer(&path.into_inner()).await {
+        Ok(offer) => HttpResponse::Ok().json(offer),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+pub async fn decline_public_offer_handler(path: web::Path<String>) -> HttpResponse {
+    match waitlist_service::handle_decline_public_offer(&path.into_inner()).await {
+        Ok(offer) => HttpResponse::Ok().json(offer),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
@@ -0,0 +1,88 @@
+use crate::jobs::job_runner::{JobDefinition, JobFuture};
+use crate::notifications::notifier::{Notification, Notifiers};
+use crate::services::appointment_service::{self, clinic_offset};
+use crate::services::{product_service, reminder_service, report_service, retention_service, waitlist_service};
+use crate::storage::photo_storage::PhotoStorage;
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use std::env;
+use std::sync::Arc;
+
+fn job<F>(name: &'static str, default_schedule: &'static str, max_attempts: u32, run: F) -> JobDefinition
+where
+    F: Fn() -> JobFuture + Send + Sync + 'static,
+{
+    JobDefinition { name, default_schedule, max_attempts, run: Arc::new(run) }
+}
+
+// Emails the stock report to STOCK_ALERT_EMAIL when it lists anything
+async fn send_stock_alert(notifiers: &Notifiers, report: &Value) -> Result<(), String> {
+    let Ok(recipient) = env::var("STOCK_ALERT_EMAIL") else {
+        return Ok(());
+    };
+    let mut lines = Vec::new();
+    for (key, label) in [("expired", "Kedaluwarsa"), ("expiring_soon", "Segera kedaluwarsa"), ("low_stock", "Stok menipis")] {
+        for product in report[key].as_array().into_iter().flatten() {
+            lines.push(format!(
+                "- {}: {} (stok {}, kedaluwarsa {})",
+                label,
+                product["name"].as_str().unwrap_or_default(),
+                product["stock"],
+                product["expiry_date"].as_str().unwrap_or("-")
+            ));
+        }
+    }
+    if lines.is_empty() {
+        return Ok(());
+    }
+    let notification = Notification {
+        recipient,
+        subject: "Peringatan stok produk".to_string(),
+        body: lines.join("\n"),
+    };
+    notifiers.email.send(&notification).await
+}
+
+// Schedules are cron expressions with a seconds field, in clinic local time
+pub fn definitions(storage: Arc<dyn PhotoStorage>, notifiers: Arc<Notifiers>) -> Vec<JobDefinition> {
+    let reminder_notifiers = notifiers.clone();
+    vec![
+        // Failed messages are retried by the reminder cycle itself, so one attempt is enough here
+        job("appointment_reminders", "0 */5 * * * *", 1, move || {
+            let notifiers = reminder_notifiers.clone();
+            Box::pin(async move {
+                let (scheduled, sent) = reminder_service::run_reminder_cycle(&notifiers).await?;
+                Ok(json!({ "scheduled": scheduled, "sent": sent }))
+            })
+        }),
+        job("mark_no_shows", "0 */15 * * * *", 3, || {
+            Box::pin(async { appointment_service::mark_no_shows(Utc::now()).await })
+        }),
+        job("waitlist_offer_expiry", "0 */5 * * * *", 3, || {
+            Box::pin(async { waitlist_service::expire_offers().await })
+        }),
+        job("daily_report_snapshot", "0 30 0 * * *", 3, || {
+            Box::pin(async {
+                let yesterday = Utc::now().with_timezone(&clinic_offset()).date_naive() - Duration::days(1);
+                let snapshot = report_service::snapshot_daily_report(yesterday).await?;
+                Ok(json!({ "report_date": snapshot.report_date }))
+            })
+        }),
+        job("stock_expiry_check", "0 0 7 * * *", 3, move || {
+            let notifiers = notifiers.clone();
+            Box::pin(async move {
+                let today = Utc::now().with_timezone(&clinic_offset()).date_naive();
+                let report = product_service::check_stock(today).await?;
+                send_stock_alert(&notifiers, &report).await?;
+                Ok(report)
+            })
+        }),
+        job("retention_purge", "0 0 3 * * *", 3, move || {
+            let storage = storage.clone();
+            Box::pin(async move {
+                let purged = retention_service::purge_expired_records(storage.as_ref()).await;
+                Ok(json!({ "purged": purged }))
+            })
+        }),
+    ]
+}
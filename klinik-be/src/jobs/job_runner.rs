@@ -0,0 +1,198 @@
+use crate::dtos::job_dto::{CreateJobDto, CreateJobRunDto, FinishJobRunDto};
+use crate::models::job::Job;
+use crate::repositories::{job_repo, job_run_repo};
+use crate::services::appointment_service::clinic_offset;
+use chrono::{DateTime, Utc};
+use cron::Schedule;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::env;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+const DEFAULT_POLL_SECONDS: u64 = 30;
+const RETRY_BASE_SECONDS: u64 = 30;
+
+pub type JobFuture = Pin<Box<dyn Future<Output = Result<Value, String>> + Send>>;
+
+// A job the runner knows how to execute. The schedule stored in the `jobs` table wins over
+// `default_schedule` once the row exists, so admins can reschedule without a deploy.
+pub struct JobDefinition {
+    pub name: &'static str,
+    pub default_schedule: &'static str,
+    pub max_attempts: u32,
+    pub run: Arc<dyn Fn() -> JobFuture + Send + Sync>,
+}
+
+// Next occurrence of a cron expression after `after`, evaluated in clinic local time
+pub fn next_occurrence(schedule: &str, after: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    let schedule = Schedule::from_str(schedule).map_err(|e| format!("Jadwal cron tidak valid: {}", e))?;
+    schedule
+        .after(&after.with_timezone(&clinic_offset()))
+        .next()
+        .map(|next| next.to_utc())
+        .ok_or_else(|| "Jadwal cron tidak memiliki waktu berikutnya".to_string())
+}
+
+pub struct JobRunner {
+    definitions: Vec<JobDefinition>,
+    running: Mutex<HashSet<&'static str>>,
+}
+
+impl JobRunner {
+    pub fn new(definitions: Vec<JobDefinition>) -> Self {
+        JobRunner { definitions, running: Mutex::new(HashSet::new()) }
+    }
+
+    // Creates the row of every definition that doesn't have one yet
+    async fn register(&self) -> Result<(), String> {
+        let existing = job_repo::get_all_jobs().await?;
+        for definition in &self.definitions {
+            if existing.iter().any(|job| job.name == definition.name) {
+                continue;
+            }
+            let job_data = CreateJobDto {
+                name: definition.name.to_string(),
+                schedule: definition.default_schedule.to_string(),
+                is_enabled: true,
+                next_run_at: next_occurrence(definition.default_schedule, Utc::now())?,
+            };
+            job_repo::create_job(&job_data).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        if let Err(e) = self.register().await {
+            println!("Failed to register jobs: {}", e);
+        }
+        let seconds = env::var("JOB_POLL_SECONDS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|seconds| *seconds > 0)
+            .unwrap_or(DEFAULT_POLL_SECONDS);
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(seconds));
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.poll().await {
+                println!("Job poll failed: {}", e);
+            }
+        }
+    }
+
+    async fn poll(self: &Arc<Self>) -> Result<(), String> {
+        let now = Utc::now();
+        for job in job_repo::get_all_jobs().await? {
+            let Some(index) = self.definitions.iter().position(|d| d.name == job.name) else {
+                continue;
+            };
+            let Some(due_at) = job.next_run_at.filter(|due_at| job.is_enabled && *due_at <= now) else {
+                continue;
+            };
+            if self.running.lock().unwrap().contains(self.definitions[index].name) {
+                continue;
+            }
+            let next_run_at = match next_occurrence(&job.schedule, now) {
+                Ok(next) => next,
+                Err(e) => {
+                    println!("Job {} skipped: {}", job.name, e);
+                    continue;
+                }
+            };
+            if !job_repo::claim_job(&job.name, due_at, next_run_at).await? {
+                continue;
+            }
+            self.running.lock().unwrap().insert(self.definitions[index].name);
+            let runner = Arc::clone(self);
+            tokio::spawn(async move {
+                runner.execute(index, job).await;
+                runner.running.lock().unwrap().remove(runner.definitions[index].name);
+            });
+        }
+        Ok(())
+    }
+
+    // Runs one occurrence, retrying failures with exponential backoff (30s, 60s, 120s, ...)
+    async fn execute(&self, index: usize, job: Job) {
+        let definition = &self.definitions[index];
+        let mut status = "failed";
+        for attempt in 1..=definition.max_attempts.max(1) {
+            let run_data = CreateJobRunDto {
+                job_name: job.name.clone(),
+                attempt: attempt as i32,
+                status: "running".to_string(),
+                started_at: Utc::now(),
+            };
+            let run = job_run_repo::create_job_run(&run_data).await;
+            let result = (definition.run)().await;
+
+            let finish_data = match &result {
+                Ok(output) => FinishJobRunDto {
+                    status: "succeeded".to_string(),
+                    finished_at: Utc::now(),
+                    error: None,
+                    output: Some(output.clone()),
+                },
+                Err(e) => FinishJobRunDto {
+                    status: "failed".to_string(),
+                    finished_at: Utc::now(),
+                    error: Some(e.clone()),
+                    output: None,
+                },
+            };
+            match run {
+                Ok(run) => {
+                    if let Err(e) = job_run_repo::finish_job_run(run.id, &finish_data).await {
+                        println!("Failed to record run of job {}: {}", job.name, e);
+                    }
+                }
+                Err(e) => println!("Failed to record run of job {}: {}", job.name, e),
+            }
+
+            if result.is_ok() {
+                status = "succeeded";
+                break;
+            }
+            if attempt < definition.max_attempts {
+                let delay = RETRY_BASE_SECONDS * 2_u64.pow(attempt - 1);
+                tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
+            }
+        }
+        if let Err(e) = job_repo::record_job_result(&job.name, status, Utc::now()).await {
+            println!("Failed to update job {}: {}", job.name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The clinic default is UTC+7 (WIB); CLINIC_UTC_OFFSET_HOURS is left unset in tests
+    fn utc(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn daily_schedules_follow_clinic_local_time() {
+        // 07:00 WIB is midnight UTC
+        assert_eq!(next_occurrence("0 0 7 * * *", utc("2026-03-01T23:59:00Z")), Ok(utc("2026-03-02T00:00:00Z")));
+        // 00:30 WIB on 2 March is still 1 March in UTC
+        assert_eq!(next_occurrence("0 30 0 * * *", utc("2026-03-01T17:00:00Z")), Ok(utc("2026-03-01T17:30:00Z")));
+    }
+
+    #[test]
+    fn the_next_run_is_strictly_after_the_given_time() {
+        assert_eq!(next_occurrence("0 0 7 * * *", utc("2026-03-02T00:00:00Z")), Ok(utc("2026-03-03T00:00:00Z")));
+        assert_eq!(next_occurrence("0 */5 * * * *", utc("2026-03-02T10:05:00Z")), Ok(utc("2026-03-02T10:10:00Z")));
+        assert_eq!(next_occurrence("0 */5 * * * *", utc("2026-03-02T10:02:30Z")), Ok(utc("2026-03-02T10:05:00Z")));
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(next_occurrence("setiap hari", utc("2026-03-02T00:00:00Z")).is_err());
+        assert!(next_occurrence("0 0 25 * * *", utc("2026-03-02T00:00:00Z")).is_err());
+    }
+}
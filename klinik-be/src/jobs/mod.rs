@@ -0,0 +1,2 @@
+pub mod job_runner;
+pub mod clinic_jobs;
@@ -1,23 +1,32 @@
 //src/main.rs
 use actix_web::{web, App, HttpServer};
-use actix_cors::Cors;
 use dotenvy::dotenv;
 use std::env;
 use crate::handlers::product_handler;
-use crate::handlers::skin_analysis_handler;
-use crate::handlers::invoice_handler;
 use crate::handlers::user_handler;
-use crate::handlers::treatment_progress_handler;
 use crate::handlers::treatment_handler;
 use crate::handlers::dokter_handler;
 use crate::handlers::pasien_handler;
-use crate::handlers::appointment_handler;
+use crate::auth::{auth_provider, jwt_verifier::JwtVerifier, login_guard::LoginGuard, stream_tickets::StreamTickets};
+use crate::middlewares::auth_middleware::AuthMiddleware;
+use crate::middlewares::cors_config::CorsSettings;
+use crate::middlewares::rate_limit_middleware::RateLimit;
+use crate::middlewares::security_headers_middleware::SecurityHeaders;
+use crate::notifications::notifier;
+use crate::jobs::{clinic_jobs, job_runner::JobRunner};
+use crate::services::{consent_service, waitlist_service};
+use crate::storage::photo_storage;
 mod handlers;
 mod dtos;
 mod models;
 mod services;
 mod repositories;
 mod middlewares;
+mod storage;
+mod notifications;
+mod jobs;
+mod events;
+mod auth;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -28,61 +37,247 @@ async fn main() -> std::io::Result<()> {
     
     println!("Server running at http://{}:{}", host, port);
 
-HttpServer::new(|| {
-        let cors = Cors::default()
-            .allow_any_origin()
-            .allow_any_method()
-            .allow_any_header()
-            .max_age(3600);
+    let jwt_verifier = std::sync::Arc::new(
+        JwtVerifier::from_env().map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+    );
 
+    let photo_storage = photo_storage::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+    let notifiers = std::sync::Arc::new(
+        notifier::from_env().map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?,
+    );
+    waitlist_service::set_notifiers(notifiers.clone());
+    let auth_provider = auth_provider::from_env(notifiers.clone())
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let job_runner = std::sync::Arc::new(JobRunner::new(clinic_jobs::definitions(photo_storage.clone(), notifiers.clone())));
+    tokio::spawn(job_runner.start());
+
+    // Shared by every worker, so the limit applies per client across the whole server
+    let public_rate_limit = RateLimit::new(
+        env::var("PUBLIC_RATE_LIMIT_PER_MINUTE").ok().and_then(|v| v.parse().ok()).unwrap_or(30),
+        std::time::Duration::from_secs(60),
+    );
+    // Per-IP limits on the auth routes; LoginGuard adds the per-account side
+    let login_rate_limit = RateLimit::from_env("LOGIN", 20, 60);
+    let register_rate_limit = RateLimit::from_env("REGISTER", 5, 60 * 60);
+    let forgot_password_rate_limit = RateLimit::from_env("FORGOT_PASSWORD", 5, 60 * 60);
+    let verify_email_rate_limit = RateLimit::from_env("VERIFY_EMAIL", 10, 10 * 60);
+    let login_guard = std::sync::Arc::new(LoginGuard::from_env());
+    let stream_tickets = std::sync::Arc::new(StreamTickets::new());
+
+    let cors_settings = CorsSettings::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let security_headers = SecurityHeaders::from_env()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+HttpServer::new(move || {
         App::new()
-            .wrap(cors)
+            .wrap(cors_settings.build())
+            .wrap(security_headers.clone())
+            .app_data(web::Data::from(photo_storage.clone()))
+            .app_data(web::Data::from(notifiers.clone()))
+            .app_data(web::Data::from(jwt_verifier.clone()))
+            .app_data(web::Data::from(auth_provider.clone()))
+            .app_data(web::Data::from(login_guard.clone()))
+            .app_data(web::Data::from(stream_tickets.clone()))
             .service(web::scope("/api")
-                .route("/register", web::post().to(handlers::user_handler::register))
-                .route("/forgot-password", web::post().to(handlers::user_handler::forgot_password))
+                .service(web::resource("/register")
+                    .wrap(register_rate_limit.clone())
+                    .route(web::post().to(handlers::user_handler::register)))
+                .service(web::resource("/verify-email")
+                    .wrap(verify_email_rate_limit.clone())
+                    .route(web::post().to(user_handler::verify_email)))
+                .service(web::resource("/verify-email/resend")
+                    .wrap(verify_email_rate_limit.clone())
+                    .route(web::post().to(user_handler::resend_verification)))
+                .service(web::resource("/forgot-password")
+                    .wrap(forgot_password_rate_limit.clone())
+                    .route(web::post().to(handlers::user_handler::forgot_password)))
                 .route("/reset-password", web::post().to(user_handler::reset_password))
-                .route("/login", web::post().to(user_handler::login))
-                
-                // Rute Produk
-                .route("/products", web::get().to(product_handler::get_all_products_handler))
-                .route("/products", web::post().to(product_handler::create_product_handler))
-                .route("/products/{id}", web::patch().to(product_handler::update_product_handler))
-                .route("/products/{id}", web::delete().to(product_handler::delete_product_handler))
-                  // Rute Appointment
-                .route("/appointments", web::get().to(handlers::appointment_handler::get_all_appointments_handler))
-                .route("/appointments", web::post().to(handlers::appointment_handler::create_appointment_handler))
-                .route("/appointments/{id}", web::patch().to(handlers::appointment_handler::update_appointment_handler))
-                .route("/appointments/{id}", web::delete().to(handlers::appointment_handler::delete_appointment_handler))
-                // Rute Treatment 
-                .route("/treatments", web::get().to(treatment_handler::get_all_treatments_handler))
-                .route("/treatments", web::post().to(treatment_handler::create_treatment_handler))
-                .route("/treatments/{id}", web::patch().to(treatment_handler::update_treatment_handler))
-                .route("/treatments/{id}", web::delete().to(treatment_handler::delete_treatment_handler))
-                 // Rute Dokter 
-                .route("/dokters", web::get().to(dokter_handler::get_all_dokters_handler))
-                .route("/dokters", web::post().to(dokter_handler::create_dokter_handler))
-                .route("/dokters/{id}", web::patch().to(dokter_handler::update_dokter_handler))
-                .route("/dokters/{id}", web::delete().to(dokter_handler::delete_dokter_handler))
-                 // Rute Pasien
-                .route("/pasiens", web::get().to(pasien_handler::get_all_pasiens_handler))
-                .route("/pasiens", web::post().to(pasien_handler::create_pasien_handler))
-                .route("/pasiens/{id}", web::patch().to(pasien_handler::update_pasien_handler))
-                .route("/pasiens/{id}", web::delete().to(pasien_handler::delete_pasien_handler))
-                // Rute Treatment Progress
-                .route("/treatment-progress", web::get().to(handlers::treatment_progress_handler::get_all_treatment_progress_handler))
-                .route("/treatment-progress", web::post().to(handlers::treatment_progress_handler::create_treatment_progress_handler))
-                .route("/treatment-progress/{id}", web::patch().to(handlers::treatment_progress_handler::update_treatment_progress_handler))
-                .route("/treatment-progress/{id}", web::delete().to(handlers::treatment_progress_handler::delete_treatment_progress_handler))
-                // Rute Skin Analysis
-                .route("/skin-analyses", web::get().to(handlers::skin_analysis_handler::get_all_skin_analyses_handler))
-                .route("/skin-analyses", web::post().to(handlers::skin_analysis_handler::create_skin_analysis_handler))
-                .route("/skin-analyses/{id}", web::patch().to(handlers::skin_analysis_handler::update_skin_analysis_handler))
-                .route("/skin-analyses/{id}", web::delete().to(handlers::skin_analysis_handler::delete_skin_analysis_handler))
-                 // Rute Invoices
-                .route("/invoices", web::get().to(handlers::invoice_handler::get_all_invoices_handler))
-                .route("/invoices", web::post().to(handlers::invoice_handler::create_invoice_handler))
-                .route("/invoices/{id}", web::patch().to(handlers::invoice_handler::update_invoice_handler))
-                .route("/invoices/{id}", web::delete().to(handlers::invoice_handler::delete_invoice_handler))
+                .service(web::resource("/login")
+                    .wrap(login_rate_limit.clone())
+                    .route(web::post().to(user_handler::login)))
+                .service(web::resource("/login/mfa")
+                    .wrap(login_rate_limit.clone())
+                    .route(web::post().to(handlers::totp_handler::mfa_login_handler)))
+                .service(web::resource("/login/mfa/enroll")
+                    .wrap(login_rate_limit.clone())
+                    .route(web::post().to(handlers::totp_handler::mfa_enrolment_handler)))
+                .service(web::resource("/login/mfa/enroll/confirm")
+                    .wrap(login_rate_limit.clone())
+                    .route(web::post().to(handlers::totp_handler::mfa_enrolment_confirm_handler)))
+                .route("/token/refresh", web::post().to(handlers::session_handler::refresh_token_handler))
+                // Authenticates itself with a ticket, EventSource can't send the Authorization header
+                .route("/events", web::get().to(handlers::event_handler::event_stream_handler))
+                .route("/queue/display", web::get().to(handlers::queue_handler::get_queue_display_handler))
+                .route("/calendar/{token}", web::get().to(handlers::calendar_handler::get_calendar_handler))
+                // Rute Booking Online (publik)
+                .service(web::scope("/public")
+                    .wrap(public_rate_limit.clone())
+                    .route("/treatments", web::get().to(handlers::booking_handler::get_public_treatments_handler))
+                    .route("/slots", web::get().to(handlers::booking_handler::get_available_slots_handler))
+                    .route("/otp/request", web::post().to(handlers::booking_handler::request_otp_handler))
+                    .route("/otp/verify", web::post().to(handlers::booking_handler::verify_otp_handler))
+                    .route("/bookings", web::post().to(handlers::booking_handler::create_booking_handler))
+                    .route("/bookings/{token}", web::get().to(handlers::booking_handler::get_booking_handler))
+                    .route("/bookings/{token}/cancel", web::post().to(handlers::booking_handler::cancel_booking_handler))
+                    .route("/bookings/{token}/reschedule", web::post().to(handlers::booking_handler::reschedule_booking_handler))
+                    .route("/waitlist", web::post().to(handlers::waitlist_handler::join_waitlist_handler))
+                    .route("/waitlist-offers/{token}", web::get().to(handlers::waitlist_handler::get_public_offer_handler))
+                    .route("/waitlist-offers/{token}/accept", web::post().to(handlers::waitlist_handler::accept_public_offer_handler))
+                    .route("/waitlist-offers/{token}/decline", web::post().to(handlers::waitlist_handler::decline_public_offer_handler)))
+
+                .service(web::scope("")
+                    .wrap(AuthMiddleware::new(jwt_verifier.clone()))
+                    .route("/dashboard", web::get().to(handlers::protected_handler::get_dashboard))
+                    .route("/dokter-data", web::get().to(handlers::protected_handler::get_dokter_data))
+
+                    // Rute Sesi Login
+                    .route("/logout", web::post().to(handlers::session_handler::logout_handler))
+                    .route("/sessions", web::get().to(handlers::session_handler::get_sessions_handler))
+                    .route("/sessions/{id}", web::delete().to(handlers::session_handler::revoke_session_handler))
+                    .route("/users/{id}/sessions", web::delete().to(handlers::session_handler::revoke_user_sessions_handler))
+                    .route("/events/ticket", web::post().to(handlers::event_handler::create_stream_ticket_handler))
+
+                    // Rute Autentikasi Dua Faktor
+                    .route("/account/totp", web::post().to(handlers::totp_handler::start_enrolment_handler))
+                    .route("/account/totp", web::delete().to(handlers::totp_handler::disable_totp_handler))
+                    .route("/account/totp/confirm", web::post().to(handlers::totp_handler::confirm_enrolment_handler))
+                    .route("/account/totp/recovery-codes", web::post().to(handlers::totp_handler::regenerate_recovery_codes_handler))
+                    .route("/users/{id}/totp", web::delete().to(handlers::totp_handler::reset_user_totp_handler))
+
+                    // Rute Manajemen Staf
+                    .route("/users", web::get().to(user_handler::get_users_handler))
+                    .route("/users", web::post().to(user_handler::invite_user_handler))
+                    .route("/users/{id}", web::patch().to(user_handler::update_user_handler))
+                    .route("/users/{id}/deactivate", web::post().to(user_handler::deactivate_user_handler))
+                    .route("/users/{id}/reactivate", web::post().to(user_handler::reactivate_user_handler))
+                    .route("/users/{id}/password-reset", web::post().to(user_handler::force_password_reset_handler))
+
+                    // Rute Produk
+                    .route("/products", web::get().to(product_handler::get_all_products_handler))
+                    .route("/products", web::post().to(product_handler::create_product_handler))
+                    .route("/products/{id}", web::patch().to(product_handler::update_product_handler))
+                    .route("/products/{id}", web::delete().to(product_handler::delete_product_handler))
+                    .route("/products/{id}/restore", web::post().to(product_handler::restore_product_handler))
+                      // Rute Appointment
+                    .route("/appointments", web::get().to(handlers::appointment_handler::get_all_appointments_handler))
+                    .route("/appointments", web::post().to(handlers::appointment_handler::create_appointment_handler))
+                    .route("/appointments/{id}", web::patch().to(handlers::appointment_handler::update_appointment_handler))
+                    .route("/appointments/{id}", web::delete().to(handlers::appointment_handler::delete_appointment_handler))
+                    .route("/appointments/{id}/restore", web::post().to(handlers::appointment_handler::restore_appointment_handler))
+                    .route("/appointment-series", web::post().to(handlers::appointment_series_handler::create_appointment_series_handler))
+                    .route("/appointment-series/{id}", web::get().to(handlers::appointment_series_handler::get_appointment_series_handler))
+                    .route("/appointment-series/{id}/occurrences/{index}", web::patch().to(handlers::appointment_series_handler::update_series_occurrences_handler))
+                    .route("/appointment-series/{id}/occurrences/{index}/cancel", web::post().to(handlers::appointment_series_handler::cancel_series_occurrences_handler))
+                    .service(web::resource("/appointments/{id}/consents")
+                        .app_data(web::JsonConfig::default().limit(consent_service::sign_request_limit()))
+                        .route(web::get().to(handlers::consent_handler::get_appointment_consents_handler))
+                        .route(web::post().to(handlers::consent_handler::sign_consent_handler)))
+                    .route("/appointments/{id}/notifications", web::get().to(handlers::notification_handler::get_appointment_notifications_handler))
+                    .route("/consent-signatures/{id}/image", web::get().to(handlers::consent_handler::get_consent_signature_image_handler))
+                    .route("/consent-templates", web::get().to(handlers::consent_handler::get_all_consent_templates_handler))
+                    .route("/consent-templates", web::post().to(handlers::consent_handler::create_consent_template_handler))
+                    .route("/consent-templates/{id}", web::delete().to(handlers::consent_handler::delete_consent_template_handler))
+                    .route("/consent-templates/{id}/restore", web::post().to(handlers::consent_handler::restore_consent_template_handler))
+                    .route("/consent-templates/{id}/versions", web::post().to(handlers::consent_handler::create_consent_template_version_handler))
+                    // Rute Treatment 
+                    .route("/treatments", web::get().to(treatment_handler::get_all_treatments_handler))
+                    .route("/treatments", web::post().to(treatment_handler::create_treatment_handler))
+                    .route("/treatments/{id}", web::patch().to(treatment_handler::update_treatment_handler))
+                    .route("/treatments/{id}", web::delete().to(treatment_handler::delete_treatment_handler))
+                    .route("/treatments/{id}/restore", web::post().to(treatment_handler::restore_treatment_handler))
+                     // Rute Dokter 
+                    .route("/dokters", web::get().to(dokter_handler::get_all_dokters_handler))
+                    .route("/dokters", web::post().to(dokter_handler::create_dokter_handler))
+                    .route("/dokters/{id}", web::patch().to(dokter_handler::update_dokter_handler))
+                    .route("/dokters/{id}", web::delete().to(dokter_handler::delete_dokter_handler))
+                    .route("/dokters/{id}/restore", web::post().to(dokter_handler::restore_dokter_handler))
+                    .route("/dokters/{id}/calendar-feed", web::post().to(handlers::calendar_handler::create_calendar_feed_handler))
+                    .route("/dokters/{id}/calendar-feed", web::delete().to(handlers::calendar_handler::revoke_calendar_feed_handler))
+                    .route("/dokters/{id}/schedule-exceptions", web::get().to(handlers::schedule_handler::get_schedule_exceptions_handler))
+                    .route("/dokters/{id}/schedule-exceptions", web::post().to(handlers::schedule_handler::create_schedule_exception_handler))
+                    .route("/schedule-exceptions/{id}", web::delete().to(handlers::schedule_handler::delete_schedule_exception_handler))
+                    .route("/schedule-exceptions/{id}/restore", web::post().to(handlers::schedule_handler::restore_schedule_exception_handler))
+                    // Rute Daftar Tunggu
+                    .route("/waitlist", web::get().to(handlers::waitlist_handler::get_waitlist_handler))
+                    .route("/waitlist", web::post().to(handlers::waitlist_handler::create_waitlist_entry_handler))
+                    .route("/waitlist/{id}", web::delete().to(handlers::waitlist_handler::delete_waitlist_entry_handler))
+                    .route("/waitlist/{id}/restore", web::post().to(handlers::waitlist_handler::restore_waitlist_entry_handler))
+                    .route("/waitlist/{id}/offers", web::get().to(handlers::waitlist_handler::get_waitlist_entry_offers_handler))
+                    .route("/waitlist-offers/{id}/accept", web::post().to(handlers::waitlist_handler::accept_waitlist_offer_handler))
+                    .route("/waitlist-offers/{id}/decline", web::post().to(handlers::waitlist_handler::decline_waitlist_offer_handler))
+                    // Rute Ruangan & Alat
+                    .route("/clinic-resources", web::get().to(handlers::clinic_resource_handler::get_all_clinic_resources_handler))
+                    .route("/clinic-resources", web::post().to(handlers::clinic_resource_handler::create_clinic_resource_handler))
+                    .route("/clinic-resources/{id}", web::patch().to(handlers::clinic_resource_handler::update_clinic_resource_handler))
+                    .route("/clinic-resources/{id}", web::delete().to(handlers::clinic_resource_handler::delete_clinic_resource_handler))
+                    .route("/clinic-resources/{id}/restore", web::post().to(handlers::clinic_resource_handler::restore_clinic_resource_handler))
+                    // Rute Hari Libur Klinik
+                    .route("/clinic-holidays", web::get().to(handlers::schedule_handler::get_clinic_holidays_handler))
+                    .route("/clinic-holidays", web::post().to(handlers::schedule_handler::create_clinic_holiday_handler))
+                    .route("/clinic-holidays/{id}", web::delete().to(handlers::schedule_handler::delete_clinic_holiday_handler))
+                    .route("/clinic-holidays/{id}/restore", web::post().to(handlers::schedule_handler::restore_clinic_holiday_handler))
+                     // Rute Pasien
+                    .route("/pasiens", web::get().to(pasien_handler::get_all_pasiens_handler))
+                    .route("/pasiens", web::post().to(pasien_handler::create_pasien_handler))
+                    .route("/pasiens/{id}", web::patch().to(pasien_handler::update_pasien_handler))
+                    .route("/pasiens/{id}", web::delete().to(pasien_handler::delete_pasien_handler))
+                    .route("/pasiens/{id}/restore", web::post().to(pasien_handler::restore_pasien_handler))
+                    .route("/pasiens/{id}/timeline", web::get().to(pasien_handler::get_pasien_timeline_handler))
+                    .route("/pasiens/{id}/photos/before-after", web::get().to(handlers::photo_handler::get_before_after_photos_handler))
+                    .route("/pasiens/{id}/skin-metrics/trend", web::get().to(handlers::skin_analysis_handler::get_skin_metric_trends_handler))
+                    .route("/pasiens/{id}/safety-overrides", web::get().to(handlers::safety_handler::get_pasien_safety_overrides_handler))
+                    .route("/safety-check", web::post().to(handlers::safety_handler::safety_check_handler))
+                    // Rute Treatment Progress
+                    .route("/treatment-progress", web::get().to(handlers::treatment_progress_handler::get_all_treatment_progress_handler))
+                    .route("/treatment-progress", web::post().to(handlers::treatment_progress_handler::create_treatment_progress_handler))
+                    .route("/treatment-progress/{id}", web::patch().to(handlers::treatment_progress_handler::update_treatment_progress_handler))
+                    .route("/treatment-progress/{id}", web::delete().to(handlers::treatment_progress_handler::delete_treatment_progress_handler))
+                    .route("/treatment-progress/{id}/restore", web::post().to(handlers::treatment_progress_handler::restore_treatment_progress_handler))
+                    .route("/treatment-progress/{id}/photos", web::get().to(handlers::photo_handler::get_treatment_progress_photos_handler))
+                    .route("/treatment-progress/{id}/photos", web::post().to(handlers::photo_handler::upload_treatment_progress_photo_handler))
+                    // Rute Skin Analysis
+                    .route("/skin-analyses", web::get().to(handlers::skin_analysis_handler::get_all_skin_analyses_handler))
+                    .route("/skin-analyses", web::post().to(handlers::skin_analysis_handler::create_skin_analysis_handler))
+                    .route("/skin-analyses/{id}", web::patch().to(handlers::skin_analysis_handler::update_skin_analysis_handler))
+                    .route("/skin-analyses/{id}", web::delete().to(handlers::skin_analysis_handler::delete_skin_analysis_handler))
+                    .route("/skin-analyses/{id}/restore", web::post().to(handlers::skin_analysis_handler::restore_skin_analysis_handler))
+                    .route("/skin-analyses/{id}/recommendations", web::get().to(handlers::recommendation_handler::get_recommendations_handler))
+                    .route("/skin-analyses/{id}/recommendations", web::post().to(handlers::recommendation_handler::generate_recommendations_handler))
+                    .route("/skin-analyses/{id}/recommendations/decision", web::post().to(handlers::recommendation_handler::decide_recommendations_handler))
+                    .route("/recommendation-rules", web::get().to(handlers::recommendation_handler::get_all_recommendation_rules_handler))
+                    .route("/recommendation-rules", web::post().to(handlers::recommendation_handler::create_recommendation_rule_handler))
+                    .route("/recommendation-rules/{id}", web::patch().to(handlers::recommendation_handler::update_recommendation_rule_handler))
+                    .route("/recommendation-rules/{id}", web::delete().to(handlers::recommendation_handler::delete_recommendation_rule_handler))
+                    .route("/recommendation-rules/{id}/restore", web::post().to(handlers::recommendation_handler::restore_recommendation_rule_handler))
+                    .route("/skin-analyses/{id}/photos", web::get().to(handlers::photo_handler::get_skin_analysis_photos_handler))
+                    .route("/skin-analyses/{id}/photos", web::post().to(handlers::photo_handler::upload_skin_analysis_photo_handler))
+                    // Rute Foto
+                    .route("/photos/{id}", web::get().to(handlers::photo_handler::get_photo_content_handler))
+                    .route("/photos/{id}/thumbnail", web::get().to(handlers::photo_handler::get_photo_thumbnail_handler))
+                    .route("/photos/{id}", web::delete().to(handlers::photo_handler::delete_photo_handler))
+                     // Rute Invoices
+                    .route("/invoices", web::get().to(handlers::invoice_handler::get_all_invoices_handler))
+                    .route("/invoices", web::post().to(handlers::invoice_handler::create_invoice_handler))
+                    .route("/invoices/{id}", web::patch().to(handlers::invoice_handler::update_invoice_handler))
+                    .route("/invoices/{id}", web::delete().to(handlers::invoice_handler::delete_invoice_handler))
+                    .route("/invoices/{id}/restore", web::post().to(handlers::invoice_handler::restore_invoice_handler))
+                    // Rute Audit Log
+                    .route("/audit-logs", web::get().to(handlers::audit_log_handler::get_audit_logs_handler))
+                    // Rute Antrean Walk-in
+                    .route("/queue", web::get().to(handlers::queue_handler::get_queue_handler))
+                    .route("/queue", web::post().to(handlers::queue_handler::check_in_handler))
+                    .route("/queue/{id}/status", web::patch().to(handlers::queue_handler::update_queue_status_handler))
+                    // Rute Job Background
+                    .route("/jobs", web::get().to(handlers::job_handler::get_all_jobs_handler))
+                    .route("/jobs/{name}", web::patch().to(handlers::job_handler::update_job_handler))
+                    .route("/jobs/{name}/runs", web::get().to(handlers::job_handler::get_job_runs_handler))
+                    .route("/jobs/{name}/run", web::post().to(handlers::job_handler::trigger_job_handler))
+                    // Rute Laporan
+                    .route("/reports/daily", web::get().to(handlers::report_handler::get_daily_reports_handler))
+                    )
                 )
     })
     .bind(format!("{}:{}", host, port))?
@@ -1,23 +1,27 @@
 use actix_web::{
     dev::{Service, ServiceRequest, ServiceResponse, Transform},
-    http::StatusCode,
-    web, Error, HttpResponse,
+    Error, HttpResponse,
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
+use crate::auth::jwt_verifier::JwtVerifier;
+use crate::repositories::{auth_session_repo, user_repo};
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::{
-    env,
     future::{ready, Ready},
     pin::Pin,
+    rc::Rc,
+    sync::Arc,
     task::{Context, Poll},
 };
+use uuid::Uuid;
 use actix_http::{body::{BoxBody, MessageBody, EitherBody}, HttpMessage};
 
 // Claims is the payload of the JWT
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
-    pub role: String,
+    pub role: Option<String>,
+    pub session_id: Option<Uuid>,
     pub exp: usize,
 }
 
@@ -26,10 +30,69 @@ pub struct Claims {
 pub struct AuthenticatedUser {
     pub id: String,
     pub position: String,
+    pub session_id: Option<Uuid>, // None only for actors built outside a request, e.g. at registration
+}
+
+impl AuthenticatedUser {
+    pub fn is_admin(&self) -> bool {
+        self.position == "admin"
+    }
+}
+
+// Validates a JWT and loads the user it belongs to. Shared by the middleware and by
+// endpoints that can't send an Authorization header, such as EventSource streams.
+// The error is the reason sent back with the 401.
+pub async fn authenticate(verifier: &JwtVerifier, token: &str) -> Result<AuthenticatedUser, String> {
+    let claims: Claims = verifier.verify(token).await?;
+    let session_id = claims.session_id.ok_or_else(|| "Token has no session".to_string())?;
+
+    // Position comes from the `users` table, not the token, so role changes apply immediately.
+    // The session is checked on every request so logout and revocation take effect at once.
+    let (user, session) = tokio::join!(user_repo::get_user_by_id(&claims.sub), auth_session_repo::get_session_by_id(session_id));
+    let user = user.map_err(|e| {
+        println!("Failed to load authenticated user: {}", e);
+        "Unknown user".to_string()
+    })?;
+    if user.deactivated_at.is_some() {
+        return Err("Account deactivated".to_string());
+    }
+    let session = session
+        .map_err(|e| {
+            println!("Failed to load session: {}", e);
+            "Unknown session".to_string()
+        })?
+        .filter(|session| session.user_id.to_string() == claims.sub)
+        .ok_or_else(|| "Unknown session".to_string())?;
+    if session.revoked_at.is_some() || session.expires_at <= Utc::now() {
+        return Err("Session has ended".to_string());
+    }
+    Ok(AuthenticatedUser {
+        id: claims.sub,
+        position: user.position,
+        session_id: Some(session_id),
+    })
+}
+
+// 401 with the reason in the body and in WWW-Authenticate (RFC 6750)
+pub fn unauthorized(reason: &str) -> HttpResponse {
+    HttpResponse::Unauthorized()
+        .insert_header((
+            "WWW-Authenticate",
+            format!("Bearer error=\"invalid_token\", error_description=\"{}\"", reason.replace('"', "'")),
+        ))
+        .body(reason.to_string())
 }
 
 // This struct is the "factory" that creates the middleware instance.
-pub struct AuthMiddleware;
+pub struct AuthMiddleware {
+    verifier: Arc<JwtVerifier>,
+}
+
+impl AuthMiddleware {
+    pub fn new(verifier: Arc<JwtVerifier>) -> AuthMiddleware {
+        AuthMiddleware { verifier }
+    }
+}
 
 impl<S, B> Transform<S, ServiceRequest> for AuthMiddleware
 where
@@ -44,13 +107,14 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ready(Ok(AuthMiddlewareService { service }))
+        ready(Ok(AuthMiddlewareService { service: Rc::new(service), verifier: self.verifier.clone() }))
     }
 }
 
 // This struct is the "service" that will be called for each request.
 pub struct AuthMiddlewareService<S> {
-    service: S,
+    service: Rc<S>,
+    verifier: Arc<JwtVerifier>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddlewareService<S>
@@ -67,48 +131,30 @@ where
         self.service.poll_ready(cx)
     }
 
-    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+    fn call(&self, req: ServiceRequest) -> Self::Future {
         let token_result = req.headers().get("Authorization")
             .and_then(|h| h.to_str().ok())
             .and_then(|s| s.strip_prefix("Bearer "))
             .map(|s| s.to_string());
 
-        if let Some(token) = token_result {
-            let secret_key = env::var("SUPABASE_JWT_SECRET").expect("SUPABASE_JWT_SECRET not set");
-            let decoding_key = DecodingKey::from_secret(secret_key.as_ref());
-            let validation = Validation::new(jsonwebtoken::Algorithm::HS256);
-
-            match decode::<Claims>(&token, &decoding_key, &validation) {
-                Ok(token_data) => {
-                    // Placeholder: fetch `position` from the database using `token_data.claims.sub`
-                    let user_position = "resepsionis".to_string(); 
-                    
-                    let auth_user = AuthenticatedUser {
-                        id: token_data.claims.sub,
-                        position: user_position,
-                    };
-                    
+        let service = Rc::clone(&self.service);
+        let verifier = self.verifier.clone();
+        Box::pin(async move {
+            let auth_user = match token_result {
+                Some(token) => authenticate(&verifier, &token).await,
+                None => Err("Missing bearer token".to_string()),
+            };
+            match auth_user {
+                Ok(auth_user) => {
                     req.extensions_mut().insert(auth_user);
-                    
-                    let fut = self.service.call(req);
-                    Box::pin(async move {
-                        let res = fut.await?;
-                        Ok(res.map_into_left_body())
-                    })
+                    let res = service.call(req).await?;
+                    Ok(res.map_into_left_body())
                 }
                 Err(e) => {
-                    println!("JWT Validation Error: {:?}", e);
-                    Box::pin(async move {
-                        let response = HttpResponse::Unauthorized().finish();
-                        Ok(ServiceResponse::new(req.request().clone(), response.map_into_right_body()))
-                    })
+                    let response = unauthorized(&e);
+                    Ok(ServiceResponse::new(req.request().clone(), response.map_into_right_body()))
                 }
             }
-        } else {
-            Box::pin(async move {
-                let response = HttpResponse::Unauthorized().finish();
-                Ok(ServiceResponse::new(req.request().clone(), response.map_into_right_body()))
-            })
-        }
+        })
     }
-}
\ No newline at end of file
+}
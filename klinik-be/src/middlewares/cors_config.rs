@@ -0,0 +1,169 @@
+use crate::middlewares::security_headers_middleware::is_production;
+use actix_cors::Cors;
+use actix_web::http::{header::HeaderName, Method};
+use std::env;
+
+const DEFAULT_METHODS: &str = "GET,POST,PUT,PATCH,DELETE";
+const DEFAULT_HEADERS: &str = "Authorization,Content-Type,Accept";
+// Browsers hide response headers from scripts unless they are exposed
+const DEFAULT_EXPOSE_HEADERS: &str = "Retry-After,Content-Disposition";
+const DEFAULT_MAX_AGE_SECONDS: usize = 3600;
+
+// CORS policy, read and validated once at startup; `build` is called per worker.
+//   CORS_ALLOWED_ORIGINS  comma-separated origins such as https://klinik.example.com; "*" allows
+//                         any origin and is refused when APP_ENV is production. Unset allows none
+//                         in production and any in development.
+//   CORS_ALLOWED_METHODS  default GET,POST,PUT,PATCH,DELETE
+//   CORS_ALLOWED_HEADERS  default Authorization,Content-Type,Accept; "*" allows any
+//   CORS_EXPOSE_HEADERS   default Retry-After,Content-Disposition
+//   CORS_MAX_AGE_SECONDS  preflight cache, default 3600
+#[derive(Clone)]
+pub struct CorsSettings {
+    origins: Option<Vec<String>>, // None means any origin
+    methods: Vec<Method>,
+    headers: Option<Vec<HeaderName>>, // None means any header
+    expose_headers: Vec<HeaderName>,
+    max_age: usize,
+}
+
+fn list(value: Option<String>, default: &str) -> Vec<String> {
+    value
+        .unwrap_or_else(|| default.to_string())
+        .split(',')
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .collect()
+}
+
+fn header_names(key: &str, values: &[String]) -> Result<Vec<HeaderName>, String> {
+    values
+        .iter()
+        .map(|v| HeaderName::from_bytes(v.as_bytes()).map_err(|_| format!("Invalid header in {}: {}", key, v)))
+        .collect()
+}
+
+// An origin is scheme and host (and port) only, exactly as browsers send it
+fn valid_origin(origin: &str) -> bool {
+    let Some(rest) = origin.strip_prefix("https://").or_else(|| origin.strip_prefix("http://")) else {
+        return false;
+    };
+    !rest.is_empty() && !rest.contains('/') && !rest.contains('*')
+}
+
+impl CorsSettings {
+    pub fn from_env() -> Result<CorsSettings, String> {
+        CorsSettings::from_vars(is_production()?, |key| env::var(key).ok())
+    }
+
+    // `var` looks up a setting by name, so the parsing can be exercised without the environment
+    fn from_vars(production: bool, var: impl Fn(&str) -> Option<String>) -> Result<CorsSettings, String> {
+        let origins = match var("CORS_ALLOWED_ORIGINS") {
+            Some(value) => {
+                let origins = list(Some(value.clone()), "");
+                if origins.iter().any(|o| o == "*") {
+                    if production {
+                        return Err("CORS_ALLOWED_ORIGINS=* is not allowed in production; list the frontend origins or set APP_ENV=development".to_string());
+                    }
+                    None
+                } else {
+                    if let Some(invalid) = origins.iter().find(|o| !valid_origin(o)) {
+                        return Err(format!("Invalid origin in CORS_ALLOWED_ORIGINS: {} (from {})", invalid, value));
+                    }
+                    Some(origins)
+                }
+            }
+            None if production => {
+                println!("CORS_ALLOWED_ORIGINS is not set; cross-origin requests will be refused");
+                Some(Vec::new())
+            }
+            None => None,
+        };
+
+        let methods = list(var("CORS_ALLOWED_METHODS"), DEFAULT_METHODS)
+            .iter()
+            .map(|m| Method::from_bytes(m.to_uppercase().as_bytes()).map_err(|_| format!("Invalid method in CORS_ALLOWED_METHODS: {}", m)))
+            .collect::<Result<Vec<_>, String>>()?;
+        let allowed_headers = list(var("CORS_ALLOWED_HEADERS"), DEFAULT_HEADERS);
+        let headers = if allowed_headers.iter().any(|h| h == "*") {
+            None
+        } else {
+            Some(header_names("CORS_ALLOWED_HEADERS", &allowed_headers)?)
+        };
+        let expose_headers = header_names("CORS_EXPOSE_HEADERS", &list(var("CORS_EXPOSE_HEADERS"), DEFAULT_EXPOSE_HEADERS))?;
+        let max_age = match var("CORS_MAX_AGE_SECONDS") {
+            Some(v) => v.trim().parse().map_err(|_| format!("Invalid CORS_MAX_AGE_SECONDS: {}", v))?,
+            None => DEFAULT_MAX_AGE_SECONDS,
+        };
+        Ok(CorsSettings { origins, methods, headers, expose_headers, max_age })
+    }
+
+    pub fn build(&self) -> Cors {
+        let mut cors = Cors::default()
+            .allowed_methods(self.methods.clone())
+            .max_age(self.max_age);
+        cors = match &self.origins {
+            Some(origins) => origins.iter().fold(cors, |cors, origin| cors.allowed_origin(origin)),
+            None => cors.allow_any_origin(),
+        };
+        cors = match &self.headers {
+            Some(headers) => cors.allowed_headers(headers.clone()),
+            None => cors.allow_any_header(),
+        };
+        if !self.expose_headers.is_empty() {
+            cors = cors.expose_headers(self.expose_headers.clone());
+        }
+        cors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn settings(production: bool, vars: &[(&str, &str)]) -> Result<CorsSettings, String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        CorsSettings::from_vars(production, |key| vars.get(key).cloned())
+    }
+
+    #[test]
+    fn unset_origins_allow_none_in_production_and_any_in_development() {
+        assert_eq!(settings(true, &[]).unwrap().origins, Some(Vec::new()));
+        assert_eq!(settings(false, &[]).unwrap().origins, None);
+    }
+
+    #[test]
+    fn wildcard_origin_is_refused_in_production() {
+        assert!(settings(true, &[("CORS_ALLOWED_ORIGINS", "*")]).is_err());
+        assert_eq!(settings(false, &[("CORS_ALLOWED_ORIGINS", "*")]).unwrap().origins, None);
+    }
+
+    #[test]
+    fn origins_must_be_scheme_and_host_only() {
+        let cors = settings(true, &[("CORS_ALLOWED_ORIGINS", " https://klinik.example.com, http://localhost:3000 ,")]).unwrap();
+        assert_eq!(
+            cors.origins,
+            Some(vec!["https://klinik.example.com".to_string(), "http://localhost:3000".to_string()])
+        );
+        for invalid in ["klinik.example.com", "https://klinik.example.com/", "https://*.example.com", "https://"] {
+            assert!(settings(true, &[("CORS_ALLOWED_ORIGINS", invalid)]).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn methods_headers_and_max_age_have_defaults_and_are_validated() {
+        let cors = settings(true, &[]).unwrap();
+        assert_eq!(cors.methods.len(), 5);
+        assert_eq!(cors.headers.map(|h| h.len()), Some(3));
+        assert_eq!(cors.expose_headers.len(), 2);
+        assert_eq!(cors.max_age, DEFAULT_MAX_AGE_SECONDS);
+
+        let cors = settings(true, &[("CORS_ALLOWED_METHODS", "get, post"), ("CORS_ALLOWED_HEADERS", "*"), ("CORS_MAX_AGE_SECONDS", "60")]).unwrap();
+        assert_eq!(cors.methods, vec![Method::GET, Method::POST]);
+        assert!(cors.headers.is_none());
+        assert_eq!(cors.max_age, 60);
+
+        assert!(settings(true, &[("CORS_ALLOWED_HEADERS", "bad header")]).is_err());
+        assert!(settings(true, &[("CORS_MAX_AGE_SECONDS", "an hour")]).is_err());
+    }
+}
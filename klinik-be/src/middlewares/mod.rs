@@ -1 +1,4 @@
-pub mod auth_middleware;
\ No newline at end of file
+pub mod auth_middleware;
+pub mod rate_limit_middleware;
+pub mod cors_config;
+pub mod security_headers_middleware;
\ No newline at end of file
@@ -0,0 +1,215 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    Error, HttpRequest, HttpResponse,
+};
+use actix_http::body::{BoxBody, EitherBody, MessageBody};
+use std::{
+    collections::HashMap,
+    env,
+    future::{ready, Ready},
+    net::IpAddr,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+// Above this many tracked clients, expired windows are swept on the next request
+const SWEEP_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started: Instant,
+    count: u32,
+}
+
+// Fixed-window request limit per client IP, kept in memory. Each instance has its own
+// counters, so with several server instances the effective limit is multiplied.
+// Build it once outside `HttpServer::new` and clone it into the app factory so every
+// worker shares the same counters.
+#[derive(Clone)]
+pub struct RateLimit {
+    max_requests: u32,
+    window: Duration,
+    windows: Arc<Mutex<HashMap<String, Window>>>,
+}
+
+impl RateLimit {
+    pub fn new(max_requests: u32, window: Duration) -> RateLimit {
+        RateLimit { max_requests, window, windows: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    // Limits overridable per route with RATE_LIMIT_<NAME>_MAX and RATE_LIMIT_<NAME>_WINDOW_SECONDS
+    pub fn from_env(name: &str, default_max: u32, default_window_seconds: u64) -> RateLimit {
+        let setting = |suffix: &str| env::var(format!("RATE_LIMIT_{}_{}", name, suffix)).ok().and_then(|v| v.parse().ok());
+        RateLimit::new(
+            setting("MAX").filter(|max| *max > 0).map(|max: u64| max as u32).unwrap_or(default_max),
+            Duration::from_secs(setting("WINDOW_SECONDS").filter(|secs| *secs > 0).unwrap_or(default_window_seconds)),
+        )
+    }
+
+    // Counts a request against `key`. Returns the seconds until the key may retry, or None if
+    // this request is allowed. Also used directly for buckets that aren't keyed by IP.
+    pub fn check(&self, key: &str) -> Option<u64> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        if windows.len() > SWEEP_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started) < self.window);
+        }
+        let window = windows.entry(key.to_string()).or_insert(Window { started: now, count: 0 });
+        if now.duration_since(window.started) >= self.window {
+            window.started = now;
+            window.count = 0;
+        }
+        window.count += 1;
+        if window.count > self.max_requests {
+            let remaining = self.window.saturating_sub(now.duration_since(window.started));
+            Some(remaining.as_secs().max(1))
+        } else {
+            None
+        }
+    }
+}
+
+// Our own reverse proxies, from the comma-separated TRUSTED_PROXIES
+fn trusted_proxies() -> Vec<IpAddr> {
+    env::var("TRUSTED_PROXIES")
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|ip| ip.trim().parse().ok())
+        .collect()
+}
+
+// The right-most X-Forwarded-For hop that isn't one of our proxies. Each proxy appends the
+// address it received the request from, so everything further left was written by the
+// client and can't be believed. None if a hop we'd have to skip past is malformed.
+fn forwarded_client(forwarded_for: &str, trusted: &[IpAddr]) -> Option<IpAddr> {
+    for hop in forwarded_for.rsplit(',') {
+        let ip: IpAddr = hop.trim().parse().ok()?;
+        if !trusted.contains(&ip) {
+            return Some(ip);
+        }
+    }
+    None
+}
+
+// The peer address, or with TRUST_PROXY_HEADERS=true the client address taken from
+// X-Forwarded-For. When TRUSTED_PROXIES is set, the header is only read on requests that
+// come from one of those addresses, and their hops are skipped.
+pub fn client_address(req: &HttpRequest) -> String {
+    let peer = req.peer_addr().map(|addr| addr.ip());
+    let trust_proxy = env::var("TRUST_PROXY_HEADERS").map(|v| v == "true").unwrap_or(false);
+    let forwarded = if trust_proxy {
+        let trusted = trusted_proxies();
+        let from_proxy = trusted.is_empty() || peer.is_some_and(|ip| trusted.contains(&ip));
+        let forwarded_for = req
+            .headers()
+            .get_all("X-Forwarded-For")
+            .filter_map(|value| value.to_str().ok())
+            .collect::<Vec<_>>()
+            .join(",");
+        if from_proxy && !forwarded_for.is_empty() { forwarded_client(&forwarded_for, &trusted) } else { None }
+    } else {
+        None
+    };
+    forwarded.or(peer).map(|ip| ip.to_string()).unwrap_or_else(|| "unknown".to_string())
+}
+
+// 429 with the Retry-After header clients are expected to honour
+pub fn too_many_requests(retry_after: u64, message: &str) -> HttpResponse {
+    HttpResponse::TooManyRequests()
+        .insert_header(("Retry-After", retry_after.to_string()))
+        .body(message.to_string())
+}
+
+fn client_key(req: &ServiceRequest) -> String {
+    client_address(req.request())
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitService { service: Rc::new(service), limit: self.clone() }))
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: Rc<S>,
+    limit: RateLimit,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(retry_after) = self.limit.check(&client_key(&req)) {
+            return Box::pin(async move {
+                let response = too_many_requests(retry_after, "Terlalu banyak permintaan, silakan coba lagi nanti.");
+                Ok(ServiceResponse::new(req.request().clone(), response.map_into_right_body()))
+            });
+        }
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(addr: &str) -> IpAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn allows_up_to_the_limit_per_key() {
+        let limit = RateLimit::new(2, Duration::from_secs(60));
+        assert_eq!(limit.check("a"), None);
+        assert_eq!(limit.check("a"), None);
+        assert!(limit.check("a").is_some_and(|retry_after| (1..=60).contains(&retry_after)));
+        assert_eq!(limit.check("b"), None);
+    }
+
+    #[test]
+    fn a_new_window_starts_over() {
+        let limit = RateLimit::new(1, Duration::from_millis(20));
+        assert_eq!(limit.check("a"), None);
+        assert_eq!(limit.check("a"), Some(1));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(limit.check("a"), None);
+    }
+
+    #[test]
+    fn takes_the_right_most_hop_that_is_not_a_proxy() {
+        assert_eq!(forwarded_client("6.6.6.6, 203.0.113.7", &[]), Some(ip("203.0.113.7")));
+        let proxies = [ip("10.0.0.2"), ip("10.0.0.3")];
+        assert_eq!(forwarded_client("6.6.6.6, 203.0.113.7, 10.0.0.3, 10.0.0.2", &proxies), Some(ip("203.0.113.7")));
+        assert_eq!(forwarded_client("10.0.0.3", &proxies), None);
+        assert_eq!(forwarded_client("203.0.113.7, garbage", &[]), None);
+        assert_eq!(forwarded_client("garbage, 203.0.113.7", &[]), Some(ip("203.0.113.7")));
+    }
+}
@@ -0,0 +1,124 @@
+use actix_web::{
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{HeaderName, HeaderValue},
+    Error,
+};
+use std::{
+    env,
+    future::{ready, Ready},
+    pin::Pin,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+const DEFAULT_HSTS_MAX_AGE_SECONDS: u64 = 365 * 24 * 60 * 60;
+
+// APP_ENV=development relaxes the startup checks (wildcard CORS, no HSTS); any other value is
+// treated as production. It must be set, so a missing variable can't quietly pick either one.
+pub fn is_production() -> Result<bool, String> {
+    match env::var("APP_ENV") {
+        Ok(v) if !v.trim().is_empty() => Ok(v.trim().to_lowercase() != "development"),
+        _ => Err("APP_ENV is not set; set it to production or development".to_string()),
+    }
+}
+
+fn header_value(key: &str, default: &str) -> Result<Option<HeaderValue>, String> {
+    let value = env::var(key).unwrap_or_else(|_| default.to_string());
+    if value.trim().is_empty() || value.trim() == "off" {
+        return Ok(None);
+    }
+    HeaderValue::from_str(value.trim())
+        .map(Some)
+        .map_err(|_| format!("Invalid {}: {}", key, value))
+}
+
+// Adds security headers to every response, unless the handler already set one:
+//   Strict-Transport-Security  SECURITY_HSTS_MAX_AGE_SECONDS (default one year, production only; 0 disables)
+//   X-Content-Type-Options     always nosniff
+//   X-Frame-Options            SECURITY_FRAME_OPTIONS, default DENY
+//   Referrer-Policy            SECURITY_REFERRER_POLICY, default no-referrer, since several public
+//                              URLs carry tokens (bookings, calendar feeds)
+// Setting SECURITY_FRAME_OPTIONS or SECURITY_REFERRER_POLICY to "off" leaves the header out.
+#[derive(Clone)]
+pub struct SecurityHeaders {
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl SecurityHeaders {
+    pub fn from_env() -> Result<SecurityHeaders, String> {
+        let mut headers = vec![(HeaderName::from_static("x-content-type-options"), HeaderValue::from_static("nosniff"))];
+        let production = is_production()?;
+        let hsts_max_age = match env::var("SECURITY_HSTS_MAX_AGE_SECONDS") {
+            Ok(v) => v.trim().parse().map_err(|_| format!("Invalid SECURITY_HSTS_MAX_AGE_SECONDS: {}", v))?,
+            Err(_) if production => DEFAULT_HSTS_MAX_AGE_SECONDS,
+            Err(_) => 0,
+        };
+        if hsts_max_age > 0 {
+            headers.push((
+                HeaderName::from_static("strict-transport-security"),
+                HeaderValue::from_str(&format!("max-age={}; includeSubDomains", hsts_max_age))
+                    .map_err(|e| format!("Invalid HSTS header: {}", e))?,
+            ));
+        }
+        if let Some(value) = header_value("SECURITY_FRAME_OPTIONS", "DENY")? {
+            headers.push((HeaderName::from_static("x-frame-options"), value));
+        }
+        if let Some(value) = header_value("SECURITY_REFERRER_POLICY", "no-referrer")? {
+            headers.push((HeaderName::from_static("referrer-policy"), value));
+        }
+        Ok(SecurityHeaders { headers: Arc::new(headers) })
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for SecurityHeaders
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = SecurityHeadersService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(SecurityHeadersService { service: Rc::new(service), headers: self.headers.clone() }))
+    }
+}
+
+pub struct SecurityHeadersService<S> {
+    service: Rc<S>,
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl<S, B> Service<ServiceRequest> for SecurityHeadersService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = Pin<Box<dyn std::future::Future<Output = Result<Self::Response, Self::Error>>>>;
+
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let service = Rc::clone(&self.service);
+        let headers = self.headers.clone();
+        Box::pin(async move {
+            let mut res = service.call(req).await?;
+            let response_headers = res.headers_mut();
+            for (name, value) in headers.iter() {
+                if !response_headers.contains_key(name) {
+                    response_headers.insert(name.clone(), value.clone());
+                }
+            }
+            Ok(res)
+        })
+    }
+}
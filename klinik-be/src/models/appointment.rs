@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::models::nullable::null_as_default;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
@@ -15,5 +16,42 @@ pub struct Appointment {
     pub is_initial_skin_analysis: bool,
     pub skin_analysis_id: Option<Uuid>,
     pub treatment_progress_id: Option<Uuid>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub resource_ids: Vec<Uuid>, // rooms and equipment allocated at booking
+    // Set when a leave or holiday is added over the appointment's time; cleared once it is moved
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub needs_reschedule: bool,
+    pub reschedule_reason: Option<String>,
+    pub series_id: Option<Uuid>, // set for occurrences of a recurring series
+    pub series_index: Option<i32>, // 1-based position in the series
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// A booked appointment with fresh ids and nothing optional set; tests override the rest
+#[cfg(test)]
+impl Appointment {
+    pub fn fixture() -> Appointment {
+        Appointment {
+            id: Uuid::new_v4(),
+            pasien_id: Uuid::new_v4(),
+            dokter_id: Uuid::new_v4(),
+            treatment_ids: Value::Array(Vec::new()),
+            tanggal: "2026-03-02".to_string(),
+            waktu: "10:00".to_string(),
+            status: "booked".to_string(),
+            is_initial_skin_analysis: false,
+            skin_analysis_id: None,
+            treatment_progress_id: None,
+            resource_ids: Vec::new(),
+            needs_reschedule: false,
+            reschedule_reason: None,
+            series_id: None,
+            series_index: None,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// The pattern a course of treatment was booked with. The occurrences themselves are
+// ordinary appointments carrying series_id and series_index; once booked they are the
+// source of truth and may drift from this pattern through edits.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AppointmentSeries {
+    pub id: Uuid,
+    pub pasien_id: Uuid,
+    pub dokter_id: Uuid,
+    pub treatment_ids: Vec<Uuid>,
+    pub start_tanggal: String,
+    pub waktu: String,
+    pub interval_days: i32,
+    pub occurrences: i32,
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub actor_position: Option<String>,
+    pub resource: String,
+    pub record_id: Uuid,
+    pub action: String,
+    pub before: Value,
+    pub after: Value,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// An email verification code or password reset token of the local auth provider.
+// Only its HMAC is stored.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AuthCode {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub purpose: String, // "email_verification" | "password_reset"
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+use chrono::{DateTime, Utc};
+
+// Password and verification state of a user under the local auth provider.
+// Kept out of `users` so the hash never ends up in API responses or audit logs.
+#[derive(Debug, Deserialize)]
+pub struct AuthCredential {
+    pub password_hash: String,
+    pub email_verified_at: Option<DateTime<Utc>>,
+}
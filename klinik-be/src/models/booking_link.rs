@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// The secret behind a patient's cancel/reschedule link; only its HMAC is stored
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BookingLink {
+    pub id: Uuid,
+    pub appointment_id: Uuid,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+}
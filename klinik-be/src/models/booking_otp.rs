@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A one-time code sent to a phone number for public self-booking. Only its HMAC is stored.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BookingOtp {
+    pub id: Uuid,
+    pub no_telepon: String,
+    pub code_hash: String,
+    pub attempts: i32,
+    pub expires_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// Access to one doctor's .ics feed. The URL carries the token; only its SHA-256 is stored.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CalendarFeed {
+    pub id: Uuid,
+    pub dokter_id: Uuid,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
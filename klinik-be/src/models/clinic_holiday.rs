@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A day the whole clinic is closed, e.g. a national holiday
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClinicHoliday {
+    pub id: Uuid,
+    pub tanggal: String, // YYYY-MM-DD
+    pub nama: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A room or machine that only one appointment can use at a time. Treatments name the
+// categories they need; booking picks a free resource of each category.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClinicResource {
+    pub id: Uuid,
+    pub name: String,
+    pub kind: String, // "room" or "equipment"
+    pub category: String, // e.g. "laser_room", "hifu"
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// An active room; tests set the category they allocate from
+#[cfg(test)]
+impl ClinicResource {
+    pub fn fixture() -> ClinicResource {
+        ClinicResource {
+            id: Uuid::new_v4(),
+            name: "Ruang 1".to_string(),
+            kind: "room".to_string(),
+            category: "laser_room".to_string(),
+            is_active: true,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A signed consent for one appointment. Never updated or deleted once written.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ConsentSignature {
+    pub id: Uuid,
+    pub appointment_id: Uuid,
+    pub pasien_id: Uuid,
+    pub template_id: Uuid,
+    pub template_code: String,
+    pub template_version: i32,
+    pub signer_name: String,
+    pub signer_relation: Option<String>, // e.g. "pasien" or "wali" when signed by a guardian
+    pub signature_method: String, // "typed" or "image"
+    pub signature_image_key: Option<String>,
+    pub witnessed_by: Uuid,
+    pub signed_at: DateTime<Utc>,
+}
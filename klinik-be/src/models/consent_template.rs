@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A versioned informed-consent form. Every version is its own row sharing `code`;
+// editing a form publishes a new version so signed consents keep the text they saw.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ConsentTemplate {
+    pub id: Uuid,
+    pub code: String,
+    pub version: i32,
+    pub title: String,
+    pub body: String,
+    pub treatment_ids: Vec<Uuid>, // treatments that require this consent
+    pub is_active: bool, // only the latest version of a code is active
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
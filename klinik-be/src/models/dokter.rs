@@ -9,4 +9,15 @@ pub struct Dokter {
     pub posisi: String,
     pub jadwal: serde_json::Value,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// One entry of `jadwal`, as the frontend stores it: { day: "Senin", startTime: "09:00", endTime: "17:00" }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySchedule {
+    pub day: String,
+    pub start_time: String,
+    pub end_time: String,
+}
@@ -18,4 +18,6 @@ pub struct Invoice {
     pub kasir_name: Option<String>,
     pub appointment_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
 }
\ No newline at end of file
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// Persistent state of a registered background job, keyed by its name
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Job {
+    pub id: Uuid,
+    pub name: String,
+    pub schedule: String, // cron expression with seconds, in clinic local time
+    pub is_enabled: bool,
+    pub next_run_at: Option<DateTime<Utc>>,
+    pub last_run_at: Option<DateTime<Utc>>,
+    pub last_status: Option<String>, // "succeeded" or "failed"
+    pub created_at: DateTime<Utc>,
+}
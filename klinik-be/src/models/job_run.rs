@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+// One attempt at running a job
+#[derive(Debug, Deserialize, Serialize)]
+pub struct JobRun {
+    pub id: Uuid,
+    pub job_name: String,
+    pub attempt: i32,
+    pub status: String, // "running", "succeeded" or "failed"
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub error: Option<String>,
+    pub output: Option<Value>,
+}
@@ -6,4 +6,31 @@ pub mod pasien;
 pub mod appointment;
 pub mod treatment_progress;
 pub mod skin_analysis;
-pub mod invoice;
\ No newline at end of file
+pub mod nullable;
+pub mod invoice;
+pub mod audit_log;
+pub mod photo;
+pub mod recommendation_rule;
+pub mod recommendation_suggestion;
+pub mod safety_override;
+pub mod consent_template;
+pub mod consent_signature;
+pub mod notification_delivery;
+pub mod job;
+pub mod job_run;
+pub mod report_snapshot;
+pub mod schedule_exception;
+pub mod clinic_holiday;
+pub mod clinic_resource;
+pub mod queue_entry;
+pub mod booking_otp;
+pub mod booking_link;
+pub mod waitlist_entry;
+pub mod waitlist_offer;
+pub mod appointment_series;
+pub mod calendar_feed;
+pub mod auth_credential;
+pub mod auth_code;
+pub mod auth_session;
+pub mod user_totp;
+pub mod totp_recovery_code;
\ No newline at end of file
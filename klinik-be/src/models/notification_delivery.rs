@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// One message to one patient over one channel, with its delivery attempts
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NotificationDelivery {
+    pub id: Uuid,
+    pub appointment_id: Uuid,
+    pub pasien_id: Uuid,
+    pub kind: String, // "reminder_h1", "reminder_2h" or "follow_up"
+    pub scheduled_for: Option<DateTime<Utc>>, // appointment start the message was written for
+    pub channel: String, // "whatsapp", "sms" or "email"
+    pub recipient: String,
+    pub subject: String,
+    pub body: String,
+    pub status: String, // "pending", "sent", "failed" or "cancelled"
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// A pending day-before WhatsApp reminder; tests override the rest
+#[cfg(test)]
+impl NotificationDelivery {
+    pub fn fixture() -> NotificationDelivery {
+        NotificationDelivery {
+            id: Uuid::new_v4(),
+            appointment_id: Uuid::new_v4(),
+            pasien_id: Uuid::new_v4(),
+            kind: "reminder_h1".to_string(),
+            scheduled_for: None,
+            channel: "whatsapp".to_string(),
+            recipient: "081234567890".to_string(),
+            subject: String::new(),
+            body: String::new(),
+            status: "pending".to_string(),
+            attempts: 0,
+            last_error: None,
+            next_attempt_at: None,
+            sent_at: None,
+            created_at: DateTime::UNIX_EPOCH,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Deserializer};
+
+// `#[serde(default)]` only covers a missing key. PostgREST always returns every column, and
+// rows written before a column was added hold null, so columns added later read null as the
+// default too: `#[serde(default, deserialize_with = "null_as_default")]`.
+pub fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Row {
+        #[serde(default, deserialize_with = "null_as_default")]
+        values: Vec<String>,
+    }
+
+    #[test]
+    fn null_missing_and_present_values() {
+        let null: Row = serde_json::from_str(r#"{"values": null}"#).unwrap();
+        assert!(null.values.is_empty());
+        let missing: Row = serde_json::from_str("{}").unwrap();
+        assert!(missing.values.is_empty());
+        let present: Row = serde_json::from_str(r#"{"values": ["a"]}"#).unwrap();
+        assert_eq!(present.values, vec!["a".to_string()]);
+    }
+}
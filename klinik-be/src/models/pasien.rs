@@ -26,4 +26,37 @@ pub struct Pasien {
     pub setuju_data: Option<bool>,
     pub has_initial_skin_analysis: Option<bool>,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// A patient with only the required columns filled in; tests override the rest
+#[cfg(test)]
+impl Pasien {
+    pub fn fixture() -> Pasien {
+        Pasien {
+            id: Uuid::new_v4(),
+            nama_lengkap: "Ana".to_string(),
+            no_telepon: "081234567890".to_string(),
+            email: None,
+            tanggal_lahir: None,
+            jenis_kelamin: None,
+            alamat_lengkap: None,
+            riwayat_alergi: None,
+            kondisi_medis: None,
+            obat_konsumsi: None,
+            riwayat_treatment: None,
+            keluhan_utama: None,
+            no_identitas: None,
+            kontak_darurat_nama: None,
+            kontak_darurat_hubungan: None,
+            nomer_kontak_darurat: None,
+            preferensi_komunikasi: Value::Array(Vec::new()),
+            setuju_data: None,
+            has_initial_skin_analysis: None,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Photo {
+    pub id: Uuid,
+    pub pasien_id: Uuid,
+    pub skin_analysis_id: Option<Uuid>,
+    pub treatment_progress_id: Option<Uuid>,
+    pub tanggal: String,
+    pub storage_key: String,
+    pub thumbnail_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub uploaded_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
@@ -1,6 +1,8 @@
 // src/models/product.rs
 use serde::{Deserialize, Serialize};
+use crate::models::nullable::null_as_default;
 use uuid::Uuid;
+use chrono::{DateTime, Utc};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Product {
@@ -9,4 +11,11 @@ pub struct Product {
     pub description: String,
     pub price: f64,
     pub stock: i32,
+    pub expiry_date: Option<String>, // nearest expiry of the stock on hand, YYYY-MM-DD
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub ingredients: Vec<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub contraindications: Vec<String>, // kondisi atau obat yang tidak boleh dikombinasikan
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
 }
\ No newline at end of file
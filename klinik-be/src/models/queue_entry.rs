@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A patient's place in the day's queue, either a walk-in or a booked patient checking in.
+// Numbers restart at 1 every day and are unique per `tanggal`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct QueueEntry {
+    pub id: Uuid,
+    pub tanggal: String, // YYYY-MM-DD, clinic time
+    pub queue_number: i32,
+    pub pasien_id: Uuid,
+    pub dokter_id: Uuid,
+    pub appointment_id: Option<Uuid>, // None for walk-ins
+    pub treatment_ids: Vec<Uuid>,
+    pub status: String, // waiting, in_treatment, done, cancelled
+    pub checked_in_at: DateTime<Utc>,
+    pub called_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+}
@@ -0,0 +1,54 @@
+use crate::models::skin_analysis::SkinType;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A single finding a rule checks against a skin analysis. Score metrics are the
+// `SkinScores` field names; device metrics match `DeviceMeasurement.metric`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleCondition {
+    ScoreAtLeast { metric: String, value: f64 },
+    ScoreAtMost { metric: String, value: f64 },
+    SkinTypeIn { skin_types: Vec<SkinType> },
+    FitzpatrickBetween { min: i16, max: i16 },
+    DeviceReadingAtLeast { metric: String, value: f64 },
+    DeviceReadingAtMost { metric: String, value: f64 },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecommendationRule {
+    pub id: Uuid,
+    pub name: String,
+    pub conditions: Vec<RuleCondition>, // all must hold
+    pub treatment_ids: Vec<Uuid>,
+    pub product_ids: Vec<Uuid>,
+    pub rationale: String,
+    pub contraindications: Vec<String>, // keywords matched against riwayat_alergi and kondisi_medis
+    pub priority: i32,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// An active rule with no conditions, targets or contraindications; tests fill in the rest
+#[cfg(test)]
+impl RecommendationRule {
+    pub fn fixture() -> RecommendationRule {
+        RecommendationRule {
+            id: Uuid::new_v4(),
+            name: "Rule".to_string(),
+            conditions: Vec::new(),
+            treatment_ids: Vec::new(),
+            product_ids: Vec::new(),
+            rationale: String::new(),
+            contraindications: Vec::new(),
+            priority: 0,
+            is_active: true,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RecommendationSuggestion {
+    pub id: Uuid,
+    pub skin_analysis_id: Uuid,
+    pub pasien_id: Uuid,
+    pub rule_ids: Vec<Uuid>,
+    pub item_type: String, // "treatment" or "product"
+    pub item_id: Uuid,
+    pub rationale: String,
+    pub status: String, // "pending", "accepted", "rejected" or "superseded"
+    pub decided_by: Option<Uuid>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub override_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
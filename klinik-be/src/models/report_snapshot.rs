@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+// Figures for one clinic day, frozen by the nightly job so later edits don't rewrite history
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReportSnapshot {
+    pub id: Uuid,
+    pub report_date: String,
+    pub metrics: Value,
+    pub created_at: DateTime<Utc>,
+}
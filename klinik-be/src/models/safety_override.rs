@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetySeverity {
+    Blocking, // an ingredient the patient is allergic to, cannot be overridden
+    Warning,  // a contraindication, allowed once acknowledged
+}
+
+// One match between an item and the patient's recorded allergies, conditions or medication
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SafetyFinding {
+    pub severity: SafetySeverity,
+    pub item_type: String, // "treatment" or "product"
+    pub item_id: Uuid,
+    pub item_name: String,
+    pub keyword: String,
+    pub source: String, // "riwayat_alergi", "kondisi_medis" or "obat_konsumsi"
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SafetyOverride {
+    pub id: Uuid,
+    pub pasien_id: Uuid,
+    pub resource: String, // "appointment" or "invoice"
+    pub record_id: Uuid,
+    pub findings: Vec<SafetyFinding>,
+    pub reason: String,
+    pub overridden_by: Uuid,
+    pub overridden_by_position: String,
+    pub created_at: DateTime<Utc>,
+}
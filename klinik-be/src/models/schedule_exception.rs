@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A dated change to a doctor's weekly `jadwal`: leave (the whole day, or only between
+// start_time and end_time) or an extra shift on a day they don't normally work.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScheduleException {
+    pub id: Uuid,
+    pub dokter_id: Uuid,
+    pub tanggal: String, // YYYY-MM-DD
+    pub kind: String, // "leave" or "extra_shift"
+    pub start_time: Option<String>, // HH:MM
+    pub end_time: Option<String>,
+    pub reason: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// A whole-day leave; tests override the date, kind and hours
+#[cfg(test)]
+impl ScheduleException {
+    pub fn fixture() -> ScheduleException {
+        ScheduleException {
+            id: Uuid::new_v4(),
+            dokter_id: Uuid::new_v4(),
+            tanggal: "2026-03-02".to_string(),
+            kind: "leave".to_string(),
+            start_time: None,
+            end_time: None,
+            reason: None,
+            created_by: None,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
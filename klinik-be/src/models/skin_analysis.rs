@@ -1,8 +1,39 @@
 use serde::{Deserialize, Serialize};
+use crate::models::nullable::null_as_default;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SkinType {
+    Normal,
+    Dry,
+    Oily,
+    Combination,
+    Sensitive,
+}
+
+// Per-metric scores on a 0-100 scale, filled from the doctor's assessment or the device
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct SkinScores {
+    pub acne: Option<f64>,
+    pub pigmentation: Option<f64>,
+    pub wrinkles: Option<f64>,
+    pub pores: Option<f64>,
+    pub hydration: Option<f64>,
+    pub sebum: Option<f64>,
+}
+
+// A raw reading from an analysis device, e.g. { metric: "moisture", value: 42.5, unit: "%" }
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DeviceMeasurement {
+    pub metric: String,
+    pub value: f64,
+    pub unit: String,
+    pub device: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SkinAnalysis {
     pub id: Uuid,
@@ -11,8 +42,40 @@ pub struct SkinAnalysis {
     pub tanggal_analisis: String,
     pub hasil_visual: Option<String>,
     pub hasil_alat: Option<String>,
+    pub skin_type: Option<SkinType>,
+    pub fitzpatrick_type: Option<i16>,
+    pub scores: Option<SkinScores>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub device_readings: Vec<DeviceMeasurement>,
     pub rekomendasi_treatment: Value,
     pub rekomendasi_produk: Value,
     pub catatan_tambahan: Option<String>,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// An analysis with no findings recorded yet; tests fill in the ones they check
+#[cfg(test)]
+impl SkinAnalysis {
+    pub fn fixture() -> SkinAnalysis {
+        SkinAnalysis {
+            id: Uuid::new_v4(),
+            pasien_id: Uuid::new_v4(),
+            appointment_id: Uuid::new_v4(),
+            tanggal_analisis: "2026-03-02".to_string(),
+            hasil_visual: None,
+            hasil_alat: None,
+            skin_type: None,
+            fitzpatrick_type: None,
+            scores: None,
+            device_readings: Vec::new(),
+            rekomendasi_treatment: Value::Array(Vec::new()),
+            rekomendasi_produk: Value::Array(Vec::new()),
+            catatan_tambahan: None,
+            created_at: DateTime::UNIX_EPOCH,
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
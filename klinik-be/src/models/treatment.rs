@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::models::nullable::null_as_default;
 use uuid::Uuid;
+use chrono::{DateTime, Utc};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Treatment {
@@ -8,4 +10,31 @@ pub struct Treatment {
     pub description: String,
     pub price: f64,
     pub estimated_time: i32,
-}
\ No newline at end of file
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub ingredients: Vec<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub contraindications: Vec<String>, // kondisi atau obat yang tidak boleh dikombinasikan
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub required_resources: Vec<String>, // kategori ruangan/alat, e.g. ["laser_room", "hifu"]
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
+
+// A 30-minute treatment with no ingredients or resource needs; tests fill in the rest
+#[cfg(test)]
+impl Treatment {
+    pub fn fixture() -> Treatment {
+        Treatment {
+            id: Uuid::new_v4(),
+            name: "Facial".to_string(),
+            description: String::new(),
+            price: 0.0,
+            estimated_time: 30,
+            ingredients: Vec::new(),
+            contraindications: Vec::new(),
+            required_resources: Vec::new(),
+            deleted_at: None,
+            deleted_by: None,
+        }
+    }
+}
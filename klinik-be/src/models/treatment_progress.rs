@@ -10,4 +10,6 @@ pub struct TreatmentProgress {
     pub tanggal_progress: String,
     pub catatan: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
 }
\ No newline at end of file
@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
+use chrono::{DateTime, Utc};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct User {
@@ -7,4 +8,6 @@ pub struct User {
     pub name: String,
     pub email: String,
     pub position: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub deactivated_at: Option<DateTime<Utc>>, // deactivated accounts can't log in; their row stays for history
+}
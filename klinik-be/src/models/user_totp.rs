@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use chrono::{DateTime, Utc};
+
+// A user's TOTP secret. Unconfirmed until the first code from the authenticator app checks
+// out; never serialized into responses or audit logs.
+#[derive(Debug, Deserialize)]
+pub struct UserTotp {
+    pub secret: String,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub last_used_step: Option<i64>, // time step of the last accepted code, against replays
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A patient waiting for a slot with a doctor (or any doctor) for the given treatments
+// between date_from and date_to. Entries are offered freed slots first come, first served.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WaitlistEntry {
+    pub id: Uuid,
+    pub pasien_id: Uuid,
+    pub dokter_id: Option<Uuid>, // None: any doctor
+    pub treatment_ids: Vec<Uuid>,
+    pub date_from: String, // YYYY-MM-DD
+    pub date_to: String,
+    pub status: String, // "waiting", "offered", "booked", "cancelled" or "expired"
+    pub notes: Option<String>,
+    pub created_by: Option<Uuid>, // None when the patient joined online
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deleted_by: Option<Uuid>,
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use chrono::{DateTime, Utc};
+
+// A freed slot offered to a waitlisted patient. The slot is held by an appointment in status
+// `held` until the patient accepts (it becomes `booked`) or the offer is declined or expires.
+// The link token's hash stays in the table and is never loaded.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WaitlistOffer {
+    pub id: Uuid,
+    pub waitlist_entry_id: Uuid,
+    pub appointment_id: Uuid,
+    pub dokter_id: Uuid,
+    pub tanggal: String,
+    pub waktu: String,
+    pub status: String, // "pending", "accepted", "declined" or "expired"
+    pub expires_at: DateTime<Utc>,
+    pub responded_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
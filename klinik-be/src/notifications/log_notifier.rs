@@ -0,0 +1,27 @@
+use crate::notifications::notifier::{Channel, Notification, Notifier};
+use async_trait::async_trait;
+
+// Prints messages instead of sending them, for development and testing
+pub struct LogNotifier {
+    channel: Channel,
+}
+
+impl LogNotifier {
+    pub fn new(channel: Channel) -> Self {
+        LogNotifier { channel }
+    }
+}
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        println!(
+            "[{}] to {}: {} | {}",
+            self.channel.as_str(),
+            notification.recipient,
+            notification.subject,
+            notification.body
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod notifier;
+pub mod log_notifier;
+pub mod smtp_notifier;
+pub mod whatsapp_notifier;
+pub mod sms_notifier;
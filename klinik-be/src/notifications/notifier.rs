@@ -0,0 +1,96 @@
+use crate::notifications::log_notifier::LogNotifier;
+use crate::notifications::sms_notifier::SmsNotifier;
+use crate::notifications::smtp_notifier::SmtpNotifier;
+use crate::notifications::whatsapp_notifier::WhatsAppNotifier;
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+
+pub struct Notification {
+    pub recipient: String, // phone number or email address, depending on the channel
+    pub subject: String,
+    pub body: String,
+}
+
+// Delivers a message over one channel. Errors are returned, not retried: retries and
+// bookkeeping are the scheduler's job.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn send(&self, notification: &Notification) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Whatsapp,
+    Sms,
+    Email,
+}
+
+impl Channel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Channel::Whatsapp => "whatsapp",
+            Channel::Sms => "sms",
+            Channel::Email => "email",
+        }
+    }
+
+    // Accepts the labels staff type into preferensi_komunikasi ("WhatsApp", "WA", "E-mail", ...)
+    pub fn parse(value: &str) -> Option<Channel> {
+        match value.trim().to_lowercase().replace(['-', ' '], "").as_str() {
+            "whatsapp" | "wa" => Some(Channel::Whatsapp),
+            "sms" => Some(Channel::Sms),
+            "email" | "surel" => Some(Channel::Email),
+            _ => None,
+        }
+    }
+}
+
+pub struct Notifiers {
+    pub whatsapp: Arc<dyn Notifier>,
+    pub sms: Arc<dyn Notifier>,
+    pub email: Arc<dyn Notifier>,
+}
+
+impl Notifiers {
+    pub fn for_channel(&self, channel: Channel) -> &dyn Notifier {
+        match channel {
+            Channel::Whatsapp => self.whatsapp.as_ref(),
+            Channel::Sms => self.sms.as_ref(),
+            Channel::Email => self.email.as_ref(),
+        }
+    }
+}
+
+// Indonesian numbers are stored as typed ("0812...", "+62 812-..."); providers want
+// international digits ("62812...").
+pub fn normalize_phone(phone: &str) -> String {
+    let digits: String = phone.chars().filter(|c| c.is_ascii_digit()).collect();
+    match digits.strip_prefix('0') {
+        Some(rest) => format!("62{}", rest),
+        None => digits,
+    }
+}
+
+// Each channel picks its backend from {CHANNEL}_NOTIFIER, defaulting to the log-only adapter
+// so a fresh setup never messages real patients.
+pub fn from_env() -> Result<Notifiers, String> {
+    let backend = |name: &str| env::var(name).unwrap_or_else(|_| "log".to_string());
+
+    let whatsapp: Arc<dyn Notifier> = match backend("WHATSAPP_NOTIFIER").as_str() {
+        "log" => Arc::new(LogNotifier::new(Channel::Whatsapp)),
+        "cloud_api" => Arc::new(WhatsAppNotifier::from_env()?),
+        other => return Err(format!("Unknown WHATSAPP_NOTIFIER backend: {}", other)),
+    };
+    let sms: Arc<dyn Notifier> = match backend("SMS_NOTIFIER").as_str() {
+        "log" => Arc::new(LogNotifier::new(Channel::Sms)),
+        "twilio" => Arc::new(SmsNotifier::from_env()?),
+        other => return Err(format!("Unknown SMS_NOTIFIER backend: {}", other)),
+    };
+    let email: Arc<dyn Notifier> = match backend("EMAIL_NOTIFIER").as_str() {
+        "log" => Arc::new(LogNotifier::new(Channel::Email)),
+        "smtp" => Arc::new(SmtpNotifier::from_env()?),
+        other => return Err(format!("Unknown EMAIL_NOTIFIER backend: {}", other)),
+    };
+    Ok(Notifiers { whatsapp, sms, email })
+}
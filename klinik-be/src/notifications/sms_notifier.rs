@@ -0,0 +1,50 @@
+use crate::notifications::notifier::{normalize_phone, Notification, Notifier};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::env;
+
+// SMS through Twilio's Messages API (or any gateway exposing the same form-encoded API)
+pub struct SmsNotifier {
+    client: Client,
+    api_url: String,
+    account_sid: String,
+    auth_token: String,
+    from: String,
+}
+
+impl SmsNotifier {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| env::var(name).map_err(|_| format!("{} not set", name));
+        Ok(SmsNotifier {
+            client: Client::new(),
+            api_url: env::var("TWILIO_API_URL")
+                .unwrap_or_else(|_| "https://api.twilio.com/2010-04-01".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            account_sid: var("TWILIO_ACCOUNT_SID")?,
+            auth_token: var("TWILIO_AUTH_TOKEN")?,
+            from: var("TWILIO_FROM")?,
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmsNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let to = format!("+{}", normalize_phone(&notification.recipient));
+        let res = self
+            .client
+            .post(format!("{}/Accounts/{}/Messages.json", self.api_url, self.account_sid))
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&[("To", to.as_str()), ("From", self.from.as_str()), ("Body", notification.body.as_str())])
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send SMS: {}", e))?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("SMS gateway error: {}", res.text().await.unwrap_or_default()))
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::notifications::notifier::{Notification, Notifier};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::env;
+
+pub struct SmtpNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpNotifier {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| env::var(name).map_err(|_| format!("{} not set", name));
+        let port = env::var("SMTP_PORT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(587);
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&var("SMTP_HOST")?)
+            .map_err(|e| format!("Invalid SMTP_HOST: {}", e))?
+            .port(port)
+            .credentials(Credentials::new(var("SMTP_USERNAME")?, var("SMTP_PASSWORD")?))
+            .build();
+        let from = var("SMTP_FROM")?
+            .parse()
+            .map_err(|e| format!("Invalid SMTP_FROM: {}", e))?;
+        Ok(SmtpNotifier { transport, from })
+    }
+}
+
+#[async_trait]
+impl Notifier for SmtpNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let to: Mailbox = notification
+            .recipient
+            .parse()
+            .map_err(|e| format!("Invalid email address {}: {}", notification.recipient, e))?;
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(&notification.subject)
+            .body(notification.body.clone())
+            .map_err(|e| format!("Failed to build email: {}", e))?;
+        self.transport
+            .send(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| format!("Failed to send email: {}", e))
+    }
+}
@@ -0,0 +1,76 @@
+use crate::notifications::notifier::{normalize_phone, Notification, Notifier};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::json;
+use std::env;
+
+// WhatsApp Business Cloud API. Outside a 24 hour conversation window Meta only delivers
+// pre-approved templates; set WHATSAPP_TEMPLATE_NAME to send the body as the template's
+// single text parameter instead of as a free-form message.
+pub struct WhatsAppNotifier {
+    client: Client,
+    api_url: String,
+    phone_number_id: String,
+    access_token: String,
+    template_name: Option<String>,
+    template_language: String,
+}
+
+impl WhatsAppNotifier {
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| env::var(name).map_err(|_| format!("{} not set", name));
+        Ok(WhatsAppNotifier {
+            client: Client::new(),
+            api_url: env::var("WHATSAPP_API_URL")
+                .unwrap_or_else(|_| "https://graph.facebook.com/v19.0".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            phone_number_id: var("WHATSAPP_PHONE_NUMBER_ID")?,
+            access_token: var("WHATSAPP_ACCESS_TOKEN")?,
+            template_name: env::var("WHATSAPP_TEMPLATE_NAME").ok(),
+            template_language: env::var("WHATSAPP_TEMPLATE_LANGUAGE").unwrap_or_else(|_| "id".to_string()),
+        })
+    }
+}
+
+#[async_trait]
+impl Notifier for WhatsAppNotifier {
+    async fn send(&self, notification: &Notification) -> Result<(), String> {
+        let to = normalize_phone(&notification.recipient);
+        let body = match &self.template_name {
+            Some(name) => json!({
+                "messaging_product": "whatsapp",
+                "to": to,
+                "type": "template",
+                "template": {
+                    "name": name,
+                    "language": { "code": self.template_language },
+                    "components": [{
+                        "type": "body",
+                        "parameters": [{ "type": "text", "text": notification.body }]
+                    }]
+                }
+            }),
+            None => json!({
+                "messaging_product": "whatsapp",
+                "to": to,
+                "type": "text",
+                "text": { "body": notification.body }
+            }),
+        };
+        let res = self
+            .client
+            .post(format!("{}/{}/messages", self.api_url, self.phone_number_id))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to send WhatsApp message: {}", e))?;
+
+        if res.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("WhatsApp API error: {}", res.text().await.unwrap_or_default()))
+        }
+    }
+}
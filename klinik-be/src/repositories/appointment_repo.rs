@@ -1,6 +1,8 @@
 use crate::dtos::appointment_dto::{CreateAppointmentDto, UpdateAppointmentDto};
 use crate::models::appointment::Appointment;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -13,10 +15,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_appointments() -> Result<Vec<Appointment>, String> {
+pub async fn get_all_appointments(include_deleted: bool) -> Result<Vec<Appointment>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
         .send()
@@ -32,6 +35,114 @@ pub async fn get_all_appointments() -> Result<Vec<Appointment>, String> {
     }
 }
 
+pub async fn get_appointments_by_pasien(pasien_id: Uuid) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?pasien_id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, pasien_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// One doctor's appointments between `from` and `to` inclusive, excluding deleted ones
+pub async fn get_appointments_by_dokter_between(dokter_id: Uuid, from: &str, to: &str) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?dokter_id=eq.{}&tanggal=gte.{}&tanggal=lte.{}&deleted_at=is.null&order=tanggal.asc,waktu.asc",
+            supabase_url, TABLE_NAME, dokter_id, from, to
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// The occurrences of a series in order, excluding deleted ones
+pub async fn get_appointments_by_series(series_id: Uuid) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?series_id=eq.{}&deleted_at=is.null&order=series_index.asc",
+            supabase_url, TABLE_NAME, series_id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Appointments dated between `from` and `to` inclusive (YYYY-MM-DD), excluding deleted ones
+pub async fn get_appointments_between(from: &str, to: &str) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?tanggal=gte.{}&tanggal=lte.{}&deleted_at=is.null",
+            supabase_url, TABLE_NAME, from, to
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_appointment_by_id(id: Uuid) -> Result<Appointment, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse appointment: {}", e))?;
+        rows.pop().ok_or_else(|| "Appointment not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_appointment(appointment_data: &CreateAppointmentDto) -> Result<Appointment, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -78,19 +189,212 @@ pub async fn update_appointment(id: Uuid, appointment_data: &UpdateAppointmentDt
     }
 }
 
-pub async fn delete_appointment(id: Uuid) -> Result<(), String> {
+// Changes only the status, and only while the current status is one of `from_statuses`.
+// Returns None when the appointment had already moved on.
+pub async fn set_appointment_status(id: Uuid, status: &str, from_statuses: &[&str]) -> Result<Option<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?id=eq.{}&status=in.({})",
+            supabase_url,
+            TABLE_NAME,
+            id,
+            from_statuses.join(",")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "status": status }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update appointment status: {}", e))?;
+
+    if res.status().is_success() {
+        let mut appointments: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated appointment: {}", e))?;
+        Ok(appointments.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_appointments_by_pasien_from(pasien_id: Uuid, from: &str) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?pasien_id=eq.{}&tanggal=gte.{}&deleted_at=is.null",
+            supabase_url, TABLE_NAME, pasien_id, from
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Moves an appointment to a new slot with its newly allocated resources, only while its
+// status is still one of `from_statuses`. Returns None when it had already moved on.
+pub async fn move_appointment(
+    id: Uuid,
+    tanggal: &str,
+    waktu: &str,
+    resource_ids: &[Uuid],
+    status: &str,
+    from_statuses: &[&str],
+) -> Result<Option<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "tanggal": tanggal,
+        "waktu": waktu,
+        "resource_ids": resource_ids,
+        "status": status,
+        "needs_reschedule": false
+    });
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?id=eq.{}&status=in.({})",
+            supabase_url,
+            TABLE_NAME,
+            id,
+            from_statuses.join(",")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to move appointment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut appointments: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse moved appointment: {}", e))?;
+        Ok(appointments.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Flags a booked or rescheduled appointment whose slot is no longer available.
+// Returns None when the appointment had already moved on.
+pub async fn flag_appointment_for_reschedule(id: Uuid, reason: &str) -> Result<Option<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "needs_reschedule": true,
+        "reschedule_reason": reason
+    });
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?id=eq.{}&status=in.(booked,rescheduled)&deleted_at=is.null",
+            supabase_url, TABLE_NAME, id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to flag appointment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut appointments: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse flagged appointment: {}", e))?;
+        Ok(appointments.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_appointment(id: Uuid, deleted_by: &str) -> Result<Appointment, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete appointment: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted appointment: {}", e))?;
+        deleted.pop().ok_or_else(|| "Appointment not found or already deleted".to_string())
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_appointment(id: Uuid) -> Result<Appointment, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore appointment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<Appointment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored appointment: {}", e))?;
+        restored.pop().ok_or_else(|| "Appointment not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_appointments(cutoff: DateTime<Utc>) -> Result<Vec<Appointment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge appointments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Appointment>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged appointments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
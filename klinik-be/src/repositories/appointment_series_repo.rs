@@ -0,0 +1,57 @@
+use crate::dtos::appointment_series_dto::CreateAppointmentSeriesDto;
+use crate::models::appointment_series::AppointmentSeries;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "appointment_series";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_appointment_series_by_id(id: Uuid) -> Result<AppointmentSeries, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch appointment series: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<AppointmentSeries> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse appointment series: {}", e))?;
+        rows.pop().ok_or_else(|| "Appointment series not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_appointment_series(series_data: &CreateAppointmentSeriesDto) -> Result<AppointmentSeries, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&series_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create appointment series: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut rows: Vec<AppointmentSeries> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created appointment series: {}", e))?;
+        rows.pop().ok_or_else(|| "Failed to get created appointment series".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
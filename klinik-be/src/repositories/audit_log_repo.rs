@@ -0,0 +1,105 @@
+use crate::dtos::audit_log_dto::{AuditChangeDto, AuditLogQueryDto, CreateAuditLogDto};
+use crate::models::audit_log::AuditLog;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+// Append-only: this module intentionally exposes no update or delete.
+const TABLE_NAME: &str = "audit_logs";
+const DEFAULT_LIMIT: u32 = 100;
+const MAX_LIMIT: u32 = 500;
+const CHANGE_BATCH_SIZE: usize = 100;
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn create_audit_log(log_data: &CreateAuditLogDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&log_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create audit log: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_audit_logs(query: &AuditLogQueryDto) -> Result<Vec<AuditLog>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+
+    let mut filters = vec![
+        ("order", "created_at.desc".to_string()),
+        ("limit", query.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT).to_string()),
+    ];
+    if let Some(resource) = &query.resource {
+        filters.push(("resource", format!("eq.{}", resource)));
+    }
+    if let Some(record_id) = query.record_id {
+        filters.push(("record_id", format!("eq.{}", record_id)));
+    }
+    if let Some(actor_id) = query.actor_id {
+        filters.push(("actor_id", format!("eq.{}", actor_id)));
+    }
+
+    let res = client
+        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .query(&filters)
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch audit logs: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<AuditLog>>()
+            .await
+            .map_err(|e| format!("Failed to parse audit logs: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Every audit entry of the given records, oldest first. Ids are sent in batches so the
+// query string stays short.
+pub async fn get_changes_for_records(resource: &str, record_ids: &[Uuid]) -> Result<Vec<AuditChangeDto>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let mut changes = Vec::new();
+    for batch in record_ids.chunks(CHANGE_BATCH_SIZE) {
+        let ids = batch.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+        let res = client
+            .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+            .query(&[
+                ("select", "record_id,action,created_at".to_string()),
+                ("resource", format!("eq.{}", resource)),
+                ("record_id", format!("in.({})", ids)),
+                ("order", "created_at.asc".to_string()),
+            ])
+            .header("apikey", &supabase_key)
+            .header("Authorization", format!("Bearer {}", &supabase_key))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch audit logs: {}", e))?;
+
+        if !res.status().is_success() {
+            return Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()));
+        }
+        changes.extend(
+            res.json::<Vec<AuditChangeDto>>()
+                .await
+                .map_err(|e| format!("Failed to parse audit logs: {}", e))?,
+        );
+    }
+    Ok(changes)
+}
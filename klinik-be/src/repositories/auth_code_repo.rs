@@ -0,0 +1,135 @@
+use crate::dtos::auth_dto::CreateAuthCodeDto;
+use crate::models::auth_code::AuthCode;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "auth_codes";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+// Codes of one purpose issued to the user since `since`, newest first
+pub async fn get_auth_codes_since(user_id: Uuid, purpose: &str, since: DateTime<Utc>) -> Result<Vec<AuthCode>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .query(&[
+            ("user_id", format!("eq.{}", user_id)),
+            ("purpose", format!("eq.{}", purpose)),
+            ("created_at", format!("gte.{}", since.format("%Y-%m-%dT%H:%M:%SZ"))),
+            ("order", "created_at.desc".to_string()),
+        ])
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch auth codes: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<AuthCode>>()
+            .await
+            .map_err(|e| format!("Failed to parse auth codes: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_auth_code_by_hash(purpose: &str, code_hash: &str) -> Result<Option<AuthCode>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?purpose=eq.{}&code_hash=eq.{}", supabase_url, TABLE_NAME, purpose, code_hash))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch auth code: {}", e))?;
+
+    if res.status().is_success() {
+        let mut codes: Vec<AuthCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse auth code: {}", e))?;
+        Ok(codes.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_auth_code(code_data: &CreateAuthCodeDto) -> Result<AuthCode, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&code_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create auth code: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut codes: Vec<AuthCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created auth code: {}", e))?;
+        codes.pop().ok_or_else(|| "Failed to get created auth code".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Uses up one attempt before the code is compared. The update only applies while the count
+// is still `attempts`, so of several concurrent guesses only one gets through per attempt;
+// returns false for the others.
+pub async fn claim_attempt(id: Uuid, attempts: i32) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&attempts=eq.{}", supabase_url, TABLE_NAME, id, attempts))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "attempts": attempts + 1 }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update auth code: {}", e))?;
+
+    if res.status().is_success() {
+        let claimed: Vec<AuthCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated auth code: {}", e))?;
+        Ok(!claimed.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Marks the code used. Returns false if it was already consumed by a concurrent request.
+pub async fn consume_auth_code(id: Uuid) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&consumed_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "consumed_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to consume auth code: {}", e))?;
+
+    if res.status().is_success() {
+        let codes: Vec<AuthCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse consumed auth code: {}", e))?;
+        Ok(!codes.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,83 @@
+use crate::dtos::auth_dto::CreateAuthCredentialDto;
+use crate::models::auth_credential::AuthCredential;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "auth_credentials";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_auth_credential(user_id: Uuid) -> Result<Option<AuthCredential>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?user_id=eq.{}", supabase_url, TABLE_NAME, user_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch auth credential: {}", e))?;
+
+    if res.status().is_success() {
+        let mut credentials: Vec<AuthCredential> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse auth credential: {}", e))?;
+        Ok(credentials.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_auth_credential(credential_data: &CreateAuthCredentialDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&credential_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create auth credential: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+async fn update_auth_credential(user_id: Uuid, changes: serde_json::Value) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?user_id=eq.{}", supabase_url, TABLE_NAME, user_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&changes)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update auth credential: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn set_password_hash(user_id: Uuid, password_hash: &str) -> Result<(), String> {
+    update_auth_credential(user_id, json!({ "password_hash": password_hash, "updated_at": Utc::now() })).await
+}
+
+pub async fn mark_email_verified(user_id: Uuid) -> Result<(), String> {
+    let now = Utc::now();
+    update_auth_credential(user_id, json!({ "email_verified_at": now, "updated_at": now })).await
+}
@@ -0,0 +1,150 @@
+use crate::dtos::auth_dto::CreateAuthSessionDto;
+use crate::models::auth_session::AuthSession;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "auth_sessions";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+async fn get_sessions(filter: &str) -> Result<Vec<AuthSession>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch auth sessions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<AuthSession>>()
+            .await
+            .map_err(|e| format!("Failed to parse auth sessions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_session_by_id(id: Uuid) -> Result<Option<AuthSession>, String> {
+    Ok(get_sessions(&format!("id=eq.{}", id)).await?.pop())
+}
+
+pub async fn get_session_by_refresh_hash(refresh_token_hash: &str) -> Result<Option<AuthSession>, String> {
+    Ok(get_sessions(&format!("refresh_token_hash=eq.{}", refresh_token_hash)).await?.pop())
+}
+
+// The session whose previous refresh token this was, i.e. a token that has already been rotated
+pub async fn get_session_by_previous_refresh_hash(refresh_token_hash: &str) -> Result<Option<AuthSession>, String> {
+    Ok(get_sessions(&format!("previous_refresh_token_hash=eq.{}", refresh_token_hash)).await?.pop())
+}
+
+// Unrevoked, unexpired sessions of a user, most recently used first
+pub async fn get_active_sessions_by_user(user_id: Uuid) -> Result<Vec<AuthSession>, String> {
+    get_sessions(&format!(
+        "user_id=eq.{}&revoked_at=is.null&expires_at=gt.{}&order=last_used_at.desc",
+        user_id,
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    ))
+    .await
+}
+
+pub async fn create_session(session_data: &CreateAuthSessionDto) -> Result<AuthSession, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&session_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create auth session: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut sessions: Vec<AuthSession> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created auth session: {}", e))?;
+        sessions.pop().ok_or_else(|| "Failed to get created auth session".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Swaps in the new refresh token. Guarded on the old hash, so of two concurrent refreshes
+// with the same token only one wins; returns false for the loser.
+pub async fn rotate_refresh_token(
+    id: Uuid,
+    old_hash: &str,
+    new_hash: &str,
+    expires_at: DateTime<Utc>,
+) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?id=eq.{}&refresh_token_hash=eq.{}&revoked_at=is.null",
+            supabase_url, TABLE_NAME, id, old_hash
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({
+            "refresh_token_hash": new_hash,
+            "previous_refresh_token_hash": old_hash,
+            "last_used_at": Utc::now(),
+            "expires_at": expires_at,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to rotate refresh token: {}", e))?;
+
+    if res.status().is_success() {
+        let sessions: Vec<AuthSession> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse rotated auth session: {}", e))?;
+        Ok(!sessions.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+async fn revoke_sessions(filter: &str) -> Result<Vec<AuthSession>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?{}&revoked_at=is.null", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "revoked_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to revoke auth sessions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<AuthSession>>()
+            .await
+            .map_err(|e| format!("Failed to parse revoked auth sessions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Returns the session if this call revoked it, None if it was already revoked
+pub async fn revoke_session(id: Uuid) -> Result<Option<AuthSession>, String> {
+    Ok(revoke_sessions(&format!("id=eq.{}", id)).await?.pop())
+}
+
+pub async fn revoke_sessions_by_user(user_id: Uuid) -> Result<Vec<AuthSession>, String> {
+    revoke_sessions(&format!("user_id=eq.{}", user_id)).await
+}
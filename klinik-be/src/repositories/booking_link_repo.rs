@@ -0,0 +1,56 @@
+use crate::dtos::booking_dto::CreateBookingLinkDto;
+use crate::models::booking_link::BookingLink;
+use reqwest::{Client, StatusCode};
+use std::env;
+
+const TABLE_NAME: &str = "booking_links";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_booking_link_by_hash(token_hash: &str) -> Result<BookingLink, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?token_hash=eq.{}", supabase_url, TABLE_NAME, token_hash))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch booking link: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<BookingLink> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse booking link: {}", e))?;
+        rows.pop().ok_or_else(|| "Booking not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_booking_link(link_data: &CreateBookingLinkDto) -> Result<BookingLink, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&link_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create booking link: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut links: Vec<BookingLink> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created booking link: {}", e))?;
+        links.pop().ok_or_else(|| "Failed to get created booking link".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
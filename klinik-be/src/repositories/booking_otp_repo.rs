@@ -0,0 +1,114 @@
+use crate::dtos::booking_dto::CreateBookingOtpDto;
+use crate::models::booking_otp::BookingOtp;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "booking_otps";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+// Codes sent to the number since `since`, newest first
+pub async fn get_otps_since(no_telepon: &str, since: DateTime<Utc>) -> Result<Vec<BookingOtp>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .query(&[
+            ("no_telepon", format!("eq.{}", no_telepon)),
+            ("created_at", format!("gte.{}", since.format("%Y-%m-%dT%H:%M:%SZ"))),
+            ("order", "created_at.desc".to_string()),
+        ])
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch booking otps: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<BookingOtp>>()
+            .await
+            .map_err(|e| format!("Failed to parse booking otps: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_otp(otp_data: &CreateBookingOtpDto) -> Result<BookingOtp, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&otp_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create booking otp: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut otps: Vec<BookingOtp> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created booking otp: {}", e))?;
+        otps.pop().ok_or_else(|| "Failed to get created booking otp".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Uses up one attempt before the code is compared. The update only applies while the count
+// is still `attempts`, so of several concurrent guesses only one gets through per attempt;
+// returns false for the others.
+pub async fn claim_attempt(id: Uuid, attempts: i32) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&attempts=eq.{}", supabase_url, TABLE_NAME, id, attempts))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "attempts": attempts + 1 }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update booking otp: {}", e))?;
+
+    if res.status().is_success() {
+        let claimed: Vec<BookingOtp> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated booking otp: {}", e))?;
+        Ok(!claimed.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Marks the code used. Returns false if it was already consumed by a concurrent request.
+pub async fn consume_otp(id: Uuid) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&consumed_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "consumed_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to consume booking otp: {}", e))?;
+
+    if res.status().is_success() {
+        let otps: Vec<BookingOtp> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse consumed booking otp: {}", e))?;
+        Ok(!otps.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
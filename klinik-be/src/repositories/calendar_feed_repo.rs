@@ -0,0 +1,82 @@
+use crate::dtos::calendar_dto::CreateCalendarFeedDto;
+use crate::models::calendar_feed::CalendarFeed;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "calendar_feeds";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_active_calendar_feed_by_hash(token_hash: &str) -> Result<CalendarFeed, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?token_hash=eq.{}&revoked_at=is.null", supabase_url, TABLE_NAME, token_hash))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch calendar feed: {}", e))?;
+
+    if res.status().is_success() {
+        let mut feeds: Vec<CalendarFeed> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse calendar feed: {}", e))?;
+        feeds.pop().ok_or_else(|| "Calendar feed not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_calendar_feed(feed_data: &CreateCalendarFeedDto) -> Result<CalendarFeed, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&feed_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create calendar feed: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut feeds: Vec<CalendarFeed> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created calendar feed: {}", e))?;
+        feeds.pop().ok_or_else(|| "Failed to get created calendar feed".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Revokes every active feed of a doctor, returning the revoked rows
+pub async fn revoke_calendar_feeds(dokter_id: Uuid) -> Result<Vec<CalendarFeed>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?dokter_id=eq.{}&revoked_at=is.null", supabase_url, TABLE_NAME, dokter_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "revoked_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to revoke calendar feeds: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<CalendarFeed>>()
+            .await
+            .map_err(|e| format!("Failed to parse revoked calendar feeds: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
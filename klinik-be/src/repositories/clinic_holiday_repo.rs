@@ -0,0 +1,191 @@
+use crate::dtos::schedule_dto::{CreateClinicHolidayDto, ScheduleQueryDto};
+use crate::models::clinic_holiday::ClinicHoliday;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "clinic_holidays";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+fn range_filter(query: &ScheduleQueryDto) -> String {
+    let mut filter = String::from("order=tanggal.asc");
+    if !query.include_deleted.unwrap_or(false) {
+        filter.push_str("&deleted_at=is.null");
+    }
+    if let Some(from) = &query.from {
+        filter.push_str(&format!("&tanggal=gte.{}", from));
+    }
+    if let Some(to) = &query.to {
+        filter.push_str(&format!("&tanggal=lte.{}", to));
+    }
+    filter
+}
+
+pub async fn get_clinic_holidays(query: &ScheduleQueryDto) -> Result<Vec<ClinicHoliday>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, range_filter(query)))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic holidays: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicHoliday>>()
+            .await
+            .map_err(|e| format!("Failed to parse clinic holidays: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_clinic_holidays_on_date(tanggal: &str) -> Result<Vec<ClinicHoliday>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?tanggal=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, tanggal))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic holidays: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicHoliday>>()
+            .await
+            .map_err(|e| format!("Failed to parse clinic holidays: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_clinic_holiday_by_id(id: Uuid) -> Result<ClinicHoliday, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic holiday: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<ClinicHoliday> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse clinic holiday: {}", e))?;
+        rows.pop().ok_or_else(|| "Clinic holiday not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_clinic_holiday(data: &CreateClinicHolidayDto) -> Result<ClinicHoliday, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create clinic holiday: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut rows: Vec<ClinicHoliday> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created clinic holiday: {}", e))?;
+        rows.pop().ok_or_else(|| "Failed to get created clinic holiday".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_clinic_holiday(id: Uuid, deleted_by: &str) -> Result<ClinicHoliday, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete clinic holiday: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<ClinicHoliday> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted clinic holiday: {}", e))?;
+        deleted.pop().ok_or_else(|| "Clinic holiday not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_clinic_holiday(id: Uuid) -> Result<ClinicHoliday, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore clinic holiday: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<ClinicHoliday> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored clinic holiday: {}", e))?;
+        restored.pop().ok_or_else(|| "Clinic holiday not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_clinic_holidays(cutoff: DateTime<Utc>) -> Result<Vec<ClinicHoliday>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge clinic holidays: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicHoliday>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged clinic holidays: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
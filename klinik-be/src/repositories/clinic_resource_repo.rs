@@ -0,0 +1,205 @@
+use crate::dtos::clinic_resource_dto::{CreateClinicResourceDto, UpdateClinicResourceDto};
+use crate::models::clinic_resource::ClinicResource;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "clinic_resources";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_all_clinic_resources(include_deleted: bool) -> Result<Vec<ClinicResource>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
+    let res = client
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic resources: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicResource>>()
+            .await
+            .map_err(|e| format!("Failed to parse clinic resources: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Resources that can be allocated to new bookings
+pub async fn get_active_clinic_resources() -> Result<Vec<ClinicResource>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?is_active=eq.true&deleted_at=is.null&order=name.asc",
+            supabase_url, TABLE_NAME
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic resources: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicResource>>()
+            .await
+            .map_err(|e| format!("Failed to parse clinic resources: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_clinic_resource_by_id(id: Uuid) -> Result<ClinicResource, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch clinic resource: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<ClinicResource> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse clinic resource: {}", e))?;
+        rows.pop().ok_or_else(|| "Clinic resource not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_clinic_resource(resource_data: &CreateClinicResourceDto) -> Result<ClinicResource, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&resource_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create clinic resource: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut resources: Vec<ClinicResource> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created clinic resource: {}", e))?;
+        resources.pop().ok_or_else(|| "Failed to get created clinic resource".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn update_clinic_resource(id: Uuid, resource_data: &UpdateClinicResourceDto) -> Result<ClinicResource, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&resource_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update clinic resource: {}", e))?;
+
+    if res.status().is_success() {
+        let mut resources: Vec<ClinicResource> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated clinic resource: {}", e))?;
+        resources.pop().ok_or_else(|| "Failed to get updated clinic resource".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_clinic_resource(id: Uuid, deleted_by: &str) -> Result<ClinicResource, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete clinic resource: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<ClinicResource> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted clinic resource: {}", e))?;
+        deleted.pop().ok_or_else(|| "Clinic resource not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_clinic_resource(id: Uuid) -> Result<ClinicResource, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore clinic resource: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<ClinicResource> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored clinic resource: {}", e))?;
+        restored.pop().ok_or_else(|| "Clinic resource not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_clinic_resources(cutoff: DateTime<Utc>) -> Result<Vec<ClinicResource>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge clinic resources: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ClinicResource>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged clinic resources: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
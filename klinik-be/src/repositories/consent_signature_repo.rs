@@ -0,0 +1,80 @@
+use crate::dtos::consent_dto::CreateConsentSignatureDto;
+use crate::models::consent_signature::ConsentSignature;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+// Signed consents are legal records: this module intentionally exposes no update or delete.
+const TABLE_NAME: &str = "consent_signatures";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_consent_signatures_by_appointment(appointment_id: Uuid) -> Result<Vec<ConsentSignature>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?appointment_id=eq.{}&order=signed_at.asc",
+            supabase_url, TABLE_NAME, appointment_id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent signatures: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ConsentSignature>>()
+            .await
+            .map_err(|e| format!("Failed to parse consent signatures: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_consent_signature_by_id(id: Uuid) -> Result<ConsentSignature, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent signature: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<ConsentSignature> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse consent signature: {}", e))?;
+        rows.pop().ok_or_else(|| "Consent signature not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_consent_signature(signature_data: &CreateConsentSignatureDto) -> Result<ConsentSignature, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&signature_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create consent signature: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut signatures: Vec<ConsentSignature> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created consent signature: {}", e))?;
+        signatures.pop().ok_or_else(|| "Failed to get created consent signature".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
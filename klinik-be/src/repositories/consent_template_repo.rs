@@ -0,0 +1,194 @@
+use crate::dtos::consent_dto::ConsentTemplateRecordDto;
+use crate::models::consent_template::ConsentTemplate;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "consent_templates";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_all_consent_templates(include_deleted: bool) -> Result<Vec<ConsentTemplate>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
+    let res = client
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent templates: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ConsentTemplate>>()
+            .await
+            .map_err(|e| format!("Failed to parse consent templates: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.status()))
+    }
+}
+
+// The active version of every template, as used to decide which consents a booking needs
+pub async fn get_active_consent_templates() -> Result<Vec<ConsentTemplate>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?is_active=eq.true&deleted_at=is.null",
+            supabase_url, TABLE_NAME
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent templates: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ConsentTemplate>>()
+            .await
+            .map_err(|e| format!("Failed to parse consent templates: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_consent_templates_by_code(code: &str) -> Result<Vec<ConsentTemplate>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?code=eq.{}&order=version.desc", supabase_url, TABLE_NAME, code))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent templates: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ConsentTemplate>>()
+            .await
+            .map_err(|e| format!("Failed to parse consent templates: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_consent_template_by_id(id: Uuid) -> Result<ConsentTemplate, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch consent template: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<ConsentTemplate> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse consent template: {}", e))?;
+        rows.pop().ok_or_else(|| "Consent template not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_consent_template(template_data: &ConsentTemplateRecordDto) -> Result<ConsentTemplate, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&template_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create consent template: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut templates: Vec<ConsentTemplate> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created consent template: {}", e))?;
+        templates.pop().ok_or_else(|| "Failed to get created consent template".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Retires a version once its successor is published
+pub async fn deactivate_consent_template(id: Uuid) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&json!({ "is_active": false }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to deactivate consent template: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_consent_template(id: Uuid, deleted_by: &str) -> Result<ConsentTemplate, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete consent template: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<ConsentTemplate> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted consent template: {}", e))?;
+        deleted.pop().ok_or_else(|| "Consent template not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_consent_template(id: Uuid) -> Result<ConsentTemplate, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore consent template: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<ConsentTemplate> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored consent template: {}", e))?;
+        restored.pop().ok_or_else(|| "Consent template not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
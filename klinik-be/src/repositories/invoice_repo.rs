@@ -1,6 +1,8 @@
 use crate::dtos::invoice_dto::{CreateInvoiceDto, UpdateInvoiceDto};
 use crate::models::invoice::Invoice;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -13,10 +15,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_invoices() -> Result<Vec<Invoice>, String> {
+pub async fn get_all_invoices(include_deleted: bool) -> Result<Vec<Invoice>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
         .send()
@@ -32,6 +35,68 @@ pub async fn get_all_invoices() -> Result<Vec<Invoice>, String> {
     }
 }
 
+pub async fn get_invoices_by_pasien(pasien_id: Uuid) -> Result<Vec<Invoice>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?pasien_id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, pasien_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch invoices: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Invoice>>()
+            .await
+            .map_err(|e| format!("Failed to parse invoices: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Invoices dated between `from` and `to` inclusive (YYYY-MM-DD), excluding deleted ones
+pub async fn get_invoices_between(from: &str, to: &str) -> Result<Vec<Invoice>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?tanggal=gte.{}&tanggal=lte.{}&deleted_at=is.null",
+            supabase_url, TABLE_NAME, from, to
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch invoices: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Invoice>>()
+            .await
+            .map_err(|e| format!("Failed to parse invoices: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_invoice_by_id(id: Uuid) -> Result<Invoice, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch invoice: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Invoice> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse invoice: {}", e))?;
+        rows.pop().ok_or_else(|| "Invoice not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_invoice(invoice_data: &CreateInvoiceDto) -> Result<Invoice, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -78,19 +143,82 @@ pub async fn update_invoice(id: Uuid, invoice_data: &UpdateInvoiceDto) -> Result
     }
 }
 
-pub async fn delete_invoice(id: Uuid) -> Result<(), String> {
+pub async fn delete_invoice(id: Uuid, deleted_by: &str) -> Result<Invoice, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete invoice: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<Invoice> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted invoice: {}", e))?;
+        deleted.pop().ok_or_else(|| "Invoice not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_invoice(id: Uuid) -> Result<Invoice, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore invoice: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<Invoice> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored invoice: {}", e))?;
+        restored.pop().ok_or_else(|| "Invoice not found or not deleted".to_string())
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_invoices(cutoff: DateTime<Utc>) -> Result<Vec<Invoice>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge invoices: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Invoice>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged invoices: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
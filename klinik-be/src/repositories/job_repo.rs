@@ -0,0 +1,134 @@
+use crate::dtos::job_dto::{CreateJobDto, UpdateJobDto};
+use crate::models::job::Job;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+
+const TABLE_NAME: &str = "jobs";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+fn timestamp(value: DateTime<Utc>) -> String {
+    value.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()
+}
+
+pub async fn get_all_jobs() -> Result<Vec<Job>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?order=name.asc", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch jobs: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Job>>()
+            .await
+            .map_err(|e| format!("Failed to parse jobs: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_job(job_data: &CreateJobDto) -> Result<Job, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&job_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create job: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut jobs: Vec<Job> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created job: {}", e))?;
+        jobs.pop().ok_or_else(|| "Failed to get created job".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn update_job(name: &str, job_data: &UpdateJobDto) -> Result<Job, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?name=eq.{}", supabase_url, TABLE_NAME, name))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&job_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update job: {}", e))?;
+
+    if res.status().is_success() {
+        let mut jobs: Vec<Job> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated job: {}", e))?;
+        jobs.pop().ok_or_else(|| "Job not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Moves next_run_at forward only if it still holds the value this instance read, so when
+// several server instances poll the same table exactly one of them runs each occurrence.
+pub async fn claim_job(name: &str, expected_next_run_at: DateTime<Utc>, next_run_at: DateTime<Utc>) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?name=eq.{}&next_run_at=eq.{}",
+            supabase_url,
+            TABLE_NAME,
+            name,
+            timestamp(expected_next_run_at)
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "next_run_at": next_run_at }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to claim job: {}", e))?;
+
+    if res.status().is_success() {
+        let claimed: Vec<Job> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse claimed job: {}", e))?;
+        Ok(!claimed.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn record_job_result(name: &str, status: &str, finished_at: DateTime<Utc>) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?name=eq.{}", supabase_url, TABLE_NAME, name))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&json!({ "last_status": status, "last_run_at": finished_at }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update job: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
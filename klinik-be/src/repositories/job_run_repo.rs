@@ -0,0 +1,82 @@
+use crate::dtos::job_dto::{CreateJobRunDto, FinishJobRunDto};
+use crate::models::job_run::JobRun;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "job_runs";
+const DEFAULT_LIMIT: u32 = 50;
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn create_job_run(run_data: &CreateJobRunDto) -> Result<JobRun, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&run_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create job run: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut runs: Vec<JobRun> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created job run: {}", e))?;
+        runs.pop().ok_or_else(|| "Failed to get created job run".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn finish_job_run(id: Uuid, run_data: &FinishJobRunDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&run_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update job run: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_job_runs(job_name: &str, limit: Option<u32>) -> Result<Vec<JobRun>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?job_name=eq.{}&order=started_at.desc&limit={}",
+            supabase_url,
+            TABLE_NAME,
+            job_name,
+            limit.unwrap_or(DEFAULT_LIMIT)
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch job runs: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<JobRun>>()
+            .await
+            .map_err(|e| format!("Failed to parse job runs: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
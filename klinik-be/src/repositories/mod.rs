@@ -6,4 +6,30 @@ pub mod pasien_repo;
 pub mod appointment_repo;
 pub mod treatment_progress_repo;
 pub mod skin_analysis_repo;
-pub mod invoice_repo;
\ No newline at end of file
+pub mod invoice_repo;
+pub mod audit_log_repo;
+pub mod photo_repo;
+pub mod recommendation_rule_repo;
+pub mod recommendation_suggestion_repo;
+pub mod safety_override_repo;
+pub mod consent_template_repo;
+pub mod consent_signature_repo;
+pub mod notification_delivery_repo;
+pub mod job_repo;
+pub mod job_run_repo;
+pub mod report_snapshot_repo;
+pub mod schedule_exception_repo;
+pub mod clinic_holiday_repo;
+pub mod clinic_resource_repo;
+pub mod queue_entry_repo;
+pub mod booking_otp_repo;
+pub mod booking_link_repo;
+pub mod waitlist_entry_repo;
+pub mod waitlist_offer_repo;
+pub mod appointment_series_repo;
+pub mod calendar_feed_repo;
+pub mod auth_credential_repo;
+pub mod auth_code_repo;
+pub mod auth_session_repo;
+pub mod user_totp_repo;
+pub mod totp_recovery_code_repo;
\ No newline at end of file
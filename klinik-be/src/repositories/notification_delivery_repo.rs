@@ -0,0 +1,97 @@
+use crate::dtos::notification_dto::{CreateNotificationDeliveryDto, UpdateNotificationDeliveryDto};
+use crate::models::notification_delivery::NotificationDelivery;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "notification_deliveries";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+async fn fetch_deliveries(query: String) -> Result<Vec<NotificationDelivery>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, query))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch notification deliveries: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<NotificationDelivery>>()
+            .await
+            .map_err(|e| format!("Failed to parse notification deliveries: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_deliveries_by_appointment(appointment_id: Uuid) -> Result<Vec<NotificationDelivery>, String> {
+    fetch_deliveries(format!("appointment_id=eq.{}&order=created_at.asc", appointment_id)).await
+}
+
+pub async fn get_deliveries_by_appointments(appointment_ids: &[Uuid]) -> Result<Vec<NotificationDelivery>, String> {
+    if appointment_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let id_list = appointment_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    fetch_deliveries(format!("appointment_id=in.({})", id_list)).await
+}
+
+// Pending deliveries whose next attempt is due
+pub async fn get_due_deliveries(now: DateTime<Utc>) -> Result<Vec<NotificationDelivery>, String> {
+    fetch_deliveries(format!(
+        "status=eq.pending&next_attempt_at=lte.{}&order=next_attempt_at.asc",
+        now.format("%Y-%m-%dT%H:%M:%SZ")
+    ))
+    .await
+}
+
+pub async fn create_delivery(delivery_data: &CreateNotificationDeliveryDto) -> Result<NotificationDelivery, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&delivery_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create notification delivery: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut deliveries: Vec<NotificationDelivery> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created notification delivery: {}", e))?;
+        deliveries.pop().ok_or_else(|| "Failed to get created notification delivery".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn update_delivery(id: Uuid, delivery_data: &UpdateNotificationDeliveryDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&delivery_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update notification delivery: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -1,7 +1,9 @@
 //src/repositories/pasien_repo.rs
 use crate::dtos::pasien_dto::{CreatePasienDto, UpdatePasienDto};
 use crate::models::pasien::Pasien;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -14,10 +16,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_pasiens() -> Result<Vec<Pasien>, String> {
+pub async fn get_all_pasiens(include_deleted: bool) -> Result<Vec<Pasien>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .send()
         .await
@@ -32,6 +35,102 @@ pub async fn get_all_pasiens() -> Result<Vec<Pasien>, String> {
     }
 }
 
+pub async fn get_pasiens_created_between(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<Pasien>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?created_at=gte.{}&created_at=lt.{}&deleted_at=is.null",
+            supabase_url,
+            TABLE_NAME,
+            start.format("%Y-%m-%dT%H:%M:%SZ"),
+            end.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch pasiens: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Pasien>>()
+            .await
+            .map_err(|e| format!("Failed to parse pasiens: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Patients whose no_telepon is any of the given spellings, excluding deleted ones
+pub async fn get_pasiens_by_phones(phones: &[String]) -> Result<Vec<Pasien>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let list = phones.iter().map(|p| format!("\"{}\"", p)).collect::<Vec<_>>().join(",");
+    let res = client
+        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .query(&[
+            ("no_telepon", format!("in.({})", list)),
+            ("deleted_at", "is.null".to_string()),
+            ("order", "created_at.asc".to_string()),
+        ])
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch pasiens: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Pasien>>()
+            .await
+            .map_err(|e| format!("Failed to parse pasiens: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_pasiens_by_ids(ids: &[Uuid]) -> Result<Vec<Pasien>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let mut pasiens = Vec::new();
+    for batch in ids.chunks(100) {
+        let list = batch.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+        let res = client
+            .get(format!("{}/rest/v1/{}?id=in.({})", supabase_url, TABLE_NAME, list))
+            .header("apikey", &supabase_key)
+            .header("Authorization", format!("Bearer {}", &supabase_key))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch pasiens: {}", e))?;
+
+        if !res.status().is_success() {
+            return Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()));
+        }
+        pasiens.extend(
+            res.json::<Vec<Pasien>>()
+                .await
+                .map_err(|e| format!("Failed to parse pasiens: {}", e))?,
+        );
+    }
+    Ok(pasiens)
+}
+
+pub async fn get_pasien_by_id(id: Uuid) -> Result<Pasien, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch pasien: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Pasien> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse pasien: {}", e))?;
+        rows.pop().ok_or_else(|| "Pasien not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_pasien(pasien_data: &CreatePasienDto) -> Result<Pasien, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -78,19 +177,82 @@ pub async fn update_pasien(id: Uuid, pasien_data: &UpdatePasienDto) -> Result<Pa
     }
 }
 
-pub async fn delete_pasien(id: Uuid) -> Result<(), String> {
+pub async fn delete_pasien(id: Uuid, deleted_by: &str) -> Result<Pasien, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete pasien: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<Pasien> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted pasien: {}", e))?;
+        deleted.pop().ok_or_else(|| "Pasien not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_pasien(id: Uuid) -> Result<Pasien, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore pasien: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<Pasien> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored pasien: {}", e))?;
+        restored.pop().ok_or_else(|| "Pasien not found or not deleted".to_string())
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_pasiens(cutoff: DateTime<Utc>) -> Result<Vec<Pasien>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge pasiens: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Pasien>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged pasiens: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
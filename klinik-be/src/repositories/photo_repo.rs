@@ -0,0 +1,147 @@
+use crate::dtos::photo_dto::CreatePhotoDto;
+use crate::models::photo::Photo;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "photos";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+// `filter` is a PostgREST filter such as `pasien_id=eq.<uuid>`; results are oldest first
+async fn get_photos_where(filter: String) -> Result<Vec<Photo>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?{}&deleted_at=is.null&order=tanggal.asc,created_at.asc",
+            supabase_url, TABLE_NAME, filter
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch photos: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Photo>>()
+            .await
+            .map_err(|e| format!("Failed to parse photos: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_photos_by_pasien(pasien_id: Uuid) -> Result<Vec<Photo>, String> {
+    get_photos_where(format!("pasien_id=eq.{}", pasien_id)).await
+}
+
+pub async fn get_photos_by_skin_analysis(skin_analysis_id: Uuid) -> Result<Vec<Photo>, String> {
+    get_photos_where(format!("skin_analysis_id=eq.{}", skin_analysis_id)).await
+}
+
+pub async fn get_photos_by_treatment_progress(treatment_progress_id: Uuid) -> Result<Vec<Photo>, String> {
+    get_photos_where(format!("treatment_progress_id=eq.{}", treatment_progress_id)).await
+}
+
+pub async fn get_photo_by_id(id: Uuid) -> Result<Photo, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch photo: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Photo> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse photo: {}", e))?;
+        rows.pop().ok_or_else(|| "Photo not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_photo(photo_data: &CreatePhotoDto) -> Result<Photo, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&photo_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create photo: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut photos: Vec<Photo> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created photo: {}", e))?;
+        photos.pop().ok_or_else(|| "Failed to get created photo".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_photo(id: Uuid, deleted_by: &str) -> Result<Photo, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete photo: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<Photo> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted photo: {}", e))?;
+        deleted.pop().ok_or_else(|| "Photo not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_photos(cutoff: DateTime<Utc>) -> Result<Vec<Photo>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge photos: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Photo>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged photos: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
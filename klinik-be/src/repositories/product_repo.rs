@@ -1,5 +1,6 @@
 use crate::dtos::product_dto::{CreateProductDto, UpdateProductDto};
 use crate::models::product::Product;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
 use serde_json::json;
 use std::env;
@@ -15,11 +16,12 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
 }
 
 // Fungsi untuk MENGAMBIL SEMUA produk (GET)
-pub async fn get_all_products() -> Result<Vec<Product>, String> {
+pub async fn get_all_products(include_deleted: bool) -> Result<Vec<Product>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
 
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .send()
         .await
@@ -34,6 +36,27 @@ pub async fn get_all_products() -> Result<Vec<Product>, String> {
     }
 }
 
+// Fungsi untuk MENGAMBIL satu produk berdasarkan ID, termasuk yang sudah dihapus
+pub async fn get_product_by_id(id: Uuid) -> Result<Product, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch product: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Product> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse product: {}", e))?;
+        rows.pop().ok_or_else(|| "Product not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 // Fungsi untuk MEMBUAT produk baru (POST)
 pub async fn create_product(product_data: &CreateProductDto) -> Result<Product, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
@@ -89,20 +112,82 @@ pub async fn update_product(id: Uuid, product_data: &UpdateProductDto) -> Result
 }
 
 // Fungsi untuk MENGHAPUS produk berdasarkan ID
-pub async fn delete_product(id: Uuid) -> Result<(), String> {
+pub async fn delete_product(id: Uuid, deleted_by: &str) -> Result<Product, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
-
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete product: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<Product> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted product: {}", e))?;
+        deleted.pop().ok_or_else(|| "Product not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_product(id: Uuid) -> Result<Product, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore product: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<Product> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored product: {}", e))?;
+        restored.pop().ok_or_else(|| "Product not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_products(cutoff: DateTime<Utc>) -> Result<Vec<Product>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge products: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Product>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged products: {}", e))
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,110 @@
+use crate::dtos::queue_dto::{CreateQueueEntryDto, QueueStatusChangeDto};
+use crate::models::queue_entry::QueueEntry;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "queue_entries";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_queue_entries_by_date(tanggal: &str) -> Result<Vec<QueueEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?tanggal=eq.{}&order=queue_number.asc",
+            supabase_url, TABLE_NAME, tanggal
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch queue entries: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<QueueEntry>>()
+            .await
+            .map_err(|e| format!("Failed to parse queue entries: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_queue_entry_by_id(id: Uuid) -> Result<QueueEntry, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch queue entry: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<QueueEntry> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse queue entry: {}", e))?;
+        rows.pop().ok_or_else(|| "Queue entry not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Returns None when the queue number was taken by a concurrent check-in
+// (unique on tanggal + queue_number), so the caller can retry with the next one.
+pub async fn create_queue_entry(entry_data: &CreateQueueEntryDto) -> Result<Option<QueueEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&entry_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create queue entry: {}", e))?;
+
+    match res.status() {
+        StatusCode::CREATED => {
+            let mut entries: Vec<QueueEntry> = res.json()
+                .await
+                .map_err(|e| format!("Failed to parse created queue entry: {}", e))?;
+            entries.pop().map(Some).ok_or_else(|| "Failed to get created queue entry".to_string())
+        }
+        StatusCode::CONFLICT => Ok(None),
+        _ => Err(format!("Supabase error: {}", res.text().await.unwrap_or_default())),
+    }
+}
+
+// Moves an entry on only while it is still in `from_status`; None when someone else moved it first
+pub async fn change_queue_entry_status(id: Uuid, from_status: &str, change: &QueueStatusChangeDto) -> Result<Option<QueueEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?id=eq.{}&status=eq.{}",
+            supabase_url, TABLE_NAME, id, from_status
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&change)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update queue entry: {}", e))?;
+
+    if res.status().is_success() {
+        let mut entries: Vec<QueueEntry> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated queue entry: {}", e))?;
+        Ok(entries.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,204 @@
+use crate::dtos::recommendation_rule_dto::{CreateRecommendationRuleDto, UpdateRecommendationRuleDto};
+use crate::models::recommendation_rule::RecommendationRule;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "recommendation_rules";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_all_recommendation_rules(include_deleted: bool) -> Result<Vec<RecommendationRule>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
+    let res = client
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch recommendation rules: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecommendationRule>>()
+            .await
+            .map_err(|e| format!("Failed to parse recommendation rules: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.status()))
+    }
+}
+
+// Active rules, highest priority first, as evaluated by the recommendation engine
+pub async fn get_active_recommendation_rules() -> Result<Vec<RecommendationRule>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?is_active=eq.true&deleted_at=is.null&order=priority.desc",
+            supabase_url, TABLE_NAME
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch recommendation rules: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecommendationRule>>()
+            .await
+            .map_err(|e| format!("Failed to parse recommendation rules: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_recommendation_rule_by_id(id: Uuid) -> Result<RecommendationRule, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch recommendation rule: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<RecommendationRule> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse recommendation rule: {}", e))?;
+        rows.pop().ok_or_else(|| "Recommendation rule not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_recommendation_rule(rule_data: &CreateRecommendationRuleDto) -> Result<RecommendationRule, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&rule_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create recommendation rule: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut rules: Vec<RecommendationRule> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created recommendation rule: {}", e))?;
+        rules.pop().ok_or_else(|| "Failed to get created recommendation rule".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn update_recommendation_rule(id: Uuid, rule_data: &UpdateRecommendationRuleDto) -> Result<RecommendationRule, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&rule_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update recommendation rule: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rules: Vec<RecommendationRule> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated recommendation rule: {}", e))?;
+        rules.pop().ok_or_else(|| "Failed to get updated recommendation rule".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_recommendation_rule(id: Uuid, deleted_by: &str) -> Result<RecommendationRule, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete recommendation rule: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<RecommendationRule> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted recommendation rule: {}", e))?;
+        deleted.pop().ok_or_else(|| "Recommendation rule not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_recommendation_rule(id: Uuid) -> Result<RecommendationRule, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore recommendation rule: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<RecommendationRule> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored recommendation rule: {}", e))?;
+        restored.pop().ok_or_else(|| "Recommendation rule not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_recommendation_rules(cutoff: DateTime<Utc>) -> Result<Vec<RecommendationRule>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge recommendation rules: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecommendationRule>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged recommendation rules: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,123 @@
+use crate::dtos::recommendation_suggestion_dto::CreateRecommendationSuggestionDto;
+use crate::models::recommendation_suggestion::RecommendationSuggestion;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "recommendation_suggestions";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_suggestions_by_skin_analysis(skin_analysis_id: Uuid) -> Result<Vec<RecommendationSuggestion>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?skin_analysis_id=eq.{}&order=created_at.asc",
+            supabase_url, TABLE_NAME, skin_analysis_id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch recommendation suggestions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecommendationSuggestion>>()
+            .await
+            .map_err(|e| format!("Failed to parse recommendation suggestions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_suggestions(suggestions: &[CreateRecommendationSuggestionDto]) -> Result<Vec<RecommendationSuggestion>, String> {
+    if suggestions.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&suggestions)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create recommendation suggestions: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        res.json::<Vec<RecommendationSuggestion>>()
+            .await
+            .map_err(|e| format!("Failed to parse created recommendation suggestions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Marks the still-pending suggestions of an analysis as superseded by a new run of the engine
+pub async fn supersede_pending_suggestions(skin_analysis_id: Uuid) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?skin_analysis_id=eq.{}&status=eq.pending",
+            supabase_url, TABLE_NAME, skin_analysis_id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&json!({ "status": "superseded" }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to supersede recommendation suggestions: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn decide_suggestions(
+    ids: &[Uuid],
+    status: &str,
+    decided_by: &str,
+    override_reason: Option<&str>,
+) -> Result<Vec<RecommendationSuggestion>, String> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let id_list = ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",");
+    let body = json!({
+        "status": status,
+        "decided_by": decided_by,
+        "decided_at": Utc::now(),
+        "override_reason": override_reason
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=in.({})&status=eq.pending", supabase_url, TABLE_NAME, id_list))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update recommendation suggestions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<RecommendationSuggestion>>()
+            .await
+            .map_err(|e| format!("Failed to parse updated recommendation suggestions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,65 @@
+use crate::dtos::report_dto::{CreateReportSnapshotDto, ReportQueryDto};
+use crate::models::report_snapshot::ReportSnapshot;
+use reqwest::Client;
+use std::env;
+
+const TABLE_NAME: &str = "report_snapshots";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+// One row per report_date: re-running the snapshot for a day replaces it
+pub async fn upsert_report_snapshot(snapshot_data: &CreateReportSnapshotDto) -> Result<ReportSnapshot, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}?on_conflict=report_date", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "resolution=merge-duplicates,return=representation")
+        .json(&snapshot_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to save report snapshot: {}", e))?;
+
+    if res.status().is_success() {
+        let mut snapshots: Vec<ReportSnapshot> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse report snapshot: {}", e))?;
+        snapshots.pop().ok_or_else(|| "Failed to get saved report snapshot".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_report_snapshots(query: &ReportQueryDto) -> Result<Vec<ReportSnapshot>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+
+    let mut filters = vec!["order=report_date.asc".to_string()];
+    if let Some(from) = &query.from {
+        filters.push(format!("report_date=gte.{}", from));
+    }
+    if let Some(to) = &query.to {
+        filters.push(format!("report_date=lte.{}", to));
+    }
+
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, filters.join("&")))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch report snapshots: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ReportSnapshot>>()
+            .await
+            .map_err(|e| format!("Failed to parse report snapshots: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,56 @@
+use crate::dtos::safety_dto::CreateSafetyOverrideDto;
+use crate::models::safety_override::SafetyOverride;
+use reqwest::{Client, StatusCode};
+use std::env;
+use uuid::Uuid;
+
+// Append-only, like the audit trail: an acknowledged warning stays on record.
+const TABLE_NAME: &str = "safety_overrides";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn create_safety_override(override_data: &CreateSafetyOverrideDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&override_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create safety override: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_safety_overrides_by_pasien(pasien_id: Uuid) -> Result<Vec<SafetyOverride>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?pasien_id=eq.{}&order=created_at.desc",
+            supabase_url, TABLE_NAME, pasien_id
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch safety overrides: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<SafetyOverride>>()
+            .await
+            .map_err(|e| format!("Failed to parse safety overrides: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
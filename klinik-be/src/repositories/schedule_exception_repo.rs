@@ -0,0 +1,191 @@
+use crate::dtos::schedule_dto::{CreateScheduleExceptionDto, ScheduleQueryDto};
+use crate::models::schedule_exception::ScheduleException;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "schedule_exceptions";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+fn range_filter(query: &ScheduleQueryDto) -> String {
+    let mut filter = String::from("order=tanggal.asc");
+    if !query.include_deleted.unwrap_or(false) {
+        filter.push_str("&deleted_at=is.null");
+    }
+    if let Some(from) = &query.from {
+        filter.push_str(&format!("&tanggal=gte.{}", from));
+    }
+    if let Some(to) = &query.to {
+        filter.push_str(&format!("&tanggal=lte.{}", to));
+    }
+    filter
+}
+
+pub async fn get_schedule_exceptions_by_dokter(dokter_id: Uuid, query: &ScheduleQueryDto) -> Result<Vec<ScheduleException>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?dokter_id=eq.{}&{}", supabase_url, TABLE_NAME, dokter_id, range_filter(query)))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch schedule exceptions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ScheduleException>>()
+            .await
+            .map_err(|e| format!("Failed to parse schedule exceptions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_schedule_exceptions_on_date(dokter_id: Uuid, tanggal: &str) -> Result<Vec<ScheduleException>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?dokter_id=eq.{}&tanggal=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, dokter_id, tanggal))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch schedule exceptions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ScheduleException>>()
+            .await
+            .map_err(|e| format!("Failed to parse schedule exceptions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_schedule_exception_by_id(id: Uuid) -> Result<ScheduleException, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch schedule exception: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<ScheduleException> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse schedule exception: {}", e))?;
+        rows.pop().ok_or_else(|| "Schedule exception not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_schedule_exception(data: &CreateScheduleExceptionDto) -> Result<ScheduleException, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create schedule exception: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut rows: Vec<ScheduleException> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created schedule exception: {}", e))?;
+        rows.pop().ok_or_else(|| "Failed to get created schedule exception".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_schedule_exception(id: Uuid, deleted_by: &str) -> Result<ScheduleException, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete schedule exception: {}", e))?;
+
+    if res.status().is_success() {
+        let mut deleted: Vec<ScheduleException> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted schedule exception: {}", e))?;
+        deleted.pop().ok_or_else(|| "Schedule exception not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_schedule_exception(id: Uuid) -> Result<ScheduleException, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore schedule exception: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<ScheduleException> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored schedule exception: {}", e))?;
+        restored.pop().ok_or_else(|| "Schedule exception not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_schedule_exceptions(cutoff: DateTime<Utc>) -> Result<Vec<ScheduleException>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge schedule exceptions: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<ScheduleException>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged schedule exceptions: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -1,6 +1,8 @@
 use crate::dtos::skin_analysis_dto::{CreateSkinAnalysisDto, UpdateSkinAnalysisDto};
 use crate::models::skin_analysis::SkinAnalysis;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -13,10 +15,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_skin_analyses() -> Result<Vec<SkinAnalysis>, String> {
+pub async fn get_all_skin_analyses(include_deleted: bool) -> Result<Vec<SkinAnalysis>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
         .send()
@@ -32,6 +35,45 @@ pub async fn get_all_skin_analyses() -> Result<Vec<SkinAnalysis>, String> {
     }
 }
 
+pub async fn get_skin_analyses_by_pasien(pasien_id: Uuid) -> Result<Vec<SkinAnalysis>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?pasien_id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, pasien_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch skin analyses: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<SkinAnalysis>>()
+            .await
+            .map_err(|e| format!("Failed to parse skin analyses: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_skin_analysis_by_id(id: Uuid) -> Result<SkinAnalysis, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch skin analysis: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<SkinAnalysis> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse skin analysis: {}", e))?;
+        rows.pop().ok_or_else(|| "Skin analysis not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_skin_analysis(analysis_data: &CreateSkinAnalysisDto) -> Result<SkinAnalysis, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -78,19 +120,110 @@ pub async fn update_skin_analysis(id: Uuid, analysis_data: &UpdateSkinAnalysisDt
     }
 }
 
-pub async fn delete_skin_analysis(id: Uuid) -> Result<(), String> {
+// Writes only the two recommendation columns, leaving the rest of the analysis untouched
+pub async fn set_skin_analysis_recommendations(id: Uuid, treatment_ids: &[Uuid], product_ids: &[Uuid]) -> Result<SkinAnalysis, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "rekomendasi_treatment": treatment_ids,
+        "rekomendasi_produk": product_ids
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update skin analysis recommendations: {}", e))?;
+
+    if res.status().is_success() {
+        let mut analyses: Vec<SkinAnalysis> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated skin analysis: {}", e))?;
+        analyses.pop().ok_or_else(|| "Failed to get updated skin analysis".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_skin_analysis(id: Uuid, deleted_by: &str) -> Result<SkinAnalysis, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete skin analysis: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<SkinAnalysis> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted skin analysis: {}", e))?;
+        deleted.pop().ok_or_else(|| "Skin analysis not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_skin_analysis(id: Uuid) -> Result<SkinAnalysis, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore skin analysis: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<SkinAnalysis> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored skin analysis: {}", e))?;
+        restored.pop().ok_or_else(|| "Skin analysis not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_skin_analyses(cutoff: DateTime<Utc>) -> Result<Vec<SkinAnalysis>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge skin analyses: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<SkinAnalysis>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged skin analyses: {}", e))
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
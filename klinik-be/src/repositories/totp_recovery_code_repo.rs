@@ -0,0 +1,99 @@
+use crate::dtos::totp_dto::CreateTotpRecoveryCodeDto;
+use crate::models::totp_recovery_code::TotpRecoveryCode;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "totp_recovery_codes";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_unused_recovery_code(user_id: Uuid, code_hash: &str) -> Result<Option<TotpRecoveryCode>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!(
+            "{}/rest/v1/{}?user_id=eq.{}&code_hash=eq.{}&used_at=is.null",
+            supabase_url, TABLE_NAME, user_id, code_hash
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch recovery code: {}", e))?;
+
+    if res.status().is_success() {
+        let mut codes: Vec<TotpRecoveryCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse recovery code: {}", e))?;
+        Ok(codes.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn create_recovery_codes(codes: &[CreateTotpRecoveryCodeDto]) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .json(&codes)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create recovery codes: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Marks the code used. Returns false if it was already used by a concurrent request.
+pub async fn use_recovery_code(id: Uuid) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&used_at=is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "used_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to use recovery code: {}", e))?;
+
+    if res.status().is_success() {
+        let codes: Vec<TotpRecoveryCode> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse used recovery code: {}", e))?;
+        Ok(!codes.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_recovery_codes(user_id: Uuid) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!("{}/rest/v1/{}?user_id=eq.{}", supabase_url, TABLE_NAME, user_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete recovery codes: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
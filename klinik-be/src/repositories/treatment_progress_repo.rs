@@ -1,6 +1,8 @@
 use crate::dtos::treatment_progress_dto::{CreateTreatmentProgressDto, UpdateTreatmentProgressDto};
 use crate::models::treatment_progress::TreatmentProgress;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -13,10 +15,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_treatment_progress() -> Result<Vec<TreatmentProgress>, String> {
+pub async fn get_all_treatment_progress(include_deleted: bool) -> Result<Vec<TreatmentProgress>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
         .send()
@@ -32,6 +35,45 @@ pub async fn get_all_treatment_progress() -> Result<Vec<TreatmentProgress>, Stri
     }
 }
 
+pub async fn get_treatment_progress_by_pasien(pasien_id: Uuid) -> Result<Vec<TreatmentProgress>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?pasien_id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, pasien_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch treatment progress: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<TreatmentProgress>>()
+            .await
+            .map_err(|e| format!("Failed to parse treatment progress: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_treatment_progress_by_id(id: Uuid) -> Result<TreatmentProgress, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch treatment progress: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<TreatmentProgress> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse treatment progress: {}", e))?;
+        rows.pop().ok_or_else(|| "Treatment progress not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_treatment_progress(treatment_progress_data: &CreateTreatmentProgressDto) -> Result<TreatmentProgress, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -78,19 +120,82 @@ pub async fn update_treatment_progress(id: Uuid, treatment_progress_data: &Updat
     }
 }
 
-pub async fn delete_treatment_progress(id: Uuid) -> Result<(), String> {
+pub async fn delete_treatment_progress(id: Uuid, deleted_by: &str) -> Result<TreatmentProgress, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete treatment progress: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<TreatmentProgress> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted treatment progress: {}", e))?;
+        deleted.pop().ok_or_else(|| "Treatment progress not found or already deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn restore_treatment_progress(id: Uuid) -> Result<TreatmentProgress, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore treatment progress: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<TreatmentProgress> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored treatment progress: {}", e))?;
+        restored.pop().ok_or_else(|| "Treatment progress not found or not deleted".to_string())
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_treatment_progress(cutoff: DateTime<Utc>) -> Result<Vec<TreatmentProgress>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge treatment progress: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<TreatmentProgress>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged treatment progress: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
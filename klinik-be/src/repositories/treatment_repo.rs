@@ -1,6 +1,8 @@
 use crate::dtos::treatment_dto::{CreateTreatmentDto, UpdateTreatmentDto};
 use crate::models::treatment::Treatment;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
 
@@ -13,10 +15,11 @@ async fn get_supabase_client_and_keys() -> Result<(Client, String, String), Stri
     Ok((client, supabase_url, supabase_key))
 }
 
-pub async fn get_all_treatments() -> Result<Vec<Treatment>, String> {
+pub async fn get_all_treatments(include_deleted: bool) -> Result<Vec<Treatment>, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let filter = if include_deleted { "" } else { "?deleted_at=is.null" };
     let res = client
-        .get(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .get(format!("{}/rest/v1/{}{}", supabase_url, TABLE_NAME, filter))
         .header("apikey", &supabase_key)
         .send()
         .await
@@ -31,6 +34,26 @@ pub async fn get_all_treatments() -> Result<Vec<Treatment>, String> {
     }
 }
 
+pub async fn get_treatment_by_id(id: Uuid) -> Result<Treatment, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch treatment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut rows: Vec<Treatment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse treatment: {}", e))?;
+        rows.pop().ok_or_else(|| "Treatment not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
 pub async fn create_treatment(treatment_data: &CreateTreatmentDto) -> Result<Treatment, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
     let res = client
@@ -77,19 +100,82 @@ pub async fn update_treatment(id: Uuid, treatment_data: &UpdateTreatmentDto) ->
     }
 }
 
-pub async fn delete_treatment(id: Uuid) -> Result<(), String> {
+pub async fn delete_treatment(id: Uuid, deleted_by: &str) -> Result<Treatment, String> {
     let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
     let res = client
-        .delete(format!("{}/rest/v1/{}?id=eq.{}", supabase_url, TABLE_NAME, id))
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=is.null", supabase_url, TABLE_NAME, id))
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
         .send()
         .await
         .map_err(|e| format!("Failed to delete treatment: {}", e))?;
 
-    if res.status() == StatusCode::NO_CONTENT {
-        Ok(())
+    if res.status().is_success() {
+        let mut deleted: Vec<Treatment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse deleted treatment: {}", e))?;
+        deleted.pop().ok_or_else(|| "Treatment not found or already deleted".to_string())
     } else {
         Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
     }
-}
\ No newline at end of file
+}
+
+pub async fn restore_treatment(id: Uuid) -> Result<Treatment, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&deleted_at=not.is.null", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to restore treatment: {}", e))?;
+
+    if res.status().is_success() {
+        let mut restored: Vec<Treatment> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse restored treatment: {}", e))?;
+        restored.pop().ok_or_else(|| "Treatment not found or not deleted".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+pub async fn purge_deleted_treatments(cutoff: DateTime<Utc>) -> Result<Vec<Treatment>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge treatments: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<Treatment>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged treatments: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -1,10 +1,25 @@
-use crate::dtos::user_dto::{RegisterUserDto, ForgotPasswordDto, LoginUserDto, UpdatePasswordDto};
+use crate::auth::auth_provider::INVALID_LOGIN;
+use crate::dtos::auth_dto::SessionTokens;
+use crate::dtos::user_dto::{RegisterUserDto, ForgotPasswordDto, LoginUserDto, UpdateUserDto};
 use crate::models::user::User;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::{DateTime, Utc};
 use reqwest::{Client, StatusCode};
-use serde_json::{json, Value};
+use serde_json::json;
 use std::env;
 use uuid::Uuid;
-pub async fn register_user(user_data: &RegisterUserDto) -> Result<(), String> {
+
+// Where Supabase's confirmation and recovery emails send the user
+pub fn verify_redirect_url() -> String {
+    env::var("AUTH_VERIFY_REDIRECT_URL").unwrap_or_else(|_| "http://localhost:3000/verify-code".to_string())
+}
+
+pub fn reset_redirect_url() -> String {
+    env::var("AUTH_RESET_REDIRECT_URL").unwrap_or_else(|_| "http://localhost:3000/reset-password".to_string())
+}
+
+pub async fn register_user(user_data: &RegisterUserDto) -> Result<User, String> {
     let client = reqwest::Client::new();
     let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
     let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
@@ -14,12 +29,10 @@ pub async fn register_user(user_data: &RegisterUserDto) -> Result<(), String> {
         "email": user_data.email,
         "password": user_data.password
     });
-    
-    // UBAH BAGIAN INI
-    let redirect_url = "http://localhost:3000/verify-code";
 
     let auth_res = client
-        .post(format!("{}/auth/v1/signup?redirect_to={}", supabase_url, redirect_url)) // <--- Tambahkan ini
+        .post(format!("{}/auth/v1/signup", supabase_url))
+        .query(&[("redirect_to", verify_redirect_url())])
         .header("apikey", &supabase_key)
         .json(&auth_body)
         .send()
@@ -38,61 +51,97 @@ pub async fn register_user(user_data: &RegisterUserDto) -> Result<(), String> {
     // ... (kode untuk menyimpan data ke database lainnya tetap sama)
     let auth_data: serde_json::Value = auth_res.json().await.map_err(|e| format!("Failed to parse auth response: {}", e))?;
     let user_id = auth_data["user"]["id"].as_str().ok_or("User ID not found")?.to_string(); // Supabase Auth v1 sekarang mengembalikan 'user.id' bukan 'id' langsung. Pastikan ini sesuai dengan versi Supabase-mu.
-    
-    // ... (kode untuk menyimpan data ke database lainnya tetap sama)
-    let db_body = json!({
-        "id": user_id,
-        "name": user_data.name,
-        "position": user_data.position,
-        "email": user_data.email
-    });
-    
-    let db_res = client
-        .post(format!("{}/rest/v1/users", supabase_url))
+
+    create_user(&User {
+        id: user_id.parse().map_err(|_| "Invalid user ID from Supabase Auth".to_string())?,
+        name: user_data.name.clone(),
+        position: user_data.position.clone(),
+        email: user_data.email.clone(),
+        deactivated_at: None,
+    })
+    .await
+}
+
+// Creates the Supabase Auth account and emails an invitation link that lets the user pick
+// a password. Needs the service role key in SUPABASE_KEY. Returns the new auth user id.
+pub async fn invite_auth_user(email: &str) -> Result<Uuid, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .post(format!("{}/auth/v1/invite", supabase_url))
+        .query(&[("redirect_to", reset_redirect_url())])
         .header("apikey", &supabase_key)
         .header("Authorization", format!("Bearer {}", &supabase_key))
-        .header("Content-Type", "application/json")
-        .header("Prefer", "return=representation")
-        .json(&db_body)
+        .json(&json!({ "email": email }))
         .send()
         .await
-        .map_err(|e| format!("Failed to insert user into DB: {}", e))?;
+        .map_err(|e| format!("Failed to invite user: {}", e))?;
 
-    if db_res.status() != StatusCode::CREATED {
-        let error_text = db_res.text().await.unwrap_or_default();
-        return Err(format!("Supabase DB error: {}", error_text));
+    if !res.status().is_success() {
+        let error_text = res.text().await.unwrap_or_default();
+        if error_text.contains("already been registered") {
+            return Err("Email ini sudah terdaftar.".to_string());
+        }
+        return Err(format!("Supabase Auth error: {}", error_text));
     }
-
-    Ok(())
+    let auth_data: serde_json::Value = res.json().await.map_err(|e| format!("Failed to parse invite response: {}", e))?;
+    auth_data["id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| "User ID not found".to_string())
 }
 
-pub async fn send_password_reset_link(forgot_data: &ForgotPasswordDto) -> Result<(), String> {
+// Replaces the Supabase Auth password, e.g. with a random one so the old password stops working
+pub async fn set_auth_user_password(id: Uuid, password: &str) -> Result<(), String> {
     let client = Client::new();
     let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
     let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
 
-    let body = json!({
-        "email": forgot_data.email
-    });
-
     let res = client
-        .post(format!("{}/auth/v1/recover", supabase_url))
+        .put(format!("{}/auth/v1/admin/users/{}", supabase_url, id))
         .header("apikey", &supabase_key)
-        .header("Content-Type", "application/json")
-        .json(&body)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .json(&json!({ "password": password }))
         .send()
         .await
-        .map_err(|e| format!("Failed to send reset email request: {}", e))?;
+        .map_err(|e| format!("Failed to update auth user: {}", e))?;
 
-    if res.status() == StatusCode::OK {
+    if res.status().is_success() {
         Ok(())
     } else {
-        let error_text = res.text().await.unwrap_or_default();
-        Err(format!("Supabase error: {}", error_text))
+        Err(format!("Supabase Auth error: {}", res.text().await.unwrap_or_default()))
     }
 }
 
-pub async fn forgot_password_request(forgot_data: &ForgotPasswordDto) -> Result<(), String> {
+// Inserts the profile row in `users`; the id comes from whichever auth provider created the account
+pub async fn create_user(user: &User) -> Result<User, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let db_res = client
+        .post(format!("{}/rest/v1/users", supabase_url))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(user)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to insert user into DB: {}", e))?;
+
+    if db_res.status() != StatusCode::CREATED {
+        let error_text = db_res.text().await.unwrap_or_default();
+        return Err(format!("Supabase DB error: {}", error_text));
+    }
+
+    let mut users: Vec<User> = db_res.json().await.map_err(|e| format!("Failed to parse created user: {}", e))?;
+    users.pop().ok_or_else(|| "Failed to get created user".to_string())
+}
+
+pub async fn send_password_reset_link(forgot_data: &ForgotPasswordDto) -> Result<(), String> {
     let client = Client::new();
     let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
     let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
@@ -100,12 +149,10 @@ pub async fn forgot_password_request(forgot_data: &ForgotPasswordDto) -> Result<
     let body = json!({
         "email": forgot_data.email
     });
-    
-    // UBAH BAGIAN INI
-    let redirect_url = "http://localhost:3000/reset-password";
 
     let res = client
-        .post(format!("{}/auth/v1/recover?redirect_to={}", supabase_url, redirect_url)) // <--- Tambahkan ini
+        .post(format!("{}/auth/v1/recover", supabase_url))
+        .query(&[("redirect_to", reset_redirect_url())])
         .header("apikey", &supabase_key)
         .header("Content-Type", "application/json")
         .json(&body)
@@ -145,9 +192,30 @@ pub async fn reset_password(token: &str, new_password: &str) -> Result<(), Strin
     }
 }
 
-pub async fn login_user(login_data: &LoginUserDto) -> Result<String, String> {
+// Supabase's access token carries its session id; the token comes straight from Supabase,
+// so the payload is read without checking the signature
+fn session_tokens(json_res: &serde_json::Value) -> Result<SessionTokens, String> {
+    let access_token = json_res["access_token"].as_str().ok_or("Access token not found")?.to_string();
+    let payload = access_token.split('.').nth(1).ok_or("Malformed access token")?;
+    let claims: serde_json::Value = URL_SAFE_NO_PAD
+        .decode(payload)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .ok_or("Malformed access token")?;
+    let parse_uuid = |value: &serde_json::Value| value.as_str().and_then(|v| v.parse::<Uuid>().ok());
+    Ok(SessionTokens {
+        user_id: parse_uuid(&claims["sub"]).ok_or("User ID not found")?,
+        session_id: parse_uuid(&claims["session_id"]).ok_or("Session ID not found")?,
+        refresh_token: json_res["refresh_token"].as_str().ok_or("Refresh token not found")?.to_string(),
+        expires_in: json_res["expires_in"].as_i64().unwrap_or_default(),
+        access_token,
+    })
+}
+
+pub async fn login_user(login_data: &LoginUserDto) -> Result<SessionTokens, String> {
     let client = reqwest::Client::new();
     let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
 
     let body = json!({
         "email": login_data.email,
@@ -156,7 +224,7 @@ pub async fn login_user(login_data: &LoginUserDto) -> Result<String, String> {
 
     let res = client
         .post(format!("{}/auth/v1/token?grant_type=password", supabase_url))
-        .header("apikey", env::var("SUPABASE_KEY").unwrap())
+        .header("apikey", &supabase_key)
         .json(&body)
         .send()
         .await
@@ -164,9 +232,205 @@ pub async fn login_user(login_data: &LoginUserDto) -> Result<String, String> {
 
     if res.status().is_success() {
         let json_res: serde_json::Value = res.json().await.map_err(|e| format!("Failed to parse response: {}", e))?;
-        let access_token = json_res["access_token"].as_str().ok_or("Access token not found")?.to_string();
-        Ok(access_token)
+        return session_tokens(&json_res);
+    }
+    let status = res.status();
+    let body = res.text().await.unwrap_or_default();
+    // Older GoTrue versions only say "invalid_grant"; newer ones add error_code
+    if status == StatusCode::BAD_REQUEST
+        && (body.contains("invalid_credentials") || body.contains("Invalid login credentials"))
+    {
+        Err(INVALID_LOGIN.to_string())
     } else {
-        Err(format!("Login failed: {}", res.text().await.unwrap_or_default()))
+        Err(format!("Login failed: {}", body))
     }
-}
\ No newline at end of file
+}
+
+// Supabase rotates the refresh token itself; the old one stops working
+pub async fn refresh_session(refresh_token: &str) -> Result<SessionTokens, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .post(format!("{}/auth/v1/token?grant_type=refresh_token", supabase_url))
+        .header("apikey", &supabase_key)
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send refresh request: {}", e))?;
+
+    if res.status().is_success() {
+        let json_res: serde_json::Value = res.json().await.map_err(|e| format!("Failed to parse response: {}", e))?;
+        session_tokens(&json_res)
+    } else {
+        Err(format!("Refresh failed: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Ends the session on Supabase's side as well, so its refresh token can't be used directly
+pub async fn logout_session(access_token: &str) -> Result<(), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .post(format!("{}/auth/v1/logout?scope=local", supabase_url))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", access_token))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send logout request: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Logout failed: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Confirms a sign-up with the code from Supabase's confirmation email
+pub async fn verify_email_code(email: &str, code: &str) -> Result<(), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .post(format!("{}/auth/v1/verify", supabase_url))
+        .header("apikey", &supabase_key)
+        .json(&json!({ "type": "signup", "email": email, "token": code }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to verify email: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Verifikasi email gagal: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn resend_verification_email(email: &str) -> Result<(), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .post(format!("{}/auth/v1/resend", supabase_url))
+        .query(&[("redirect_to", verify_redirect_url())])
+        .header("apikey", &supabase_key)
+        .json(&json!({ "type": "signup", "email": email }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to resend verification email: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_user_by_email(email: &str) -> Result<Option<User>, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .get(format!("{}/rest/v1/users", supabase_url))
+        .query(&[("email", format!("eq.{}", email))])
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch user: {}", e))?;
+
+    if res.status().is_success() {
+        let mut users: Vec<User> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse user: {}", e))?;
+        Ok(users.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_all_users(include_deactivated: bool) -> Result<Vec<User>, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    let filter = if include_deactivated { "" } else { "&deactivated_at=is.null" };
+
+    let res = client
+        .get(format!("{}/rest/v1/users?order=name.asc{}", supabase_url, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch users: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<User>>()
+            .await
+            .map_err(|e| format!("Failed to parse users: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+async fn patch_user(id: Uuid, changes: &serde_json::Value) -> Result<User, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .patch(format!("{}/rest/v1/users?id=eq.{}", supabase_url, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(changes)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update user: {}", e))?;
+
+    if res.status().is_success() {
+        let mut users: Vec<User> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated user: {}", e))?;
+        users.pop().ok_or_else(|| "User not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn update_user(id: Uuid, user_data: &UpdateUserDto) -> Result<User, String> {
+    patch_user(id, &json!(user_data)).await
+}
+
+pub async fn set_user_deactivated(id: Uuid, deactivated_at: Option<DateTime<Utc>>) -> Result<User, String> {
+    patch_user(id, &json!({ "deactivated_at": deactivated_at })).await
+}
+
+pub async fn get_user_by_id(id: &str) -> Result<User, String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+
+    let res = client
+        .get(format!("{}/rest/v1/users?id=eq.{}", supabase_url, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch user: {}", e))?;
+
+    if res.status().is_success() {
+        let mut users: Vec<User> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse user: {}", e))?;
+        users.pop().ok_or_else(|| "User not found".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,111 @@
+use crate::dtos::totp_dto::CreateUserTotpDto;
+use crate::models::user_totp::UserTotp;
+use chrono::Utc;
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "user_totp";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+pub async fn get_user_totp(user_id: Uuid) -> Result<Option<UserTotp>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?user_id=eq.{}", supabase_url, TABLE_NAME, user_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch user totp: {}", e))?;
+
+    if res.status().is_success() {
+        let mut totps: Vec<UserTotp> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse user totp: {}", e))?;
+        Ok(totps.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Replaces any unconfirmed secret of the user with a new one
+pub async fn upsert_user_totp(totp_data: &CreateUserTotpDto) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "resolution=merge-duplicates")
+        .json(&json!({
+            "user_id": totp_data.user_id,
+            "secret": totp_data.secret,
+            "confirmed_at": null,
+            "last_used_step": null,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to save user totp: {}", e))?;
+
+    if res.status() == StatusCode::CREATED || res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Records an accepted code's time step. Guarded so the same or an older step can't be
+// accepted twice, even by concurrent requests; returns false if it was.
+pub async fn record_used_step(user_id: Uuid, step: i64, confirm: bool) -> Result<bool, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let mut changes = json!({ "last_used_step": step });
+    if confirm {
+        changes["confirmed_at"] = json!(Utc::now());
+    }
+    let res = client
+        .patch(format!(
+            "{}/rest/v1/{}?user_id=eq.{}&or=(last_used_step.is.null,last_used_step.lt.{})",
+            supabase_url, TABLE_NAME, user_id, step
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&changes)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update user totp: {}", e))?;
+
+    if res.status().is_success() {
+        let totps: Vec<UserTotp> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated user totp: {}", e))?;
+        Ok(!totps.is_empty())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn delete_user_totp(user_id: Uuid) -> Result<(), String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!("{}/rest/v1/{}?user_id=eq.{}", supabase_url, TABLE_NAME, user_id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to delete user totp: {}", e))?;
+
+    if res.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,183 @@
+use crate::dtos::waitlist_dto::{CreateWaitlistEntryDto, WaitlistQueryDto};
+use crate::models::waitlist_entry::WaitlistEntry;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "waitlist_entries";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+async fn fetch_entries(filter: &str) -> Result<Vec<WaitlistEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch waitlist entries: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<WaitlistEntry>>()
+            .await
+            .map_err(|e| format!("Failed to parse waitlist entries: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_waitlist_entries(query: &WaitlistQueryDto) -> Result<Vec<WaitlistEntry>, String> {
+    let mut filter = String::from("order=created_at.asc");
+    if !query.include_deleted.unwrap_or(false) {
+        filter.push_str("&deleted_at=is.null");
+    }
+    if let Some(status) = &query.status {
+        filter.push_str(&format!("&status=eq.{}", status));
+    }
+    if let Some(dokter_id) = query.dokter_id {
+        filter.push_str(&format!("&dokter_id=eq.{}", dokter_id));
+    }
+    fetch_entries(&filter).await
+}
+
+pub async fn get_waitlist_entries_by_pasien(pasien_id: Uuid) -> Result<Vec<WaitlistEntry>, String> {
+    fetch_entries(&format!("pasien_id=eq.{}&deleted_at=is.null", pasien_id)).await
+}
+
+// Waiting entries whose date window includes `tanggal`, oldest first
+pub async fn get_waiting_entries_on_date(tanggal: &str) -> Result<Vec<WaitlistEntry>, String> {
+    fetch_entries(&format!(
+        "status=eq.waiting&date_from=lte.{}&date_to=gte.{}&deleted_at=is.null&order=created_at.asc",
+        tanggal, tanggal
+    ))
+    .await
+}
+
+pub async fn get_waitlist_entry_by_id(id: Uuid) -> Result<WaitlistEntry, String> {
+    fetch_entries(&format!("id=eq.{}", id))
+        .await?
+        .pop()
+        .ok_or_else(|| "Waitlist entry not found".to_string())
+}
+
+pub async fn create_waitlist_entry(entry_data: &CreateWaitlistEntryDto) -> Result<WaitlistEntry, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&entry_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create waitlist entry: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut entries: Vec<WaitlistEntry> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created waitlist entry: {}", e))?;
+        entries.pop().ok_or_else(|| "Failed to get created waitlist entry".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+async fn patch_entries(filter: &str, body: &serde_json::Value) -> Result<Vec<WaitlistEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update waitlist entry: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<WaitlistEntry>>()
+            .await
+            .map_err(|e| format!("Failed to parse updated waitlist entry: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Changes the status only while it is one of `from_statuses`, so two freed slots can't
+// offer the same entry at once. Returns None when the entry had already moved on.
+pub async fn set_waitlist_entry_status(id: Uuid, status: &str, from_statuses: &[&str]) -> Result<Option<WaitlistEntry>, String> {
+    Ok(patch_entries(
+        &format!("id=eq.{}&status=in.({})", id, from_statuses.join(",")),
+        &json!({ "status": status }),
+    )
+    .await?
+    .pop())
+}
+
+// Waiting entries whose window ended before `tanggal`
+pub async fn expire_waitlist_entries_before(tanggal: &str) -> Result<Vec<WaitlistEntry>, String> {
+    patch_entries(
+        &format!("status=eq.waiting&date_to=lt.{}&deleted_at=is.null", tanggal),
+        &json!({ "status": "expired" }),
+    )
+    .await
+}
+
+pub async fn delete_waitlist_entry(id: Uuid, deleted_by: &str) -> Result<WaitlistEntry, String> {
+    let body = json!({
+        "deleted_at": Utc::now(),
+        "deleted_by": deleted_by
+    });
+    patch_entries(&format!("id=eq.{}&deleted_at=is.null", id), &body)
+        .await?
+        .pop()
+        .ok_or_else(|| "Waitlist entry not found or already deleted".to_string())
+}
+
+pub async fn restore_waitlist_entry(id: Uuid) -> Result<WaitlistEntry, String> {
+    let body = json!({
+        "deleted_at": null,
+        "deleted_by": null
+    });
+    patch_entries(&format!("id=eq.{}&deleted_at=not.is.null", id), &body)
+        .await?
+        .pop()
+        .ok_or_else(|| "Waitlist entry not found or not deleted".to_string())
+}
+
+// Permanently removes rows soft-deleted before `cutoff`, returning the purged rows.
+// Their offers go with them (ON DELETE CASCADE).
+pub async fn purge_deleted_waitlist_entries(cutoff: DateTime<Utc>) -> Result<Vec<WaitlistEntry>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .delete(format!(
+            "{}/rest/v1/{}?deleted_at=lt.{}",
+            supabase_url,
+            TABLE_NAME,
+            cutoff.format("%Y-%m-%dT%H:%M:%SZ")
+        ))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Prefer", "return=representation")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to purge waitlist entries: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<WaitlistEntry>>()
+            .await
+            .map_err(|e| format!("Failed to parse purged waitlist entries: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
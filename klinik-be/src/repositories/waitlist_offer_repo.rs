@@ -0,0 +1,109 @@
+use crate::dtos::waitlist_dto::CreateWaitlistOfferDto;
+use crate::models::waitlist_offer::WaitlistOffer;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, StatusCode};
+use serde_json::json;
+use std::env;
+use uuid::Uuid;
+
+const TABLE_NAME: &str = "waitlist_offers";
+
+async fn get_supabase_client_and_keys() -> Result<(Client, String, String), String> {
+    let client = Client::new();
+    let supabase_url = env::var("SUPABASE_URL").map_err(|_| "SUPABASE_URL not set".to_string())?;
+    let supabase_key = env::var("SUPABASE_KEY").map_err(|_| "SUPABASE_KEY not set".to_string())?;
+    Ok((client, supabase_url, supabase_key))
+}
+
+async fn fetch_offers(filter: &str) -> Result<Vec<WaitlistOffer>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .get(format!("{}/rest/v1/{}?{}", supabase_url, TABLE_NAME, filter))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch waitlist offers: {}", e))?;
+
+    if res.status().is_success() {
+        res.json::<Vec<WaitlistOffer>>()
+            .await
+            .map_err(|e| format!("Failed to parse waitlist offers: {}", e))
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+pub async fn get_waitlist_offer_by_id(id: Uuid) -> Result<WaitlistOffer, String> {
+    fetch_offers(&format!("id=eq.{}", id))
+        .await?
+        .pop()
+        .ok_or_else(|| "Waitlist offer not found".to_string())
+}
+
+pub async fn get_waitlist_offer_by_token_hash(token_hash: &str) -> Result<WaitlistOffer, String> {
+    fetch_offers(&format!("token_hash=eq.{}", token_hash))
+        .await?
+        .pop()
+        .ok_or_else(|| "Waitlist offer not found".to_string())
+}
+
+pub async fn get_waitlist_offers_by_entry(waitlist_entry_id: Uuid) -> Result<Vec<WaitlistOffer>, String> {
+    fetch_offers(&format!("waitlist_entry_id=eq.{}&order=created_at.asc", waitlist_entry_id)).await
+}
+
+// Every offer ever made for one doctor's slot, so nobody is offered it twice
+pub async fn get_waitlist_offers_for_slot(dokter_id: Uuid, tanggal: &str, waktu: &str) -> Result<Vec<WaitlistOffer>, String> {
+    fetch_offers(&format!("dokter_id=eq.{}&tanggal=eq.{}&waktu=eq.{}", dokter_id, tanggal, waktu)).await
+}
+
+pub async fn get_expired_pending_offers(now: DateTime<Utc>) -> Result<Vec<WaitlistOffer>, String> {
+    fetch_offers(&format!("status=eq.pending&expires_at=lt.{}", now.format("%Y-%m-%dT%H:%M:%SZ"))).await
+}
+
+pub async fn create_waitlist_offer(offer_data: &CreateWaitlistOfferDto) -> Result<WaitlistOffer, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .post(format!("{}/rest/v1/{}", supabase_url, TABLE_NAME))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&offer_data)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to create waitlist offer: {}", e))?;
+
+    if res.status() == StatusCode::CREATED {
+        let mut offers: Vec<WaitlistOffer> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse created waitlist offer: {}", e))?;
+        offers.pop().ok_or_else(|| "Failed to get created waitlist offer".to_string())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
+
+// Settles a pending offer. Returns None if it was already accepted, declined or expired.
+pub async fn respond_to_waitlist_offer(id: Uuid, status: &str) -> Result<Option<WaitlistOffer>, String> {
+    let (client, supabase_url, supabase_key) = get_supabase_client_and_keys().await?;
+    let res = client
+        .patch(format!("{}/rest/v1/{}?id=eq.{}&status=eq.pending", supabase_url, TABLE_NAME, id))
+        .header("apikey", &supabase_key)
+        .header("Authorization", format!("Bearer {}", &supabase_key))
+        .header("Content-Type", "application/json")
+        .header("Prefer", "return=representation")
+        .json(&json!({ "status": status, "responded_at": Utc::now() }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to update waitlist offer: {}", e))?;
+
+    if res.status().is_success() {
+        let mut offers: Vec<WaitlistOffer> = res.json()
+            .await
+            .map_err(|e| format!("Failed to parse updated waitlist offer: {}", e))?;
+        Ok(offers.pop())
+    } else {
+        Err(format!("Supabase error: {}", res.text().await.unwrap_or_default()))
+    }
+}
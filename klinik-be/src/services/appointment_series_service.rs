@@ -0,0 +1,351 @@
+use crate::dtos::appointment_dto::{CreateAppointmentDto, UpdateAppointmentDto};
+use crate::dtos::appointment_series_dto::{
+    CreateAppointmentSeriesDto, OccurrenceResultDto, SeriesDetailDto, SeriesResultDto, UpdateSeriesOccurrencesDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::appointment::Appointment;
+use crate::repositories::{appointment_repo, appointment_series_repo};
+use crate::services::clinic_resource_service::DayBookings;
+use crate::services::schedule_service::{self, DoctorDay};
+use crate::services::{appointment_service, audit_service, safety_service};
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::json;
+use uuid::Uuid;
+
+const RESOURCE: &str = "appointment_series";
+const MAX_OCCURRENCES: i32 = 52;
+const MAX_INTERVAL_DAYS: i32 = 365;
+const MAX_SHIFT_DAYS: i64 = 365;
+
+fn parse_date(tanggal: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(tanggal, "%Y-%m-%d").map_err(|_| "Tanggal harus berformat YYYY-MM-DD".to_string())
+}
+
+// One occurrence must fit the doctor's schedule, not overlap their other appointments and
+// leave a room or machine free for every treatment. Returns the resources it would get.
+async fn check_occurrence(
+    dokter_id: Uuid,
+    treatment_ids: &[Uuid],
+    tanggal: &str,
+    waktu: &str,
+    exclude: &[Uuid],
+) -> Result<Vec<Uuid>, String> {
+    let time = schedule_service::parse_time(waktu).ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    let bookings = DayBookings::load(tanggal).await?;
+    let (start, end) = bookings
+        .span(treatment_ids, waktu)
+        .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    DoctorDay::load(dokter_id, tanggal).await?.check(time, end - start)?;
+    check_against(&bookings, dokter_id, treatment_ids, tanggal, waktu, exclude)
+}
+
+// The part of `check_occurrence` that only needs the day's bookings. `exclude` holds the
+// appointments that are moving themselves and so don't block anything.
+fn check_against(
+    bookings: &DayBookings,
+    dokter_id: Uuid,
+    treatment_ids: &[Uuid],
+    tanggal: &str,
+    waktu: &str,
+    exclude: &[Uuid],
+) -> Result<Vec<Uuid>, String> {
+    let (start, end) = bookings
+        .span(treatment_ids, waktu)
+        .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    if bookings
+        .overlapping(start, end, exclude)
+        .iter()
+        .any(|appointment| appointment.dokter_id == dokter_id)
+    {
+        return Err(format!("Dokter sudah memiliki janji pada {} {}", tanggal, waktu));
+    }
+    bookings.allocate(treatment_ids, waktu, exclude)
+}
+
+fn occurrence_result(appointment: &Appointment, conflict: Option<String>) -> OccurrenceResultDto {
+    OccurrenceResultDto {
+        series_index: appointment.series_index.unwrap_or_default(),
+        tanggal: appointment.tanggal.clone(),
+        waktu: appointment.waktu.clone(),
+        appointment_id: Some(appointment.id),
+        conflict,
+    }
+}
+
+pub async fn handle_get_appointment_series(id: Uuid) -> Result<SeriesDetailDto, String> {
+    let series = appointment_series_repo::get_appointment_series_by_id(id).await?;
+    let appointments = appointment_repo::get_appointments_by_series(id).await?;
+    Ok(SeriesDetailDto { series, appointments })
+}
+
+// Books every occurrence of a series. Unless `skip_conflicts` is set, one conflicting
+// occurrence books nothing and the per-occurrence report is returned with applied = false.
+pub async fn handle_create_appointment_series(
+    mut series_data: CreateAppointmentSeriesDto,
+    actor: &AuthenticatedUser,
+) -> Result<SeriesResultDto, String> {
+    if !(1..=MAX_OCCURRENCES).contains(&series_data.occurrences) {
+        return Err(format!("occurrences harus antara 1 dan {}", MAX_OCCURRENCES));
+    }
+    if !(1..=MAX_INTERVAL_DAYS).contains(&series_data.interval_days) {
+        return Err(format!("interval_days harus antara 1 dan {}", MAX_INTERVAL_DAYS));
+    }
+    if series_data.treatment_ids.is_empty() {
+        return Err("Pilih minimal satu treatment".to_string());
+    }
+    let start = parse_date(&series_data.start_tanggal)?;
+    let today = Utc::now().with_timezone(&appointment_service::clinic_offset()).date_naive();
+    if start < today {
+        return Err("start_tanggal tidak boleh di masa lalu".to_string());
+    }
+    let waktu = schedule_service::parse_time(&series_data.waktu)
+        .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?
+        .format("%H:%M")
+        .to_string();
+    let warnings = safety_service::enforce(
+        series_data.pasien_id,
+        &series_data.treatment_ids,
+        &[],
+        series_data.acknowledge_warnings,
+        series_data.safety_override_reason.as_deref(),
+    )
+    .await?;
+
+    let mut planned = Vec::new();
+    for index in 1..=series_data.occurrences {
+        let tanggal = (start + Duration::days(((index - 1) * series_data.interval_days) as i64))
+            .format("%Y-%m-%d")
+            .to_string();
+        let allocation = check_occurrence(series_data.dokter_id, &series_data.treatment_ids, &tanggal, &waktu, &[]).await;
+        planned.push((index, tanggal, allocation));
+    }
+    let mut occurrences: Vec<OccurrenceResultDto> = planned
+        .iter()
+        .map(|(index, tanggal, allocation)| OccurrenceResultDto {
+            series_index: *index,
+            tanggal: tanggal.clone(),
+            waktu: waktu.clone(),
+            appointment_id: None,
+            conflict: allocation.as_ref().err().cloned(),
+        })
+        .collect();
+    let has_conflicts = occurrences.iter().any(|o| o.conflict.is_some());
+    if occurrences.iter().all(|o| o.conflict.is_some()) || (has_conflicts && !series_data.skip_conflicts) {
+        return Ok(SeriesResultDto { series: None, applied: false, occurrences });
+    }
+
+    series_data.waktu = waktu.clone();
+    series_data.created_by = actor.id.clone();
+    let series = appointment_series_repo::create_appointment_series(&series_data).await?;
+    audit_service::record(Some(actor), RESOURCE, series.id, "create", None, Some(&series)).await;
+
+    for ((index, tanggal, allocation), result) in planned.into_iter().zip(occurrences.iter_mut()) {
+        let Ok(resource_ids) = allocation else {
+            continue;
+        };
+        let appointment_data = CreateAppointmentDto {
+            pasien_id: series_data.pasien_id,
+            dokter_id: series_data.dokter_id,
+            treatment_ids: json!(series_data.treatment_ids),
+            tanggal,
+            waktu: waktu.clone(),
+            status: Some("booked".to_string()),
+            is_initial_skin_analysis: None,
+            skin_analysis_id: None,
+            treatment_progress_id: None,
+            resource_ids: Some(resource_ids),
+            series_id: Some(series.id),
+            series_index: Some(index),
+            acknowledge_warnings: false,
+            safety_override_reason: None,
+        };
+        match appointment_service::record_appointment(&appointment_data, Some(actor)).await {
+            Ok(appointment) => result.appointment_id = Some(appointment.id),
+            Err(e) => result.conflict = Some(e),
+        }
+    }
+    safety_service::record_override(
+        actor,
+        series.pasien_id,
+        RESOURCE,
+        series.id,
+        warnings,
+        series_data.safety_override_reason.as_deref(),
+    )
+    .await;
+    Ok(SeriesResultDto { series: Some(series), applied: true, occurrences })
+}
+
+// The active, not yet started occurrences the request applies to: `series_index` alone,
+// or with scope "following" it and every later one
+async fn occurrences_in_scope(series_id: Uuid, series_index: i32, scope: Option<&str>) -> Result<Vec<Appointment>, String> {
+    let following = match scope.unwrap_or("this") {
+        "this" => false,
+        "following" => true,
+        other => return Err(format!("scope tidak dikenal: {}", other)),
+    };
+    let now = Utc::now();
+    let offset = appointment_service::clinic_offset();
+    let appointments: Vec<Appointment> = appointment_repo::get_appointments_by_series(series_id)
+        .await?
+        .into_iter()
+        .filter(|a| {
+            let index = a.series_index.unwrap_or_default();
+            if following { index >= series_index } else { index == series_index }
+        })
+        .filter(|a| appointment_service::PATIENT_MANAGEABLE_STATUSES.contains(&a.status.as_str()))
+        .filter(|a| appointment_service::appointment_start(a, offset).is_some_and(|start| start > now))
+        .collect();
+    if appointments.is_empty() {
+        return Err("Tidak ada sesi aktif yang dapat diubah".to_string());
+    }
+    Ok(appointments)
+}
+
+// Edits one occurrence or it and all following. Each changed occurrence is checked first;
+// unless `skip_conflicts` is set a single conflict changes nothing.
+pub async fn handle_update_series_occurrences(
+    series_id: Uuid,
+    series_index: i32,
+    scope: Option<&str>,
+    update_data: UpdateSeriesOccurrencesDto,
+    actor: &AuthenticatedUser,
+) -> Result<SeriesResultDto, String> {
+    let waktu = match &update_data.waktu {
+        Some(waktu) => Some(
+            schedule_service::parse_time(waktu)
+                .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?
+                .format("%H:%M")
+                .to_string(),
+        ),
+        None => None,
+    };
+    if update_data.shift_days.is_some_and(|days| days.abs() > MAX_SHIFT_DAYS) {
+        return Err(format!("shift_days harus antara -{} dan {}", MAX_SHIFT_DAYS, MAX_SHIFT_DAYS));
+    }
+    let appointments = occurrences_in_scope(series_id, series_index, scope).await?;
+    // Every occurrence in scope is moving, so none of them blocks another's new slot
+    let moving: Vec<Uuid> = appointments.iter().map(|a| a.id).collect();
+
+    let mut planned = Vec::new();
+    for appointment in appointments {
+        let tanggal = match update_data.shift_days {
+            Some(days) => parse_date(&appointment.tanggal)?
+                .checked_add_signed(Duration::days(days))
+                .ok_or_else(|| "shift_days di luar rentang tanggal".to_string())?
+                .format("%Y-%m-%d")
+                .to_string(),
+            None => appointment.tanggal.clone(),
+        };
+        let waktu = waktu.clone().unwrap_or_else(|| appointment.waktu.clone());
+        let dokter_id = update_data.dokter_id.unwrap_or(appointment.dokter_id);
+        let treatment_ids = update_data
+            .treatment_ids
+            .clone()
+            .unwrap_or_else(|| safety_service::uuids_from_value(&appointment.treatment_ids));
+        let check = check_occurrence(dokter_id, &treatment_ids, &tanggal, &waktu, &moving)
+            .await
+            .map(|_| ());
+        let changes = UpdateAppointmentDto {
+            dokter_id: Some(dokter_id),
+            tanggal: Some(tanggal),
+            waktu: Some(waktu),
+            treatment_ids: update_data.treatment_ids.as_ref().map(|ids| json!(ids)),
+            acknowledge_warnings: update_data.acknowledge_warnings,
+            safety_override_reason: update_data.safety_override_reason.clone(),
+            ..Default::default()
+        };
+        planned.push((appointment, changes, check));
+    }
+    let has_conflicts = planned.iter().any(|(_, _, check)| check.is_err());
+    if has_conflicts && !update_data.skip_conflicts {
+        let occurrences = planned
+            .iter()
+            .map(|(appointment, changes, check)| OccurrenceResultDto {
+                series_index: appointment.series_index.unwrap_or_default(),
+                tanggal: changes.tanggal.clone().unwrap_or_default(),
+                waktu: changes.waktu.clone().unwrap_or_default(),
+                appointment_id: Some(appointment.id),
+                conflict: check.as_ref().err().cloned(),
+            })
+            .collect();
+        return Ok(SeriesResultDto { series: None, applied: false, occurrences });
+    }
+
+    // Shifting later applies the last occurrence first (and earlier, the first), so each
+    // occurrence's new slot has already been vacated by the sibling that held it
+    if update_data.shift_days.is_some_and(|days| days > 0) {
+        planned.reverse();
+    }
+    let mut occurrences = Vec::new();
+    for (appointment, changes, check) in planned {
+        let result = match check {
+            Err(conflict) => occurrence_result(&appointment, Some(conflict)),
+            Ok(()) => match appointment_service::handle_update_appointment(appointment.id, changes, actor).await {
+                Ok(updated) => occurrence_result(&updated, None),
+                Err(e) => occurrence_result(&appointment, Some(e)),
+            },
+        };
+        occurrences.push(result);
+    }
+    occurrences.sort_by_key(|o| o.series_index);
+    let series = appointment_series_repo::get_appointment_series_by_id(series_id).await?;
+    Ok(SeriesResultDto { series: Some(series), applied: true, occurrences })
+}
+
+// Cancels one occurrence or it and all following; freed slots go to the waitlist as usual
+pub async fn handle_cancel_series_occurrences(
+    series_id: Uuid,
+    series_index: i32,
+    scope: Option<&str>,
+    actor: &AuthenticatedUser,
+) -> Result<SeriesResultDto, String> {
+    let mut occurrences = Vec::new();
+    for appointment in occurrences_in_scope(series_id, series_index, scope).await? {
+        let changes = UpdateAppointmentDto { status: Some("cancelled".to_string()), ..Default::default() };
+        let result = match appointment_service::handle_update_appointment(appointment.id, changes, actor).await {
+            Ok(cancelled) => occurrence_result(&cancelled, None),
+            Err(e) => occurrence_result(&appointment, Some(e)),
+        };
+        occurrences.push(result);
+    }
+    let series = appointment_series_repo::get_appointment_series_by_id(series_id).await?;
+    Ok(SeriesResultDto { series: Some(series), applied: true, occurrences })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn occurrence(id: Uuid, dokter_id: Uuid, tanggal: &str, index: i32) -> Appointment {
+        Appointment {
+            id,
+            dokter_id,
+            tanggal: tanggal.to_string(),
+            series_id: Some(Uuid::new_v4()),
+            series_index: Some(index),
+            ..Appointment::fixture()
+        }
+    }
+
+    // A 14-day series shifted by 14 days: occurrence 1 lands on occurrence 2's slot, which
+    // is moving on as well
+    #[test]
+    fn shifting_following_occurrences_ignores_moving_siblings() {
+        let dokter_id = Uuid::new_v4();
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let day = DayBookings::from_parts("2030-01-15", Vec::new(), vec![occurrence(second, dokter_id, "2030-01-15", 2)], Vec::new());
+
+        assert!(check_against(&day, dokter_id, &[], "2030-01-15", "10:00", &[first]).is_err());
+        assert!(check_against(&day, dokter_id, &[], "2030-01-15", "10:00", &[first, second, third]).is_ok());
+    }
+
+    #[test]
+    fn other_appointments_still_conflict() {
+        let dokter_id = Uuid::new_v4();
+        let (first, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let day = DayBookings::from_parts("2030-01-15", Vec::new(), vec![occurrence(other, dokter_id, "2030-01-15", 1)], Vec::new());
+        assert!(check_against(&day, dokter_id, &[], "2030-01-15", "10:15", &[first]).is_err());
+        assert!(check_against(&day, Uuid::new_v4(), &[], "2030-01-15", "10:30", &[first]).is_ok());
+    }
+}
@@ -1,20 +1,304 @@
 use crate::dtos::appointment_dto::{CreateAppointmentDto, UpdateAppointmentDto};
+use crate::events::event_bus::{self, Topic};
 use crate::models::appointment::Appointment;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::appointment_repo;
+use crate::services::clinic_resource_service::DayBookings;
+use crate::services::{audit_service, consent_service, safety_service, schedule_service, waitlist_service};
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate, NaiveTime, Utc};
+use serde_json::{json, Value};
+use std::env;
 use uuid::Uuid;
 
-pub async fn handle_get_all_appointments() -> Result<Vec<Appointment>, String> {
-    appointment_repo::get_all_appointments().await
+const RESOURCE: &str = "appointment";
+const DEFAULT_UTC_OFFSET_HOURS: i32 = 7; // WIB
+const DEFAULT_NO_SHOW_GRACE_MINUTES: i64 = 60;
+// How far back the no-show job looks for appointments left in `booked`
+const NO_SHOW_LOOKBACK_DAYS: i64 = 7;
+
+pub fn clinic_offset() -> FixedOffset {
+    env::var("CLINIC_UTC_OFFSET_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i32>().ok())
+        .and_then(|hours| FixedOffset::east_opt(hours * 3600))
+        .unwrap_or_else(|| FixedOffset::east_opt(DEFAULT_UTC_OFFSET_HOURS * 3600).unwrap())
+}
+
+// `tanggal` and `waktu` are the clinic's local date and time
+pub fn appointment_start(appointment: &Appointment, offset: FixedOffset) -> Option<DateTime<Utc>> {
+    let date = NaiveDate::parse_from_str(&appointment.tanggal, "%Y-%m-%d").ok()?;
+    let time = NaiveTime::parse_from_str(&appointment.waktu, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(&appointment.waktu, "%H:%M:%S"))
+        .ok()?;
+    date.and_time(time)
+        .and_local_timezone(offset)
+        .single()
+        .map(|start| start.with_timezone(&Utc))
+}
+
+// A booking, a check-in (status `checked_in`) and any other status change each get their own
+// event type; edits that leave the status alone are published as `appointment.updated`.
+fn publish_change(before: Option<&Appointment>, appointment: &Appointment) {
+    let event_type = match before {
+        None => "appointment.booked",
+        Some(before) if before.status == appointment.status => "appointment.updated",
+        Some(_) if appointment.status == "checked_in" => "appointment.checked_in",
+        Some(_) => "appointment.status_changed",
+    };
+    event_bus::publish(Topic::Appointments, event_type, appointment.id, appointment);
+}
+
+// Offers the old slot of a cancelled, deleted or moved booking to the waitlist. Failures are
+// logged: the change itself has already been saved.
+async fn release_slot(before: &Appointment, after: &Appointment) {
+    let was_active = PATIENT_MANAGEABLE_STATUSES.contains(&before.status.as_str());
+    let moved = before.dokter_id != after.dokter_id || before.tanggal != after.tanggal || before.waktu != after.waktu;
+    let freed = after.status == "cancelled" || after.deleted_at.is_some() || moved;
+    if !was_active || !freed {
+        return;
+    }
+    if let Err(e) = waitlist_service::offer_freed_slot(before).await {
+        println!("Failed to offer freed slot of appointment {} to the waitlist: {}", before.id, e);
+    }
+}
+
+// The doctor must be working and not already booked for the whole appointment, and every
+// room or machine the treatments need must have a free unit. Returns the resources allocated.
+// Two bookings racing for the same slot can both pass; the window is the length of one request.
+async fn check_slot(
+    dokter_id: Uuid,
+    treatment_ids: &[Uuid],
+    tanggal: &str,
+    waktu: &str,
+    exclude_appointment_id: Option<Uuid>,
+) -> Result<Vec<Uuid>, String> {
+    let bookings = DayBookings::load(tanggal).await?;
+    let (start, end) = bookings
+        .span(treatment_ids, waktu)
+        .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    schedule_service::ensure_dokter_available(dokter_id, tanggal, waktu, end - start).await?;
+    if bookings
+        .overlapping(start, end, exclude_appointment_id.as_slice())
+        .iter()
+        .any(|appointment| appointment.dokter_id == dokter_id)
+    {
+        return Err("Dokter sudah memiliki janji pada waktu tersebut".to_string());
+    }
+    bookings.allocate(treatment_ids, waktu, exclude_appointment_id.as_slice())
+}
+
+pub async fn handle_get_all_appointments(include_deleted: bool) -> Result<Vec<Appointment>, String> {
+    appointment_repo::get_all_appointments(include_deleted).await
+}
+
+pub async fn handle_create_appointment(mut appointment_data: CreateAppointmentDto, actor: &AuthenticatedUser) -> Result<Appointment, String> {
+    let treatment_ids = safety_service::uuids_from_value(&appointment_data.treatment_ids);
+    let warnings = safety_service::enforce(
+        appointment_data.pasien_id,
+        &treatment_ids,
+        &[],
+        appointment_data.acknowledge_warnings,
+        appointment_data.safety_override_reason.as_deref(),
+    )
+    .await?;
+    if appointment_data.status.as_deref().is_some_and(|status| TREATED_STATUSES.contains(&status)) {
+        consent_service::ensure_consents_signed(None, &treatment_ids).await?;
+    }
+    appointment_data.resource_ids = Some(
+        check_slot(appointment_data.dokter_id, &treatment_ids, &appointment_data.tanggal, &appointment_data.waktu, None).await?,
+    );
+    let appointment = appointment_repo::create_appointment(&appointment_data).await?;
+    audit_service::record(Some(actor), RESOURCE, appointment.id, "create", None, Some(&appointment)).await;
+    publish_change(None, &appointment);
+    safety_service::record_override(
+        actor,
+        appointment.pasien_id,
+        RESOURCE,
+        appointment.id,
+        warnings,
+        appointment_data.safety_override_reason.as_deref(),
+    )
+    .await;
+    Ok(appointment)
+}
+
+pub async fn handle_update_appointment(id: Uuid, mut appointment_data: UpdateAppointmentDto, actor: &AuthenticatedUser) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    // A new slot must be available, and moving a flagged appointment resolves the flag
+    let dokter_id = appointment_data.dokter_id.unwrap_or(before.dokter_id);
+    let tanggal = appointment_data.tanggal.clone().unwrap_or_else(|| before.tanggal.clone());
+    let waktu = appointment_data.waktu.clone().unwrap_or_else(|| before.waktu.clone());
+    let slot_changed = dokter_id != before.dokter_id || tanggal != before.tanggal || waktu != before.waktu;
+    if slot_changed && before.needs_reschedule && appointment_data.needs_reschedule.is_none() {
+        appointment_data.needs_reschedule = Some(false);
+    }
+    // A new slot or a different set of treatments (and so length) is checked against the
+    // doctor's schedule and bookings again and gets its rooms and equipment allocated again
+    if slot_changed || appointment_data.treatment_ids.as_ref().is_some_and(|ids| *ids != before.treatment_ids) {
+        let treatment_ids = safety_service::uuids_from_value(
+            appointment_data.treatment_ids.as_ref().unwrap_or(&before.treatment_ids),
+        );
+        appointment_data.resource_ids = Some(check_slot(dokter_id, &treatment_ids, &tanggal, &waktu, Some(id)).await?);
+    }
+    // Changing the patient or the treatments is checked the same way as a new booking
+    let mut warnings = Vec::new();
+    if appointment_data.pasien_id.is_some() || appointment_data.treatment_ids.is_some() {
+        let pasien_id = appointment_data.pasien_id.unwrap_or(before.pasien_id);
+        let treatment_ids = safety_service::uuids_from_value(
+            appointment_data.treatment_ids.as_ref().unwrap_or(&before.treatment_ids),
+        );
+        warnings = safety_service::enforce(
+            pasien_id,
+            &treatment_ids,
+            &[],
+            appointment_data.acknowledge_warnings,
+            appointment_data.safety_override_reason.as_deref(),
+        )
+        .await?;
+    }
+    if appointment_data.status.as_deref().is_some_and(|status| TREATED_STATUSES.contains(&status)) {
+        let treatment_ids = safety_service::uuids_from_value(
+            appointment_data.treatment_ids.as_ref().unwrap_or(&before.treatment_ids),
+        );
+        consent_service::ensure_consents_signed(Some(id), &treatment_ids).await?;
+    }
+    let appointment = appointment_repo::update_appointment(id, &appointment_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&appointment)).await;
+    publish_change(Some(&before), &appointment);
+    release_slot(&before, &appointment).await;
+    safety_service::record_override(
+        actor,
+        appointment.pasien_id,
+        RESOURCE,
+        id,
+        warnings,
+        appointment_data.safety_override_reason.as_deref(),
+    )
+    .await;
+    Ok(appointment)
+}
+
+pub async fn handle_delete_appointment(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let deleted = appointment_repo::delete_appointment(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    release_slot(&before, &deleted).await;
+    Ok(())
+}
+
+pub async fn handle_restore_appointment(id: Uuid, actor: &AuthenticatedUser) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let appointment = appointment_repo::restore_appointment(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&appointment)).await;
+    Ok(appointment)
+}
+
+// Records a booked patient's arrival for today's appointment
+pub async fn check_in(id: Uuid, actor: &AuthenticatedUser) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let appointment = appointment_repo::set_appointment_status(id, "checked_in", &["booked", "rescheduled"])
+        .await?
+        .ok_or_else(|| "Appointment sudah check-in atau tidak lagi aktif".to_string())?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&appointment)).await;
+    publish_change(Some(&before), &appointment);
+    Ok(appointment)
+}
+
+// Saves an appointment the caller has already validated and allocated resources for
+pub async fn record_appointment(appointment_data: &CreateAppointmentDto, actor: Option<&AuthenticatedUser>) -> Result<Appointment, String> {
+    let appointment = appointment_repo::create_appointment(appointment_data).await?;
+    audit_service::record(actor, RESOURCE, appointment.id, "create", None, Some(&appointment)).await;
+    publish_change(None, &appointment);
+    Ok(appointment)
+}
+
+// Statuses a patient may still cancel or move from the self-service link
+pub const PATIENT_MANAGEABLE_STATUSES: [&str; 3] = ["pending", "booked", "rescheduled"];
+// Statuses meaning the treatment has happened; reaching any of them needs the consents signed
+const TREATED_STATUSES: [&str; 2] = ["completed", "paid"];
+
+// Records an online booking, already validated and allocated by booking_service. It waits in
+// `pending` until staff confirm it by setting it to `booked`.
+pub async fn create_pending_appointment(mut appointment_data: CreateAppointmentDto, resource_ids: Vec<Uuid>) -> Result<Appointment, String> {
+    appointment_data.status = Some("pending".to_string());
+    appointment_data.resource_ids = Some(resource_ids);
+    record_appointment(&appointment_data, None).await
+}
+
+pub async fn cancel_by_patient(id: Uuid) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let appointment = appointment_repo::set_appointment_status(id, "cancelled", &PATIENT_MANAGEABLE_STATUSES)
+        .await?
+        .ok_or_else(|| "Booking tidak dapat dibatalkan lagi".to_string())?;
+    audit_service::record(None, RESOURCE, id, "update", Some(&before), Some(&appointment)).await;
+    publish_change(Some(&before), &appointment);
+    release_slot(&before, &appointment).await;
+    Ok(appointment)
 }
 
-pub async fn handle_create_appointment(appointment_data: CreateAppointmentDto) -> Result<Appointment, String> {
-    appointment_repo::create_appointment(&appointment_data).await
+// A patient's move goes back to `pending` so staff confirm the new slot as well
+pub async fn reschedule_by_patient(id: Uuid, tanggal: &str, waktu: &str, resource_ids: &[Uuid]) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let appointment = appointment_repo::move_appointment(id, tanggal, waktu, resource_ids, "pending", &PATIENT_MANAGEABLE_STATUSES)
+        .await?
+        .ok_or_else(|| "Booking tidak dapat dijadwalkan ulang lagi".to_string())?;
+    audit_service::record(None, RESOURCE, id, "update", Some(&before), Some(&appointment)).await;
+    publish_change(Some(&before), &appointment);
+    release_slot(&before, &appointment).await;
+    Ok(appointment)
 }
 
-pub async fn handle_update_appointment(id: Uuid, appointment_data: UpdateAppointmentDto) -> Result<Appointment, String> {
-    appointment_repo::update_appointment(id, &appointment_data).await
+// Holds a freed slot for a waitlisted patient while their offer is open. A `held`
+// appointment occupies the doctor and resources like a booking but gets no reminders.
+pub async fn create_held_appointment(mut appointment_data: CreateAppointmentDto, resource_ids: Vec<Uuid>) -> Result<Appointment, String> {
+    appointment_data.status = Some("held".to_string());
+    appointment_data.resource_ids = Some(resource_ids);
+    record_appointment(&appointment_data, None).await
 }
 
-pub async fn handle_delete_appointment(id: Uuid) -> Result<(), String> {
-    appointment_repo::delete_appointment(id).await
-}
\ No newline at end of file
+// Accepting the offer books the held slot; declining or letting it expire cancels it
+pub async fn settle_held_appointment(id: Uuid, accepted: bool, actor: Option<&AuthenticatedUser>) -> Result<Appointment, String> {
+    let before = appointment_repo::get_appointment_by_id(id).await?;
+    let status = if accepted { "booked" } else { "cancelled" };
+    let appointment = appointment_repo::set_appointment_status(id, status, &["held"])
+        .await?
+        .ok_or_else(|| "Slot tawaran sudah tidak ditahan lagi".to_string())?;
+    audit_service::record(actor, RESOURCE, id, "update", Some(&before), Some(&appointment)).await;
+    publish_change(Some(&before), &appointment);
+    Ok(appointment)
+}
+
+// Marks appointments still `booked` or `rescheduled` well after their start time as no-shows
+pub async fn mark_no_shows(now: DateTime<Utc>) -> Result<Value, String> {
+    let offset = clinic_offset();
+    let grace = Duration::minutes(
+        env::var("NO_SHOW_GRACE_MINUTES")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_NO_SHOW_GRACE_MINUTES),
+    );
+    let today = now.with_timezone(&offset).date_naive();
+    let from = (today - Duration::days(NO_SHOW_LOOKBACK_DAYS)).format("%Y-%m-%d").to_string();
+    let appointments = appointment_repo::get_appointments_between(&from, &today.format("%Y-%m-%d").to_string()).await?;
+
+    let mut marked = Vec::new();
+    for appointment in appointments {
+        if appointment.status != "booked" && appointment.status != "rescheduled" {
+            continue;
+        }
+        if appointment_start(&appointment, offset).is_none_or(|start| start + grace >= now) {
+            continue;
+        }
+        // The status guard means a check-in racing with the job is never overwritten
+        match appointment_repo::set_appointment_status(appointment.id, "no_show", &["booked", "rescheduled"]).await {
+            Ok(Some(updated)) => {
+                audit_service::record(None, RESOURCE, updated.id, "update", Some(&appointment), Some(&updated)).await;
+                publish_change(Some(&appointment), &updated);
+                marked.push(updated.id);
+            }
+            Ok(None) => {}
+            Err(e) => println!("Failed to mark appointment {} as no-show: {}", appointment.id, e),
+        }
+    }
+    Ok(json!({ "marked_no_show": marked }))
+}
@@ -0,0 +1,103 @@
+use crate::dtos::audit_log_dto::{AuditLogQueryDto, CreateAuditLogDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::audit_log::AuditLog;
+use crate::repositories::audit_log_repo;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+// Keeps only the fields that actually changed, so each entry reads as a before/after diff.
+// Creates and deletes have `null` on one side and therefore keep the full record.
+fn diff(before: Value, after: Value) -> (Value, Value) {
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            let mut changed_before = Map::new();
+            let mut changed_after = Map::new();
+            for key in before.keys().chain(after.keys()) {
+                if changed_before.contains_key(key) {
+                    continue;
+                }
+                let old = before.get(key).cloned().unwrap_or(Value::Null);
+                let new = after.get(key).cloned().unwrap_or(Value::Null);
+                if old != new {
+                    changed_before.insert(key.clone(), old);
+                    changed_after.insert(key.clone(), new);
+                }
+            }
+            (Value::Object(changed_before), Value::Object(changed_after))
+        }
+        (before, after) => (before, after),
+    }
+}
+
+// Records a mutation. A failed write is logged rather than returned, because the
+// mutation it describes has already been committed by the time this runs.
+pub async fn record<T: Serialize>(
+    actor: Option<&AuthenticatedUser>,
+    resource: &str,
+    record_id: Uuid,
+    action: &str,
+    before: Option<&T>,
+    after: Option<&T>,
+) {
+    let to_value = |record: Option<&T>| {
+        record
+            .and_then(|r| serde_json::to_value(r).ok())
+            .unwrap_or(Value::Null)
+    };
+    let (before, after) = diff(to_value(before), to_value(after));
+
+    let log_data = CreateAuditLogDto {
+        actor_id: actor.map(|a| a.id.clone()),
+        actor_position: actor.map(|a| a.position.clone()),
+        resource: resource.to_string(),
+        record_id,
+        action: action.to_string(),
+        before,
+        after,
+    };
+
+    if let Err(e) = audit_log_repo::create_audit_log(&log_data).await {
+        println!("Failed to write audit log for {} {}: {}", resource, record_id, e);
+    }
+}
+
+pub async fn handle_get_audit_logs(query: AuditLogQueryDto) -> Result<Vec<AuditLog>, String> {
+    audit_log_repo::get_audit_logs(&query).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_only_changed_fields() {
+        let (before, after) = diff(
+            json!({ "nama": "Ana", "stok": 5, "harga": 1000 }),
+            json!({ "nama": "Ana", "stok": 3, "harga": 1000 }),
+        );
+        assert_eq!(before, json!({ "stok": 5 }));
+        assert_eq!(after, json!({ "stok": 3 }));
+    }
+
+    #[test]
+    fn added_and_removed_fields_show_as_null_on_the_other_side() {
+        let (before, after) = diff(json!({ "old": 1 }), json!({ "new": { "a": [1] } }));
+        assert_eq!(before, json!({ "old": 1, "new": null }));
+        assert_eq!(after, json!({ "old": null, "new": { "a": [1] } }));
+    }
+
+    #[test]
+    fn unchanged_records_give_empty_objects() {
+        let record = json!({ "nama": "Ana", "tags": ["a", "b"] });
+        assert_eq!(diff(record.clone(), record), (json!({}), json!({})));
+    }
+
+    #[test]
+    fn creates_and_deletes_keep_the_full_record() {
+        let record = json!({ "nama": "Ana", "stok": 5 });
+        assert_eq!(diff(Value::Null, record.clone()), (Value::Null, record.clone()));
+        assert_eq!(diff(record.clone(), Value::Null), (record, Value::Null));
+    }
+}
@@ -0,0 +1,501 @@
+use crate::dtos::appointment_dto::CreateAppointmentDto;
+use crate::dtos::booking_dto::{
+    AvailableSlotDto, BookingCreatedDto, BookingSessionDto, CreateBookingLinkDto, CreateBookingOtpDto,
+    CreatePublicBookingDto, PublicBookingDto, PublicTreatmentDto, RequestOtpDto, RescheduleBookingDto, SlotQueryDto,
+    VerifyOtpDto,
+};
+use crate::dtos::pasien_dto::CreatePasienDto;
+use crate::models::appointment::Appointment;
+use crate::models::pasien::Pasien;
+use crate::notifications::notifier::{self, Channel, Notification, Notifiers};
+use crate::repositories::{
+    appointment_repo, booking_link_repo, booking_otp_repo, dokter_repo, pasien_repo, treatment_repo,
+};
+use crate::services::clinic_resource_service::{self, DayBookings};
+use crate::services::schedule_service::{self, DoctorDay};
+use crate::services::{appointment_service, audit_service, safety_service};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Timelike, Utc};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::env;
+use uuid::Uuid;
+
+const OTP_TTL_MINUTES: i64 = 5;
+const OTP_MAX_ATTEMPTS: i32 = 5;
+const OTP_RESEND_COOLDOWN_SECONDS: i64 = 60;
+const OTP_MAX_PER_HOUR: usize = 5;
+const SESSION_TTL_MINUTES: i64 = 30;
+const SESSION_PURPOSE: &str = "booking";
+const DEFAULT_SLOT_MINUTES: i64 = 30;
+const DEFAULT_BOOKING_HORIZON_DAYS: i64 = 60;
+// Unconfirmed online bookings one patient may hold at a time
+const MAX_PENDING_BOOKINGS: usize = 3;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionClaims {
+    sub: String, // the verified phone number, international digits
+    purpose: String,
+    exp: usize,
+}
+
+fn env_i64(name: &str, default: i64) -> i64 {
+    env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn token_secret() -> Result<String, String> {
+    env::var("BOOKING_TOKEN_SECRET").map_err(|_| "BOOKING_TOKEN_SECRET not set".to_string())
+}
+
+// OTP codes and manage tokens are stored only as an HMAC keyed with BOOKING_TOKEN_SECRET
+pub fn keyed_hash(value: &str) -> Result<String, String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(token_secret()?.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(value.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn matches_hash(value: &str, hash: &str) -> Result<bool, String> {
+    let Ok(expected) = hex::decode(hash) else {
+        return Ok(false);
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(token_secret()?.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(value.as_bytes());
+    Ok(mac.verify_slice(&expected).is_ok())
+}
+
+fn otp_channel() -> Channel {
+    env::var("BOOKING_OTP_CHANNEL")
+        .ok()
+        .and_then(|v| Channel::parse(&v))
+        .filter(|channel| *channel != Channel::Email)
+        .unwrap_or(Channel::Whatsapp)
+}
+
+// International digits ("62812...") for any way an Indonesian mobile number is typed
+fn normalize_phone(no_telepon: &str) -> Result<String, String> {
+    let phone = notifier::normalize_phone(no_telepon);
+    if phone.starts_with("62") && (10..=15).contains(&phone.len()) {
+        Ok(phone)
+    } else {
+        Err("Nomor telepon tidak valid".to_string())
+    }
+}
+
+// Patients are stored with the number as staff typed it; the common spellings are matched,
+// numbers typed with spaces or dashes are not.
+pub async fn find_pasien(phone: &str) -> Result<Option<Pasien>, String> {
+    let local = &phone[2..];
+    let variants = vec![format!("0{}", local), phone.to_string(), format!("+{}", phone)];
+    Ok(pasien_repo::get_pasiens_by_phones(&variants).await?.into_iter().next())
+}
+
+pub fn today() -> NaiveDate {
+    Utc::now().with_timezone(&appointment_service::clinic_offset()).date_naive()
+}
+
+pub fn parse_bookable_date(tanggal: &str) -> Result<NaiveDate, String> {
+    let date = NaiveDate::parse_from_str(tanggal, "%Y-%m-%d").map_err(|_| "tanggal harus berformat YYYY-MM-DD".to_string())?;
+    let horizon = env_i64("BOOKING_HORIZON_DAYS", DEFAULT_BOOKING_HORIZON_DAYS);
+    if date < today() || date > today() + Duration::days(horizon) {
+        return Err(format!("Booking online hanya untuk hari ini sampai {} hari ke depan", horizon));
+    }
+    Ok(date)
+}
+
+fn minutes_of_day(time: NaiveTime) -> i64 {
+    (time.num_seconds_from_midnight() / 60) as i64
+}
+
+// A slot is bookable online when it fits inside the doctor's working hours for the day,
+// hasn't started yet, doesn't overlap the doctor's other appointments and leaves a room or
+// machine free for every treatment. Doctors without working hours that day offer no slots.
+pub fn check_slot(
+    doctor_day: &DoctorDay,
+    bookings: &DayBookings,
+    dokter_id: Uuid,
+    treatment_ids: &[Uuid],
+    date: NaiveDate,
+    time: NaiveTime,
+    exclude_appointment_id: Option<Uuid>,
+) -> Result<Vec<Uuid>, String> {
+    let waktu = time.format("%H:%M").to_string();
+    let (start, end) = bookings
+        .span(treatment_ids, &waktu)
+        .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    let now = Utc::now().with_timezone(&appointment_service::clinic_offset()).naive_local();
+    if date.and_time(time) <= now {
+        return Err("Waktu tersebut sudah lewat".to_string());
+    }
+    doctor_day.check(time, end - start)?;
+    if !doctor_day
+        .hours()
+        .iter()
+        .any(|(open, close)| start >= minutes_of_day(*open) && end <= minutes_of_day(*close))
+    {
+        return Err("Waktu tersebut di luar jam praktik dokter".to_string());
+    }
+    if bookings
+        .overlapping(start, end, exclude_appointment_id.as_slice())
+        .iter()
+        .any(|appointment| appointment.dokter_id == dokter_id)
+    {
+        return Err("Dokter sudah memiliki janji pada waktu tersebut".to_string());
+    }
+    bookings.allocate(treatment_ids, &waktu, exclude_appointment_id.as_slice())
+}
+
+fn parse_treatment_ids(value: Option<&str>) -> Result<Vec<Uuid>, String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| Uuid::parse_str(id).map_err(|_| format!("treatment_ids tidak valid: {}", id)))
+        .collect()
+}
+
+pub fn ensure_bookable_treatments(treatment_ids: &[Uuid], bookings: &DayBookings) -> Result<(), String> {
+    if treatment_ids.is_empty() {
+        return Err("Pilih minimal satu treatment".to_string());
+    }
+    for id in treatment_ids {
+        if bookings.treatments.get(id).is_none_or(|t| t.deleted_at.is_some()) {
+            return Err(format!("Treatment {} tidak ditemukan", id));
+        }
+    }
+    Ok(())
+}
+
+// Secret for a manage or offer link: 64 hex characters, stored only as its keyed hash
+pub fn new_link_token() -> String {
+    format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+}
+
+pub async fn send_message(notifiers: &Notifiers, phone: &str, subject: &str, body: String) -> Result<(), String> {
+    let notification = Notification { recipient: phone.to_string(), subject: subject.to_string(), body };
+    notifiers.for_channel(otp_channel()).send(&notification).await
+}
+
+pub async fn handle_get_public_treatments() -> Result<Vec<PublicTreatmentDto>, String> {
+    Ok(treatment_repo::get_all_treatments(false)
+        .await?
+        .into_iter()
+        .map(|t| PublicTreatmentDto {
+            id: t.id,
+            name: t.name,
+            description: t.description,
+            price: t.price,
+            estimated_time: t.estimated_time,
+        })
+        .collect())
+}
+
+pub async fn handle_get_available_slots(query: SlotQueryDto) -> Result<Vec<AvailableSlotDto>, String> {
+    let date = parse_bookable_date(&query.tanggal)?;
+    let treatment_ids = parse_treatment_ids(query.treatment_ids.as_deref())?;
+    let bookings = DayBookings::load(&query.tanggal).await?;
+    if !treatment_ids.is_empty() {
+        ensure_bookable_treatments(&treatment_ids, &bookings)?;
+    }
+    let dokters = match query.dokter_id {
+        Some(id) => vec![dokter_repo::get_dokter_by_id(id).await?],
+        None => dokter_repo::get_all_dokters(false).await?,
+    };
+    let step = env_i64("SLOT_MINUTES", DEFAULT_SLOT_MINUTES).max(5);
+    let duration = clinic_resource_service::duration_minutes(&treatment_ids, &bookings.treatments);
+
+    let mut slots = Vec::new();
+    for dokter in dokters.into_iter().filter(|d| d.deleted_at.is_none()) {
+        let doctor_day = DoctorDay::load(dokter.id, &query.tanggal).await?;
+        let mut candidates: Vec<i64> = Vec::new();
+        for (open, close) in doctor_day.hours() {
+            let mut minute = minutes_of_day(open);
+            while minute + duration <= minutes_of_day(close) {
+                if !candidates.contains(&minute) {
+                    candidates.push(minute);
+                }
+                minute += step;
+            }
+        }
+        candidates.sort();
+        for minute in candidates {
+            let Some(time) = NaiveTime::from_hms_opt((minute / 60) as u32, (minute % 60) as u32, 0) else {
+                continue;
+            };
+            if check_slot(&doctor_day, &bookings, dokter.id, &treatment_ids, date, time, None).is_ok() {
+                slots.push(AvailableSlotDto {
+                    dokter_id: dokter.id,
+                    dokter_nama: dokter.nama.clone(),
+                    waktu: time.format("%H:%M").to_string(),
+                });
+            }
+        }
+    }
+    Ok(slots)
+}
+
+// Sends a one-time code. New and returning patients get the same response, so the endpoint
+// doesn't reveal whether a number is registered.
+pub async fn handle_request_otp(otp_data: RequestOtpDto, notifiers: &Notifiers) -> Result<(), String> {
+    let phone = normalize_phone(&otp_data.no_telepon)?;
+    let now = Utc::now();
+    let recent = booking_otp_repo::get_otps_since(&phone, now - Duration::hours(1)).await?;
+    if recent.len() >= OTP_MAX_PER_HOUR {
+        return Err("Terlalu banyak permintaan kode. Coba lagi nanti.".to_string());
+    }
+    if recent
+        .first()
+        .is_some_and(|last| last.created_at > now - Duration::seconds(OTP_RESEND_COOLDOWN_SECONDS))
+    {
+        return Err(format!("Tunggu {} detik sebelum meminta kode baru", OTP_RESEND_COOLDOWN_SECONDS));
+    }
+
+    let code = format!("{:06}", Uuid::new_v4().as_u128() % 1_000_000);
+    booking_otp_repo::create_otp(&CreateBookingOtpDto {
+        no_telepon: phone.clone(),
+        code_hash: keyed_hash(&format!("{}:{}", phone, code))?,
+        attempts: 0,
+        expires_at: now + Duration::minutes(OTP_TTL_MINUTES),
+    })
+    .await?;
+    let body = format!("Kode verifikasi booking Anda: {}. Berlaku {} menit. Jangan berikan kode ini kepada siapa pun.", code, OTP_TTL_MINUTES);
+    send_message(notifiers, &phone, "Kode verifikasi booking", body)
+        .await
+        .map_err(|e| {
+            println!("Failed to send booking OTP: {}", e);
+            "Gagal mengirim kode verifikasi".to_string()
+        })
+}
+
+// Exchanges a valid code for a short-lived booking session token
+pub async fn handle_verify_otp(verify_data: VerifyOtpDto) -> Result<BookingSessionDto, String> {
+    let phone = normalize_phone(&verify_data.no_telepon)?;
+    let now = Utc::now();
+    let invalid = || "Kode verifikasi tidak valid atau sudah kedaluwarsa".to_string();
+    let otp = booking_otp_repo::get_otps_since(&phone, now - Duration::minutes(OTP_TTL_MINUTES))
+        .await?
+        .into_iter()
+        .next()
+        .filter(|otp| otp.consumed_at.is_none() && otp.expires_at > now)
+        .ok_or_else(invalid)?;
+    if otp.attempts >= OTP_MAX_ATTEMPTS {
+        return Err("Terlalu banyak percobaan. Minta kode baru.".to_string());
+    }
+    // Every comparison, right or wrong, uses up an attempt first
+    if !booking_otp_repo::claim_attempt(otp.id, otp.attempts).await? {
+        return Err(invalid());
+    }
+    if !matches_hash(&format!("{}:{}", phone, verify_data.code.trim()), &otp.code_hash)? {
+        return Err(invalid());
+    }
+    if !booking_otp_repo::consume_otp(otp.id).await? {
+        return Err(invalid());
+    }
+
+    let expires_at = now + Duration::minutes(SESSION_TTL_MINUTES);
+    let claims = SessionClaims {
+        sub: phone.clone(),
+        purpose: SESSION_PURPOSE.to_string(),
+        exp: expires_at.timestamp() as usize,
+    };
+    let token = encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(token_secret()?.as_bytes()))
+        .map_err(|e| format!("Failed to issue booking session: {}", e))?;
+    Ok(BookingSessionDto {
+        token,
+        expires_at,
+        is_registered: find_pasien(&phone).await?.is_some(),
+    })
+}
+
+// The phone number a booking session token was issued for
+pub fn verify_session(token: &str) -> Result<String, String> {
+    let secret = token_secret()?;
+    let claims = decode::<SessionClaims>(token, &DecodingKey::from_secret(secret.as_bytes()), &Validation::new(Algorithm::HS256))
+        .map_err(|e| format!("Invalid booking session: {:?}", e))?
+        .claims;
+    if claims.purpose != SESSION_PURPOSE {
+        return Err("Invalid booking session".to_string());
+    }
+    Ok(claims.sub)
+}
+
+pub async fn treatment_names(treatment_ids: &[Uuid]) -> Result<Vec<String>, String> {
+    Ok(treatment_repo::get_all_treatments(true)
+        .await?
+        .into_iter()
+        .filter(|t| treatment_ids.contains(&t.id))
+        .map(|t| t.name)
+        .collect())
+}
+
+async fn to_public_booking(appointment: &Appointment) -> Result<PublicBookingDto, String> {
+    let dokter = dokter_repo::get_dokter_by_id(appointment.dokter_id).await?;
+    let treatments = treatment_names(&safety_service::uuids_from_value(&appointment.treatment_ids)).await?;
+    Ok(PublicBookingDto {
+        appointment_id: appointment.id,
+        status: appointment.status.clone(),
+        tanggal: appointment.tanggal.clone(),
+        waktu: appointment.waktu.clone(),
+        dokter_nama: dokter.nama,
+        treatments,
+    })
+}
+
+async fn register_pasien(phone: &str, booking_data: &CreatePublicBookingDto) -> Result<Pasien, String> {
+    let pasien_data = booking_data
+        .pasien
+        .as_ref()
+        .ok_or_else(|| "Nomor ini belum terdaftar; lengkapi data pasien".to_string())?;
+    if pasien_data.nama_lengkap.trim().is_empty() {
+        return Err("nama_lengkap wajib diisi".to_string());
+    }
+    let pasien = pasien_repo::create_pasien(&CreatePasienDto {
+        nama_lengkap: pasien_data.nama_lengkap.trim().to_string(),
+        no_telepon: format!("0{}", &phone[2..]),
+        email: pasien_data.email.clone(),
+        tanggal_lahir: pasien_data.tanggal_lahir.clone(),
+        jenis_kelamin: pasien_data.jenis_kelamin.clone(),
+        alamat_lengkap: None,
+        riwayat_alergi: None,
+        kondisi_medis: None,
+        obat_konsumsi: None,
+        riwayat_treatment: None,
+        keluhan_utama: None,
+        no_identitas: None,
+        kontak_darurat_nama: None,
+        kontak_darurat_hubungan: None,
+        nomer_kontak_darurat: None,
+        preferensi_komunikasi: None,
+        setuju_data: None,
+        has_initial_skin_analysis: None,
+    })
+    .await?;
+    audit_service::record(None, "pasien", pasien.id, "create", None, Some(&pasien)).await;
+    Ok(pasien)
+}
+
+// Books a slot for the verified phone number, registering the patient first if the number
+// is new. The appointment stays `pending` until staff confirm it.
+pub async fn handle_create_booking(
+    phone: &str,
+    booking_data: CreatePublicBookingDto,
+    notifiers: &Notifiers,
+) -> Result<BookingCreatedDto, String> {
+    let date = parse_bookable_date(&booking_data.tanggal)?;
+    let time = schedule_service::parse_time(&booking_data.waktu).ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    let bookings = DayBookings::load(&booking_data.tanggal).await?;
+    ensure_bookable_treatments(&booking_data.treatment_ids, &bookings)?;
+    let doctor_day = DoctorDay::load(booking_data.dokter_id, &booking_data.tanggal).await?;
+    let resource_ids = check_slot(&doctor_day, &bookings, booking_data.dokter_id, &booking_data.treatment_ids, date, time, None)?;
+
+    let pasien = match find_pasien(phone).await? {
+        Some(pasien) => pasien,
+        None => register_pasien(phone, &booking_data).await?,
+    };
+    // Anything the safety check would warn staff about needs a conversation, not a web form
+    if !safety_service::check(pasien.id, &booking_data.treatment_ids, &[]).await?.is_empty() {
+        return Err("Treatment ini perlu dikonsultasikan dahulu. Silakan hubungi klinik untuk booking.".to_string());
+    }
+    let pending = appointment_repo::get_appointments_by_pasien_from(pasien.id, &today().format("%Y-%m-%d").to_string())
+        .await?
+        .into_iter()
+        .filter(|appointment| appointment.status == "pending")
+        .count();
+    if pending >= MAX_PENDING_BOOKINGS {
+        return Err("Masih ada booking yang menunggu konfirmasi klinik".to_string());
+    }
+
+    let appointment = appointment_service::create_pending_appointment(
+        CreateAppointmentDto {
+            pasien_id: pasien.id,
+            dokter_id: booking_data.dokter_id,
+            treatment_ids: json!(booking_data.treatment_ids),
+            tanggal: booking_data.tanggal.clone(),
+            waktu: time.format("%H:%M").to_string(),
+            status: None,
+            is_initial_skin_analysis: None,
+            skin_analysis_id: None,
+            treatment_progress_id: None,
+            resource_ids: None,
+            series_id: None,
+            series_index: None,
+            acknowledge_warnings: false,
+            safety_override_reason: None,
+        },
+        resource_ids,
+    )
+    .await?;
+
+    let manage_token = new_link_token();
+    booking_link_repo::create_booking_link(&CreateBookingLinkDto {
+        appointment_id: appointment.id,
+        token_hash: keyed_hash(&manage_token)?,
+    })
+    .await?;
+    let manage_url = env::var("PUBLIC_BOOKING_URL")
+        .ok()
+        .map(|base| format!("{}/{}", base.trim_end_matches('/'), manage_token));
+
+    let booking = to_public_booking(&appointment).await?;
+    let mut body = format!(
+        "Booking Anda dengan {} pada {} pukul {} sudah kami terima dan menunggu konfirmasi klinik.",
+        booking.dokter_nama, booking.tanggal, booking.waktu
+    );
+    if let Some(url) = &manage_url {
+        body.push_str(&format!(" Batalkan atau ubah jadwal: {}", url));
+    }
+    if let Err(e) = send_message(notifiers, phone, "Booking diterima", body).await {
+        println!("Failed to send booking confirmation for appointment {}: {}", appointment.id, e);
+    }
+    Ok(BookingCreatedDto { booking, manage_token, manage_url })
+}
+
+async fn get_booking_by_token(manage_token: &str) -> Result<Appointment, String> {
+    let link = booking_link_repo::get_booking_link_by_hash(&keyed_hash(manage_token)?).await?;
+    let appointment = appointment_repo::get_appointment_by_id(link.appointment_id).await?;
+    if appointment.deleted_at.is_some() {
+        return Err("Booking not found".to_string());
+    }
+    Ok(appointment)
+}
+
+// Patients may change a booking until it starts, unless the clinic has already moved it on
+fn ensure_manageable(appointment: &Appointment) -> Result<(), String> {
+    let started = appointment_service::appointment_start(appointment, appointment_service::clinic_offset())
+        .is_none_or(|start: DateTime<Utc>| start <= Utc::now());
+    if !appointment_service::PATIENT_MANAGEABLE_STATUSES.contains(&appointment.status.as_str()) || started {
+        return Err("Booking ini tidak dapat diubah lagi. Silakan hubungi klinik.".to_string());
+    }
+    Ok(())
+}
+
+pub async fn handle_get_booking(manage_token: &str) -> Result<PublicBookingDto, String> {
+    to_public_booking(&get_booking_by_token(manage_token).await?).await
+}
+
+pub async fn handle_cancel_booking(manage_token: &str) -> Result<PublicBookingDto, String> {
+    let appointment = get_booking_by_token(manage_token).await?;
+    ensure_manageable(&appointment)?;
+    to_public_booking(&appointment_service::cancel_by_patient(appointment.id).await?).await
+}
+
+pub async fn handle_reschedule_booking(manage_token: &str, reschedule_data: RescheduleBookingDto) -> Result<PublicBookingDto, String> {
+    let appointment = get_booking_by_token(manage_token).await?;
+    ensure_manageable(&appointment)?;
+    let date = parse_bookable_date(&reschedule_data.tanggal)?;
+    let time = schedule_service::parse_time(&reschedule_data.waktu).ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    let treatment_ids = safety_service::uuids_from_value(&appointment.treatment_ids);
+    let bookings = DayBookings::load(&reschedule_data.tanggal).await?;
+    let doctor_day = DoctorDay::load(appointment.dokter_id, &reschedule_data.tanggal).await?;
+    let resource_ids = check_slot(&doctor_day, &bookings, appointment.dokter_id, &treatment_ids, date, time, Some(appointment.id))?;
+    let moved = appointment_service::reschedule_by_patient(
+        appointment.id,
+        &reschedule_data.tanggal,
+        &time.format("%H:%M").to_string(),
+        &resource_ids,
+    )
+    .await?;
+    to_public_booking(&moved).await
+}
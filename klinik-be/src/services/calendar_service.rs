@@ -0,0 +1,236 @@
+use crate::dtos::calendar_dto::{CalendarFeedCreatedDto, CreateCalendarFeedDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::appointment::Appointment;
+use crate::models::pasien::Pasien;
+use crate::models::treatment::Treatment;
+use crate::repositories::{appointment_repo, audit_log_repo, calendar_feed_repo, dokter_repo, pasien_repo, treatment_repo};
+use crate::services::{appointment_service, audit_service, booking_service, clinic_resource_service, safety_service};
+use chrono::{DateTime, Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::env;
+use uuid::Uuid;
+
+const RESOURCE: &str = "calendar_feed";
+const DEFAULT_PAST_DAYS: i64 = 30;
+const DEFAULT_FUTURE_DAYS: i64 = 180;
+// RFC 5545 content lines are at most 75 octets, excluding the CRLF
+const MAX_LINE_OCTETS: usize = 75;
+
+fn days_from_env(key: &str, default: i64) -> i64 {
+    env::var(key).ok().and_then(|v| v.parse().ok()).filter(|days| *days >= 0).unwrap_or(default)
+}
+
+// Feed tokens are random and long, so a plain digest is enough to look them up
+fn token_hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn feed_url(token: &str) -> String {
+    match env::var("CALENDAR_FEED_URL") {
+        Ok(base) if !base.is_empty() => format!("{}/{}.ics", base.trim_end_matches('/'), token),
+        _ => format!("/api/calendar/{}.ics", token),
+    }
+}
+
+// Creating a feed revokes the doctor's previous one, so there is at most one live URL
+pub async fn handle_create_calendar_feed(dokter_id: Uuid, actor: &AuthenticatedUser) -> Result<CalendarFeedCreatedDto, String> {
+    dokter_repo::get_dokter_by_id(dokter_id).await?;
+    for revoked in calendar_feed_repo::revoke_calendar_feeds(dokter_id).await? {
+        audit_service::record(Some(actor), RESOURCE, revoked.id, "revoke", None, Some(&revoked)).await;
+    }
+    let token = booking_service::new_link_token();
+    let feed = calendar_feed_repo::create_calendar_feed(&CreateCalendarFeedDto {
+        dokter_id,
+        token_hash: token_hash(&token),
+        created_by: actor.id.clone(),
+    })
+    .await?;
+    audit_service::record(Some(actor), RESOURCE, feed.id, "create", None, Some(&feed)).await;
+    Ok(CalendarFeedCreatedDto { id: feed.id, dokter_id, url: feed_url(&token), token })
+}
+
+pub async fn handle_revoke_calendar_feed(dokter_id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let revoked = calendar_feed_repo::revoke_calendar_feeds(dokter_id).await?;
+    if revoked.is_empty() {
+        return Err("Dokter tidak memiliki kalender aktif".to_string());
+    }
+    for feed in revoked {
+        audit_service::record(Some(actor), RESOURCE, feed.id, "revoke", None, Some(&feed)).await;
+    }
+    Ok(())
+}
+
+// Renders the doctor's appointments from CALENDAR_PAST_DAYS ago to CALENDAR_FUTURE_DAYS ahead.
+// Patients appear by initials only; the feed ends up in third-party calendar apps.
+pub async fn handle_get_calendar(token: &str) -> Result<String, String> {
+    let feed = calendar_feed_repo::get_active_calendar_feed_by_hash(&token_hash(token)).await?;
+    let dokter = dokter_repo::get_dokter_by_id(feed.dokter_id).await?;
+    let today = booking_service::today();
+    let from = today - Duration::days(days_from_env("CALENDAR_PAST_DAYS", DEFAULT_PAST_DAYS));
+    let to = today + Duration::days(days_from_env("CALENDAR_FUTURE_DAYS", DEFAULT_FUTURE_DAYS));
+    let appointments = appointment_repo::get_appointments_by_dokter_between(
+        feed.dokter_id,
+        &from.format("%Y-%m-%d").to_string(),
+        &to.format("%Y-%m-%d").to_string(),
+    )
+    .await?;
+
+    let mut pasien_ids: Vec<Uuid> = appointments.iter().map(|a| a.pasien_id).collect();
+    pasien_ids.sort();
+    pasien_ids.dedup();
+    let pasiens: HashMap<Uuid, Pasien> = pasien_repo::get_pasiens_by_ids(&pasien_ids)
+        .await?
+        .into_iter()
+        .map(|p| (p.id, p))
+        .collect();
+    let treatments: HashMap<Uuid, Treatment> = treatment_repo::get_all_treatments(true)
+        .await?
+        .into_iter()
+        .map(|t| (t.id, t))
+        .collect();
+
+    // Every change after the booking bumps SEQUENCE so clients replace their copy
+    let appointment_ids: Vec<Uuid> = appointments.iter().map(|a| a.id).collect();
+    let mut revisions: HashMap<Uuid, (u32, DateTime<Utc>)> = HashMap::new();
+    for change in audit_log_repo::get_changes_for_records("appointment", &appointment_ids).await? {
+        let revision = revisions.entry(change.record_id).or_insert((0, change.created_at));
+        if change.action != "create" {
+            revision.0 += 1;
+        }
+        revision.1 = revision.1.max(change.created_at);
+    }
+
+    let uid_domain = env::var("CALENDAR_UID_DOMAIN").unwrap_or_else(|_| "klinik-be".to_string());
+    let now = stamp(Utc::now());
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//klinik-be//Calendar Feed//ID".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape_text(&format!("Jadwal {}", dokter.nama))),
+    ];
+    let offset = appointment_service::clinic_offset();
+    for appointment in &appointments {
+        let Some(start) = appointment_service::appointment_start(appointment, offset) else {
+            continue;
+        };
+        let treatment_ids = safety_service::uuids_from_value(&appointment.treatment_ids);
+        let end = start + Duration::minutes(clinic_resource_service::duration_minutes(&treatment_ids, &treatments));
+        let (sequence, modified) = revisions.get(&appointment.id).copied().unwrap_or((0, start));
+        let names: Vec<&str> = treatment_ids
+            .iter()
+            .filter_map(|id| treatments.get(id))
+            .map(|t| t.name.as_str())
+            .collect();
+        let summary = match pasiens.get(&appointment.pasien_id) {
+            Some(pasien) => format!("{} ({})", names.join(", "), initials(&pasien.nama_lengkap)),
+            None => names.join(", "),
+        };
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@{}", appointment.id, uid_domain),
+            format!("DTSTAMP:{}", now),
+            format!("DTSTART:{}", stamp(start)),
+            format!("DTEND:{}", stamp(end)),
+            format!("SEQUENCE:{}", sequence),
+            format!("LAST-MODIFIED:{}", stamp(modified)),
+            format!("STATUS:{}", event_status(appointment)),
+            format!("SUMMARY:{}", escape_text(&summary)),
+            format!("DESCRIPTION:{}", escape_text(&format!("Status: {}", appointment.status))),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    Ok(lines.iter().map(|line| fold(line)).collect())
+}
+
+fn event_status(appointment: &Appointment) -> &'static str {
+    match appointment.status.as_str() {
+        "cancelled" | "no_show" => "CANCELLED",
+        "pending" | "held" => "TENTATIVE",
+        _ => "CONFIRMED",
+    }
+}
+
+fn stamp(at: DateTime<Utc>) -> String {
+    at.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// "Ayu Sri Lestari" -> "A.S.L."
+fn initials(name: &str) -> String {
+    name.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .flat_map(|c| c.to_uppercase().chain(std::iter::once('.')))
+        .collect()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Splits a content line into 75-octet pieces without cutting a UTF-8 character, then ends
+// it with CRLF. Continuation lines start with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unfold(folded: &str) -> String {
+        folded.trim_end_matches("\r\n").replace("\r\n ", "")
+    }
+
+    #[test]
+    fn escapes_text_special_characters() {
+        assert_eq!(escape_text(r"a\b;c,d"), r"a\\b\;c\,d");
+        assert_eq!(escape_text("baris 1\r\nbaris 2\nbaris 3"), "baris 1\\nbaris 2\\nbaris 3");
+        assert_eq!(escape_text("Facial: 60 menit"), "Facial: 60 menit");
+    }
+
+    #[test]
+    fn short_lines_are_only_terminated() {
+        assert_eq!(fold("SUMMARY:Facial"), "SUMMARY:Facial\r\n");
+        let exact = "X".repeat(MAX_LINE_OCTETS);
+        assert_eq!(fold(&exact), format!("{}\r\n", exact));
+    }
+
+    #[test]
+    fn long_lines_fold_at_75_octets() {
+        let line = format!("DESCRIPTION:{}", "a".repeat(200));
+        let folded = fold(&line);
+        for physical in folded.split_terminator("\r\n") {
+            assert!(physical.len() <= MAX_LINE_OCTETS, "{} octets", physical.len());
+        }
+        assert_eq!(folded.split_terminator("\r\n").next().unwrap().len(), MAX_LINE_OCTETS);
+        assert_eq!(unfold(&folded), line);
+    }
+
+    #[test]
+    fn folding_never_splits_a_multibyte_character() {
+        let line = format!("SUMMARY:{}", "é🙂".repeat(40));
+        let folded = fold(&line);
+        for physical in folded.split_terminator("\r\n") {
+            assert!(physical.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(unfold(&folded), line);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::dtos::clinic_resource_dto::{CreateClinicResourceDto, UpdateClinicResourceDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::appointment::Appointment;
+use crate::models::clinic_resource::ClinicResource;
+use crate::models::treatment::Treatment;
+use crate::repositories::{appointment_repo, clinic_resource_repo, treatment_repo};
+use crate::services::{audit_service, safety_service, schedule_service};
+use chrono::Timelike;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const RESOURCE: &str = "clinic_resource";
+// Used when none of an appointment's treatments has an estimated_time
+const DEFAULT_APPOINTMENT_MINUTES: i64 = 30;
+// Appointments in these statuses no longer hold their rooms and equipment
+const RELEASED_STATUSES: [&str; 2] = ["cancelled", "no_show"];
+
+fn validate_kind(kind: &str) -> Result<(), String> {
+    if kind == "room" || kind == "equipment" {
+        Ok(())
+    } else {
+        Err("kind harus room atau equipment".to_string())
+    }
+}
+
+fn validate_category(category: &str) -> Result<(), String> {
+    if category.trim().is_empty() {
+        Err("category wajib diisi".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+pub async fn handle_get_all_clinic_resources(include_deleted: bool) -> Result<Vec<ClinicResource>, String> {
+    clinic_resource_repo::get_all_clinic_resources(include_deleted).await
+}
+
+pub async fn handle_create_clinic_resource(resource_data: CreateClinicResourceDto, actor: &AuthenticatedUser) -> Result<ClinicResource, String> {
+    validate_kind(&resource_data.kind)?;
+    validate_category(&resource_data.category)?;
+    let clinic_resource = clinic_resource_repo::create_clinic_resource(&resource_data).await?;
+    audit_service::record(Some(actor), RESOURCE, clinic_resource.id, "create", None, Some(&clinic_resource)).await;
+    Ok(clinic_resource)
+}
+
+pub async fn handle_update_clinic_resource(id: Uuid, resource_data: UpdateClinicResourceDto, actor: &AuthenticatedUser) -> Result<ClinicResource, String> {
+    if let Some(kind) = &resource_data.kind {
+        validate_kind(kind)?;
+    }
+    if let Some(category) = &resource_data.category {
+        validate_category(category)?;
+    }
+    let before = clinic_resource_repo::get_clinic_resource_by_id(id).await?;
+    let clinic_resource = clinic_resource_repo::update_clinic_resource(id, &resource_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&clinic_resource)).await;
+    Ok(clinic_resource)
+}
+
+pub async fn handle_delete_clinic_resource(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = clinic_resource_repo::get_clinic_resource_by_id(id).await?;
+    let deleted = clinic_resource_repo::delete_clinic_resource(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_clinic_resource(id: Uuid, actor: &AuthenticatedUser) -> Result<ClinicResource, String> {
+    let before = clinic_resource_repo::get_clinic_resource_by_id(id).await?;
+    let clinic_resource = clinic_resource_repo::restore_clinic_resource(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&clinic_resource)).await;
+    Ok(clinic_resource)
+}
+
+// An appointment lasts as long as its treatments' estimated_time together
+pub fn duration_minutes(treatment_ids: &[Uuid], treatments: &HashMap<Uuid, Treatment>) -> i64 {
+    let total: i64 = treatment_ids
+        .iter()
+        .filter_map(|id| treatments.get(id))
+        .map(|treatment| treatment.estimated_time as i64)
+        .sum();
+    if total > 0 { total } else { DEFAULT_APPOINTMENT_MINUTES }
+}
+
+fn minutes_of_day(waktu: &str) -> Option<i64> {
+    schedule_service::parse_time(waktu).map(|time| (time.num_seconds_from_midnight() / 60) as i64)
+}
+
+// The bookings, treatments and resources of one day, loaded once so many candidate
+// slots can be checked without going back to the database
+pub struct DayBookings {
+    tanggal: String,
+    pub treatments: HashMap<Uuid, Treatment>,
+    pub appointments: Vec<Appointment>,
+    resources: Vec<ClinicResource>,
+}
+
+impl DayBookings {
+    pub async fn load(tanggal: &str) -> Result<DayBookings, String> {
+        let treatments = treatment_repo::get_all_treatments(true)
+            .await?
+            .into_iter()
+            .map(|treatment| (treatment.id, treatment))
+            .collect();
+        let appointments = appointment_repo::get_appointments_between(tanggal, tanggal)
+            .await?
+            .into_iter()
+            .filter(|appointment| !RELEASED_STATUSES.contains(&appointment.status.as_str()))
+            .collect();
+        let resources = clinic_resource_repo::get_active_clinic_resources().await?;
+        Ok(DayBookings { tanggal: tanggal.to_string(), treatments, appointments, resources })
+    }
+
+    #[cfg(test)]
+    pub fn from_parts(tanggal: &str, treatments: Vec<Treatment>, appointments: Vec<Appointment>, resources: Vec<ClinicResource>) -> DayBookings {
+        DayBookings {
+            tanggal: tanggal.to_string(),
+            treatments: treatments.into_iter().map(|treatment| (treatment.id, treatment)).collect(),
+            appointments,
+            resources,
+        }
+    }
+
+    // Start and end of an appointment in minutes since midnight
+    pub fn span(&self, treatment_ids: &[Uuid], waktu: &str) -> Option<(i64, i64)> {
+        let start = minutes_of_day(waktu)?;
+        Some((start, start + duration_minutes(treatment_ids, &self.treatments)))
+    }
+
+    // Active appointments overlapping [start, end), other than those in `exclude`
+    pub fn overlapping(&self, start: i64, end: i64, exclude: &[Uuid]) -> Vec<&Appointment> {
+        self.appointments
+            .iter()
+            .filter(|appointment| !exclude.contains(&appointment.id))
+            .filter(|appointment| {
+                self.span(&safety_service::uuids_from_value(&appointment.treatment_ids), &appointment.waktu)
+                    .is_some_and(|(other_start, other_end)| start < other_end && other_start < end)
+            })
+            .collect()
+    }
+
+    // Picks a free room or machine for every resource category the treatments require, or
+    // fails naming the category that is fully booked at that time. `exclude` holds the
+    // appointments being moved, so they don't collide with their own current slots.
+    pub fn allocate(&self, treatment_ids: &[Uuid], waktu: &str, exclude: &[Uuid]) -> Result<Vec<Uuid>, String> {
+        let mut categories: Vec<&str> = Vec::new();
+        for category in treatment_ids
+            .iter()
+            .filter_map(|id| self.treatments.get(id))
+            .flat_map(|treatment| treatment.required_resources.iter())
+        {
+            if !categories.contains(&category.as_str()) {
+                categories.push(category);
+            }
+        }
+        if categories.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let (start, end) = self
+            .span(treatment_ids, waktu)
+            .ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+        let occupied: Vec<Uuid> = self
+            .overlapping(start, end, exclude)
+            .iter()
+            .flat_map(|appointment| appointment.resource_ids.iter().copied())
+            .collect();
+
+        let mut allocated: Vec<Uuid> = Vec::new();
+        for category in categories {
+            let candidates: Vec<&ClinicResource> = self.resources.iter().filter(|r| r.category == category).collect();
+            if candidates.is_empty() {
+                return Err(format!("Belum ada ruangan/alat aktif dengan kategori {}", category));
+            }
+            let free = candidates
+                .into_iter()
+                .find(|r| !occupied.contains(&r.id) && !allocated.contains(&r.id))
+                .ok_or_else(|| format!("Semua ruangan/alat {} sudah terpakai pada {} {}", category, self.tanggal, waktu))?;
+            allocated.push(free.id);
+        }
+        Ok(allocated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn treatment(estimated_time: i32, required_resources: &[&str]) -> Treatment {
+        Treatment {
+            estimated_time,
+            required_resources: required_resources.iter().map(|c| c.to_string()).collect(),
+            ..Treatment::fixture()
+        }
+    }
+
+    fn resource(category: &str) -> ClinicResource {
+        ClinicResource { category: category.to_string(), ..ClinicResource::fixture() }
+    }
+
+    fn booking(treatment_id: Uuid, waktu: &str, resource_ids: Vec<Uuid>) -> Appointment {
+        Appointment {
+            treatment_ids: json!([treatment_id]),
+            waktu: waktu.to_string(),
+            resource_ids,
+            ..Appointment::fixture()
+        }
+    }
+
+    #[test]
+    fn overlapping_uses_treatment_length_and_skips_excluded() {
+        let laser = treatment(60, &[]);
+        let at_ten = booking(laser.id, "10:00", Vec::new());
+        let at_ten_id = at_ten.id;
+        let day = DayBookings::from_parts("2026-03-02", vec![laser], vec![at_ten], Vec::new());
+
+        assert_eq!(day.overlapping(10 * 60 + 59, 11 * 60 + 30, &[]).len(), 1);
+        assert!(day.overlapping(11 * 60, 12 * 60, &[]).is_empty());
+        assert!(day.overlapping(9 * 60, 10 * 60, &[]).is_empty());
+        assert!(day.overlapping(10 * 60, 11 * 60, &[at_ten_id]).is_empty());
+    }
+
+    #[test]
+    fn allocate_fails_naming_the_fully_booked_category() {
+        let laser = treatment(60, &["laser_room"]);
+        let room = resource("laser_room");
+        let taken = booking(laser.id, "10:00", vec![room.id]);
+        let laser_id = laser.id;
+        let day = DayBookings::from_parts("2026-03-02", vec![laser], vec![taken], vec![room]);
+
+        let err = day.allocate(&[laser_id], "10:30", &[]).unwrap_err();
+        assert!(err.contains("laser_room"), "{}", err);
+        assert!(day.allocate(&[laser_id], "11:00", &[]).is_ok());
+    }
+
+    #[test]
+    fn allocate_ignores_the_slots_of_moving_appointments() {
+        let laser = treatment(60, &["laser_room"]);
+        let room = resource("laser_room");
+        let moving = booking(laser.id, "10:00", vec![room.id]);
+        let (laser_id, room_id, moving_id) = (laser.id, room.id, moving.id);
+        let day = DayBookings::from_parts("2026-03-02", vec![laser], vec![moving], vec![room]);
+
+        assert_eq!(day.allocate(&[laser_id], "10:30", &[moving_id]).unwrap(), vec![room_id]);
+    }
+
+    #[test]
+    fn allocate_never_hands_out_the_same_resource_twice() {
+        let (laser, peel) = (treatment(30, &["laser_room"]), treatment(30, &["laser_room", "hifu"]));
+        let (one, two, machine) = (resource("laser_room"), resource("laser_room"), resource("hifu"));
+        let taken = booking(laser.id, "10:00", vec![one.id]);
+        let (laser_id, peel_id, one_id, two_id, machine_id) = (laser.id, peel.id, one.id, two.id, machine.id);
+        let day = DayBookings::from_parts("2026-03-02", vec![laser, peel], vec![taken], vec![one, two, machine]);
+
+        // Treatments sharing a category need one room of it, and not the one already taken
+        assert_eq!(day.allocate(&[laser_id, peel_id], "10:00", &[]).unwrap(), vec![two_id, machine_id]);
+        assert_eq!(day.allocate(&[laser_id, peel_id], "11:00", &[]).unwrap(), vec![one_id, machine_id]);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::dtos::consent_dto::{
+    AppointmentConsentStatusDto, ConsentTemplateRecordDto, CreateConsentSignatureDto, CreateConsentTemplateDto,
+    CreateConsentTemplateVersionDto, SignConsentDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::consent_signature::ConsentSignature;
+use crate::models::consent_template::ConsentTemplate;
+use crate::repositories::{appointment_repo, consent_signature_repo, consent_template_repo, treatment_repo};
+use crate::services::{audit_service, photo_service, safety_service};
+use crate::storage::photo_storage::PhotoStorage;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use image::ImageFormat;
+use std::io::Cursor;
+use uuid::Uuid;
+
+const RESOURCE: &str = "consent_template";
+const SIGNATURE_RESOURCE: &str = "consent_signature";
+const SIGNATURE_CONTENT_TYPE: &str = "image/png";
+
+fn validate_code(code: &str) -> Result<(), String> {
+    let valid = !code.is_empty()
+        && code
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err("code hanya boleh berisi huruf kecil, angka, '-' dan '_'".to_string())
+    }
+}
+
+async fn validate_treatments(treatment_ids: &[Uuid]) -> Result<(), String> {
+    if treatment_ids.is_empty() {
+        return Err("Template consent harus terhubung ke minimal satu treatment".to_string());
+    }
+    for id in treatment_ids {
+        let treatment = treatment_repo::get_treatment_by_id(*id).await?;
+        if treatment.deleted_at.is_some() {
+            return Err(format!("Treatment {} sudah dihapus", id));
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_get_all_consent_templates(include_deleted: bool) -> Result<Vec<ConsentTemplate>, String> {
+    consent_template_repo::get_all_consent_templates(include_deleted).await
+}
+
+pub async fn handle_create_consent_template(template_data: CreateConsentTemplateDto, actor: &AuthenticatedUser) -> Result<ConsentTemplate, String> {
+    validate_code(&template_data.code)?;
+    validate_treatments(&template_data.treatment_ids).await?;
+    if !consent_template_repo::get_consent_templates_by_code(&template_data.code).await?.is_empty() {
+        return Err(format!("Template dengan code {} sudah ada, terbitkan versi baru", template_data.code));
+    }
+    let record = ConsentTemplateRecordDto {
+        code: template_data.code,
+        version: 1,
+        title: template_data.title,
+        body: template_data.body,
+        treatment_ids: template_data.treatment_ids,
+        is_active: true,
+        created_by: actor.id.clone(),
+    };
+    let template = consent_template_repo::create_consent_template(&record).await?;
+    audit_service::record(Some(actor), RESOURCE, template.id, "create", None, Some(&template)).await;
+    Ok(template)
+}
+
+// Publishes a new version from the given one, which must be the current version of its code
+pub async fn handle_create_consent_template_version(
+    id: Uuid,
+    version_data: CreateConsentTemplateVersionDto,
+    actor: &AuthenticatedUser,
+) -> Result<ConsentTemplate, String> {
+    let current = consent_template_repo::get_consent_template_by_id(id).await?;
+    if current.deleted_at.is_some() || !current.is_active {
+        return Err("Hanya versi aktif yang dapat diperbarui".to_string());
+    }
+    let treatment_ids = version_data.treatment_ids.unwrap_or_else(|| current.treatment_ids.clone());
+    validate_treatments(&treatment_ids).await?;
+
+    let record = ConsentTemplateRecordDto {
+        code: current.code.clone(),
+        version: current.version + 1,
+        title: version_data.title.unwrap_or_else(|| current.title.clone()),
+        body: version_data.body.unwrap_or_else(|| current.body.clone()),
+        treatment_ids,
+        is_active: true,
+        created_by: actor.id.clone(),
+    };
+    let template = consent_template_repo::create_consent_template(&record).await?;
+    consent_template_repo::deactivate_consent_template(current.id).await?;
+    audit_service::record(Some(actor), RESOURCE, template.id, "create", Some(&current), Some(&template)).await;
+    Ok(template)
+}
+
+pub async fn handle_delete_consent_template(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = consent_template_repo::get_consent_template_by_id(id).await?;
+    let deleted = consent_template_repo::delete_consent_template(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_consent_template(id: Uuid, actor: &AuthenticatedUser) -> Result<ConsentTemplate, String> {
+    let before = consent_template_repo::get_consent_template_by_id(id).await?;
+    let template = consent_template_repo::restore_consent_template(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&template)).await;
+    Ok(template)
+}
+
+async fn required_templates(treatment_ids: &[Uuid]) -> Result<Vec<ConsentTemplate>, String> {
+    let templates = consent_template_repo::get_active_consent_templates().await?;
+    Ok(templates
+        .into_iter()
+        .filter(|template| template.treatment_ids.iter().any(|id| treatment_ids.contains(id)))
+        .collect())
+}
+
+// A signature counts for its template code whatever the version, so publishing a new
+// version doesn't invalidate consents patients have already given.
+fn missing_templates(required: &[ConsentTemplate], signatures: &[ConsentSignature]) -> Vec<ConsentTemplate> {
+    required
+        .iter()
+        .filter(|template| !signatures.iter().any(|s| s.template_code == template.code))
+        .cloned()
+        .collect()
+}
+
+// Fails while any consent required by the treatments has not been signed for the appointment.
+// A booking that doesn't exist yet has no signatures, so `appointment_id` is optional.
+pub async fn ensure_consents_signed(appointment_id: Option<Uuid>, treatment_ids: &[Uuid]) -> Result<(), String> {
+    let required = required_templates(treatment_ids).await?;
+    if required.is_empty() {
+        return Ok(());
+    }
+    let signatures = match appointment_id {
+        Some(id) => consent_signature_repo::get_consent_signatures_by_appointment(id).await?,
+        None => Vec::new(),
+    };
+    let missing = missing_templates(&required, &signatures);
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let titles = missing.iter().map(|t| t.title.as_str()).collect::<Vec<_>>().join(", ");
+    Err(format!("Appointment belum dapat diselesaikan, consent belum ditandatangani: {}", titles))
+}
+
+pub async fn handle_get_appointment_consents(appointment_id: Uuid) -> Result<AppointmentConsentStatusDto, String> {
+    let appointment = appointment_repo::get_appointment_by_id(appointment_id).await?;
+    let treatment_ids = safety_service::uuids_from_value(&appointment.treatment_ids);
+    let required = required_templates(&treatment_ids).await?;
+    let signatures = consent_signature_repo::get_consent_signatures_by_appointment(appointment_id).await?;
+    let missing = missing_templates(&required, &signatures);
+    Ok(AppointmentConsentStatusDto { required, signatures, missing })
+}
+
+// JSON body limit for signing: the image arrives base64 encoded, a third larger than its bytes
+pub fn sign_request_limit() -> usize {
+    photo_service::max_upload_bytes() / 3 * 4 + 64 * 1024
+}
+
+// Decodes a base64 signature (optionally a `data:` URL) and re-encodes it as PNG, which
+// keeps transparency and drops any metadata. CPU bound, so callers run it on the blocking pool.
+pub fn process_signature_image(data: &str) -> Result<Vec<u8>, String> {
+    let encoded = match data.split_once(',') {
+        Some((prefix, rest)) if prefix.starts_with("data:") => rest,
+        _ => data,
+    };
+    let bytes = STANDARD
+        .decode(encoded.trim())
+        .map_err(|e| format!("Tanda tangan bukan base64 yang valid: {}", e))?;
+    if bytes.len() > photo_service::max_upload_bytes() {
+        return Err("Ukuran gambar tanda tangan terlalu besar".to_string());
+    }
+    let image = image::load_from_memory(&bytes).map_err(|e| format!("Gambar tanda tangan tidak valid: {}", e))?;
+    let mut out = Cursor::new(Vec::new());
+    image
+        .write_to(&mut out, ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode signature: {}", e))?;
+    Ok(out.into_inner())
+}
+
+// Records a consent for the appointment, witnessed by the staff member submitting it
+pub async fn handle_sign_consent(
+    storage: &dyn PhotoStorage,
+    appointment_id: Uuid,
+    sign_data: SignConsentDto,
+    signature_image: Option<Vec<u8>>,
+    actor: &AuthenticatedUser,
+) -> Result<ConsentSignature, String> {
+    let signer_name = sign_data.signer_name.trim().to_string();
+    if signer_name.is_empty() {
+        return Err("signer_name wajib diisi".to_string());
+    }
+    let appointment = appointment_repo::get_appointment_by_id(appointment_id).await?;
+    if appointment.deleted_at.is_some() {
+        return Err("Appointment not found".to_string());
+    }
+    let template = consent_template_repo::get_consent_template_by_id(sign_data.template_id).await?;
+    if template.deleted_at.is_some() || !template.is_active {
+        return Err("Template consent tidak aktif, gunakan versi terbaru".to_string());
+    }
+    let treatment_ids = safety_service::uuids_from_value(&appointment.treatment_ids);
+    if !template.treatment_ids.iter().any(|id| treatment_ids.contains(id)) {
+        return Err("Template consent tidak berlaku untuk treatment pada appointment ini".to_string());
+    }
+
+    let id = Uuid::new_v4();
+    let signature_image_key = match signature_image {
+        Some(bytes) => {
+            let key = format!("pasiens/{}/consents/{}.png", appointment.pasien_id, id);
+            storage.put(&key, bytes, SIGNATURE_CONTENT_TYPE).await?;
+            Some(key)
+        }
+        None => None,
+    };
+    let signature_data = CreateConsentSignatureDto {
+        id,
+        appointment_id,
+        pasien_id: appointment.pasien_id,
+        template_id: template.id,
+        template_code: template.code,
+        template_version: template.version,
+        signer_name,
+        signer_relation: sign_data.signer_relation,
+        signature_method: if signature_image_key.is_some() { "image" } else { "typed" }.to_string(),
+        signature_image_key: signature_image_key.clone(),
+        witnessed_by: actor.id.clone(),
+        signed_at: Utc::now(),
+    };
+
+    match consent_signature_repo::create_consent_signature(&signature_data).await {
+        Ok(signature) => {
+            audit_service::record(Some(actor), SIGNATURE_RESOURCE, signature.id, "create", None, Some(&signature)).await;
+            Ok(signature)
+        }
+        Err(e) => {
+            if let Some(key) = &signature_image_key {
+                let _ = storage.delete(key).await;
+            }
+            Err(e)
+        }
+    }
+}
+
+pub async fn handle_get_signature_image(storage: &dyn PhotoStorage, signature_id: Uuid) -> Result<Vec<u8>, String> {
+    let signature = consent_signature_repo::get_consent_signature_by_id(signature_id).await?;
+    let key = signature
+        .signature_image_key
+        .ok_or_else(|| "Consent ini ditandatangani dengan nama, tanpa gambar".to_string())?;
+    storage.get(&key).await
+}
@@ -1,20 +1,39 @@
 use crate::dtos::dokter_dto::{CreateDokterDto, UpdateDokterDto};
 use crate::models::dokter::Dokter;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::dokter_repo;
+use crate::services::audit_service;
 use uuid::Uuid;
 
-pub async fn handle_get_all_dokters() -> Result<Vec<Dokter>, String> {
-    dokter_repo::get_all_dokters().await
+const RESOURCE: &str = "dokter";
+
+pub async fn handle_get_all_dokters(include_deleted: bool) -> Result<Vec<Dokter>, String> {
+    dokter_repo::get_all_dokters(include_deleted).await
+}
+
+pub async fn handle_create_dokter(dokter_data: CreateDokterDto, actor: &AuthenticatedUser) -> Result<Dokter, String> {
+    let dokter = dokter_repo::create_dokter(&dokter_data).await?;
+    audit_service::record(Some(actor), RESOURCE, dokter.id, "create", None, Some(&dokter)).await;
+    Ok(dokter)
 }
 
-pub async fn handle_create_dokter(dokter_data: CreateDokterDto) -> Result<Dokter, String> {
-    dokter_repo::create_dokter(&dokter_data).await
+pub async fn handle_update_dokter(id: Uuid, dokter_data: UpdateDokterDto, actor: &AuthenticatedUser) -> Result<Dokter, String> {
+    let before = dokter_repo::get_dokter_by_id(id).await?;
+    let dokter = dokter_repo::update_dokter(id, &dokter_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&dokter)).await;
+    Ok(dokter)
 }
 
-pub async fn handle_update_dokter(id: Uuid, dokter_data: UpdateDokterDto) -> Result<Dokter, String> {
-    dokter_repo::update_dokter(id, &dokter_data).await
+pub async fn handle_delete_dokter(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = dokter_repo::get_dokter_by_id(id).await?;
+    let deleted = dokter_repo::delete_dokter(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
 }
 
-pub async fn handle_delete_dokter(id: Uuid) -> Result<(), String> {
-    dokter_repo::delete_dokter(id).await
-}
\ No newline at end of file
+pub async fn handle_restore_dokter(id: Uuid, actor: &AuthenticatedUser) -> Result<Dokter, String> {
+    let before = dokter_repo::get_dokter_by_id(id).await?;
+    let dokter = dokter_repo::restore_dokter(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&dokter)).await;
+    Ok(dokter)
+}
@@ -1,20 +1,92 @@
 use crate::dtos::invoice_dto::{CreateInvoiceDto, UpdateInvoiceDto};
+use crate::events::event_bus::{self, Topic};
 use crate::models::invoice::Invoice;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::invoice_repo;
+use crate::services::{audit_service, safety_service};
 use uuid::Uuid;
 
-pub async fn handle_get_all_invoices() -> Result<Vec<Invoice>, String> {
-    invoice_repo::get_all_invoices().await
+const RESOURCE: &str = "invoice";
+
+// Only payments are pushed; the front desk doesn't need to hear about draft edits
+fn publish_if_paid(before: Option<&Invoice>, invoice: &Invoice) {
+    if invoice.status == "paid" && before.is_none_or(|before| before.status != "paid") {
+        event_bus::publish(Topic::Invoices, "invoice.paid", invoice.id, invoice);
+    }
+}
+
+pub async fn handle_get_all_invoices(include_deleted: bool) -> Result<Vec<Invoice>, String> {
+    invoice_repo::get_all_invoices(include_deleted).await
 }
 
-pub async fn handle_create_invoice(invoice_data: CreateInvoiceDto) -> Result<Invoice, String> {
-    invoice_repo::create_invoice(&invoice_data).await
+pub async fn handle_create_invoice(invoice_data: CreateInvoiceDto, actor: &AuthenticatedUser) -> Result<Invoice, String> {
+    let product_ids = safety_service::product_ids_from_invoice_items(&invoice_data.items);
+    let warnings = safety_service::enforce(
+        invoice_data.pasien_id,
+        &[],
+        &product_ids,
+        invoice_data.acknowledge_warnings,
+        invoice_data.safety_override_reason.as_deref(),
+    )
+    .await?;
+    let invoice = invoice_repo::create_invoice(&invoice_data).await?;
+    audit_service::record(Some(actor), RESOURCE, invoice.id, "create", None, Some(&invoice)).await;
+    publish_if_paid(None, &invoice);
+    safety_service::record_override(
+        actor,
+        invoice.pasien_id,
+        RESOURCE,
+        invoice.id,
+        warnings,
+        invoice_data.safety_override_reason.as_deref(),
+    )
+    .await;
+    Ok(invoice)
 }
 
-pub async fn handle_update_invoice(id: Uuid, invoice_data: UpdateInvoiceDto) -> Result<Invoice, String> {
-    invoice_repo::update_invoice(id, &invoice_data).await
+pub async fn handle_update_invoice(id: Uuid, invoice_data: UpdateInvoiceDto, actor: &AuthenticatedUser) -> Result<Invoice, String> {
+    let before = invoice_repo::get_invoice_by_id(id).await?;
+    // Changed product lines, or a different patient, are checked like a new sale
+    let mut warnings = Vec::new();
+    if invoice_data.pasien_id.is_some() || invoice_data.items.is_some() {
+        let pasien_id = invoice_data.pasien_id.unwrap_or(before.pasien_id);
+        let product_ids = safety_service::product_ids_from_invoice_items(
+            invoice_data.items.as_ref().unwrap_or(&before.items),
+        );
+        warnings = safety_service::enforce(
+            pasien_id,
+            &[],
+            &product_ids,
+            invoice_data.acknowledge_warnings,
+            invoice_data.safety_override_reason.as_deref(),
+        )
+        .await?;
+    }
+    let invoice = invoice_repo::update_invoice(id, &invoice_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&invoice)).await;
+    publish_if_paid(Some(&before), &invoice);
+    safety_service::record_override(
+        actor,
+        invoice.pasien_id,
+        RESOURCE,
+        id,
+        warnings,
+        invoice_data.safety_override_reason.as_deref(),
+    )
+    .await;
+    Ok(invoice)
 }
 
-pub async fn handle_delete_invoice(id: Uuid) -> Result<(), String> {
-    invoice_repo::delete_invoice(id).await
-}
\ No newline at end of file
+pub async fn handle_delete_invoice(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = invoice_repo::get_invoice_by_id(id).await?;
+    let deleted = invoice_repo::delete_invoice(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_invoice(id: Uuid, actor: &AuthenticatedUser) -> Result<Invoice, String> {
+    let before = invoice_repo::get_invoice_by_id(id).await?;
+    let invoice = invoice_repo::restore_invoice(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&invoice)).await;
+    Ok(invoice)
+}
@@ -0,0 +1,46 @@
+use crate::dtos::job_dto::{JobRunQueryDto, UpdateJobDto};
+use crate::jobs::job_runner::next_occurrence;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::job::Job;
+use crate::models::job_run::JobRun;
+use crate::repositories::{job_repo, job_run_repo};
+use crate::services::audit_service;
+use chrono::Utc;
+
+const RESOURCE: &str = "job";
+
+pub async fn handle_get_all_jobs() -> Result<Vec<Job>, String> {
+    job_repo::get_all_jobs().await
+}
+
+pub async fn handle_get_job_runs(name: &str, query: JobRunQueryDto) -> Result<Vec<JobRun>, String> {
+    job_run_repo::get_job_runs(name, query.limit).await
+}
+
+async fn get_job(name: &str) -> Result<Job, String> {
+    job_repo::get_all_jobs()
+        .await?
+        .into_iter()
+        .find(|job| job.name == name)
+        .ok_or_else(|| "Job not found".to_string())
+}
+
+// A new schedule takes effect from now, so the next run is recomputed from it
+pub async fn handle_update_job(name: &str, mut job_data: UpdateJobDto, actor: &AuthenticatedUser) -> Result<Job, String> {
+    let before = get_job(name).await?;
+    if let Some(schedule) = &job_data.schedule {
+        job_data.next_run_at = Some(next_occurrence(schedule, Utc::now())?);
+    }
+    let job = job_repo::update_job(name, &job_data).await?;
+    audit_service::record(Some(actor), RESOURCE, job.id, "update", Some(&before), Some(&job)).await;
+    Ok(job)
+}
+
+// Makes the job due immediately; the runner picks it up on its next poll
+pub async fn handle_trigger_job(name: &str, actor: &AuthenticatedUser) -> Result<Job, String> {
+    let before = get_job(name).await?;
+    let job_data = UpdateJobDto { schedule: None, is_enabled: None, next_run_at: Some(Utc::now()) };
+    let job = job_repo::update_job(name, &job_data).await?;
+    audit_service::record(Some(actor), RESOURCE, job.id, "trigger", Some(&before), Some(&job)).await;
+    Ok(job)
+}
@@ -6,4 +6,23 @@ pub mod pasien_service;
 pub mod appointment_service;
 pub mod treatment_progress_service;
 pub mod skin_analysis_service;
-pub mod invoice_service;
\ No newline at end of file
+pub mod invoice_service;
+pub mod retention_service;
+pub mod audit_service;
+pub mod timeline_service;
+pub mod photo_service;
+pub mod recommendation_service;
+pub mod safety_service;
+pub mod consent_service;
+pub mod reminder_service;
+pub mod job_service;
+pub mod report_service;
+pub mod schedule_service;
+pub mod clinic_resource_service;
+pub mod queue_service;
+pub mod booking_service;
+pub mod waitlist_service;
+pub mod appointment_series_service;
+pub mod calendar_service;
+pub mod session_service;
+pub mod totp_service;
\ No newline at end of file
@@ -1,21 +1,40 @@
 //src/services/pasien_service.rs
 use crate::dtos::pasien_dto::{CreatePasienDto, UpdatePasienDto};
 use crate::models::pasien::Pasien;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::pasien_repo;
+use crate::services::audit_service;
 use uuid::Uuid;
 
-pub async fn handle_get_all_pasiens() -> Result<Vec<Pasien>, String> {
-    pasien_repo::get_all_pasiens().await
+const RESOURCE: &str = "pasien";
+
+pub async fn handle_get_all_pasiens(include_deleted: bool) -> Result<Vec<Pasien>, String> {
+    pasien_repo::get_all_pasiens(include_deleted).await
+}
+
+pub async fn handle_create_pasien(pasien_data: CreatePasienDto, actor: &AuthenticatedUser) -> Result<Pasien, String> {
+    let pasien = pasien_repo::create_pasien(&pasien_data).await?;
+    audit_service::record(Some(actor), RESOURCE, pasien.id, "create", None, Some(&pasien)).await;
+    Ok(pasien)
 }
 
-pub async fn handle_create_pasien(pasien_data: CreatePasienDto) -> Result<Pasien, String> {
-    pasien_repo::create_pasien(&pasien_data).await
+pub async fn handle_update_pasien(id: Uuid, pasien_data: UpdatePasienDto, actor: &AuthenticatedUser) -> Result<Pasien, String> {
+    let before = pasien_repo::get_pasien_by_id(id).await?;
+    let pasien = pasien_repo::update_pasien(id, &pasien_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&pasien)).await;
+    Ok(pasien)
 }
 
-pub async fn handle_update_pasien(id: Uuid, pasien_data: UpdatePasienDto) -> Result<Pasien, String> {
-    pasien_repo::update_pasien(id, &pasien_data).await
+pub async fn handle_delete_pasien(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = pasien_repo::get_pasien_by_id(id).await?;
+    let deleted = pasien_repo::delete_pasien(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
 }
 
-pub async fn handle_delete_pasien(id: Uuid) -> Result<(), String> {
-    pasien_repo::delete_pasien(id).await
-}
\ No newline at end of file
+pub async fn handle_restore_pasien(id: Uuid, actor: &AuthenticatedUser) -> Result<Pasien, String> {
+    let before = pasien_repo::get_pasien_by_id(id).await?;
+    let pasien = pasien_repo::restore_pasien(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&pasien)).await;
+    Ok(pasien)
+}
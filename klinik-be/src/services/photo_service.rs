@@ -0,0 +1,192 @@
+use crate::dtos::photo_dto::{BeforeAfterPhotosDto, CreatePhotoDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::photo::Photo;
+use crate::repositories::{photo_repo, skin_analysis_repo, treatment_progress_repo};
+use crate::services::audit_service;
+use crate::storage::photo_storage::PhotoStorage;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use std::env;
+use std::io::Cursor;
+use uuid::Uuid;
+
+const RESOURCE: &str = "photo";
+const STORED_CONTENT_TYPE: &str = "image/jpeg";
+const THUMBNAIL_SIZE: u32 = 320;
+const JPEG_QUALITY: u8 = 90;
+const DEFAULT_MAX_UPLOAD_BYTES: usize = 10 * 1024 * 1024;
+
+pub fn max_upload_bytes() -> usize {
+    env::var("PHOTO_MAX_UPLOAD_BYTES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_MAX_UPLOAD_BYTES)
+}
+
+pub struct ProcessedPhoto {
+    pub full: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    JpegEncoder::new_with_quality(&mut out, JPEG_QUALITY)
+        .encode_image(&image.to_rgb8())
+        .map_err(|e| format!("Failed to encode photo: {}", e))?;
+    Ok(out)
+}
+
+// Decodes the upload and re-encodes it from raw pixels. Nothing from the original
+// file survives except the pixels, which strips EXIF (GPS position, device serials).
+// The EXIF orientation is applied first so the stripped photo isn't shown sideways.
+// CPU bound, so callers run it on the blocking pool.
+pub fn process_photo(bytes: &[u8]) -> Result<ProcessedPhoto, String> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read photo: {}", e))?
+        .into_decoder()
+        .map_err(|e| format!("Unsupported photo format: {}", e))?;
+    let orientation = decoder
+        .orientation()
+        .map_err(|e| format!("Failed to read photo orientation: {}", e))?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode photo: {}", e))?;
+    image.apply_orientation(orientation);
+
+    Ok(ProcessedPhoto {
+        full: encode_jpeg(&image)?,
+        thumbnail: encode_jpeg(&image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE))?,
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+// Where a photo is attached: exactly one of the two ids is set
+struct PhotoOwner {
+    pasien_id: Uuid,
+    skin_analysis_id: Option<Uuid>,
+    treatment_progress_id: Option<Uuid>,
+    tanggal: String,
+}
+
+async fn store_photo(
+    storage: &dyn PhotoStorage,
+    owner: PhotoOwner,
+    processed: ProcessedPhoto,
+    actor: &AuthenticatedUser,
+) -> Result<Photo, String> {
+    let id = Uuid::new_v4();
+    let storage_key = format!("pasiens/{}/{}.jpg", owner.pasien_id, id);
+    let thumbnail_key = format!("pasiens/{}/{}_thumb.jpg", owner.pasien_id, id);
+
+    storage.put(&storage_key, processed.full, STORED_CONTENT_TYPE).await?;
+    storage.put(&thumbnail_key, processed.thumbnail, STORED_CONTENT_TYPE).await?;
+
+    let photo_data = CreatePhotoDto {
+        id,
+        pasien_id: owner.pasien_id,
+        skin_analysis_id: owner.skin_analysis_id,
+        treatment_progress_id: owner.treatment_progress_id,
+        tanggal: owner.tanggal,
+        storage_key: storage_key.clone(),
+        thumbnail_key: thumbnail_key.clone(),
+        content_type: STORED_CONTENT_TYPE.to_string(),
+        width: processed.width as i32,
+        height: processed.height as i32,
+        uploaded_by: Some(actor.id.clone()),
+    };
+
+    match photo_repo::create_photo(&photo_data).await {
+        Ok(photo) => {
+            audit_service::record(Some(actor), RESOURCE, photo.id, "create", None, Some(&photo)).await;
+            Ok(photo)
+        }
+        Err(e) => {
+            // Don't leave orphaned objects behind when the row couldn't be written
+            let _ = storage.delete(&storage_key).await;
+            let _ = storage.delete(&thumbnail_key).await;
+            Err(e)
+        }
+    }
+}
+
+pub async fn handle_upload_skin_analysis_photo(
+    storage: &dyn PhotoStorage,
+    skin_analysis_id: Uuid,
+    processed: ProcessedPhoto,
+    actor: &AuthenticatedUser,
+) -> Result<Photo, String> {
+    let analysis = skin_analysis_repo::get_skin_analysis_by_id(skin_analysis_id).await?;
+    if analysis.deleted_at.is_some() {
+        return Err("Skin analysis not found".to_string());
+    }
+    let owner = PhotoOwner {
+        pasien_id: analysis.pasien_id,
+        skin_analysis_id: Some(analysis.id),
+        treatment_progress_id: None,
+        tanggal: analysis.tanggal_analisis,
+    };
+    store_photo(storage, owner, processed, actor).await
+}
+
+pub async fn handle_upload_treatment_progress_photo(
+    storage: &dyn PhotoStorage,
+    treatment_progress_id: Uuid,
+    processed: ProcessedPhoto,
+    actor: &AuthenticatedUser,
+) -> Result<Photo, String> {
+    let progress = treatment_progress_repo::get_treatment_progress_by_id(treatment_progress_id).await?;
+    if progress.deleted_at.is_some() {
+        return Err("Treatment progress not found".to_string());
+    }
+    let owner = PhotoOwner {
+        pasien_id: progress.pasien_id,
+        skin_analysis_id: None,
+        treatment_progress_id: Some(progress.id),
+        tanggal: progress.tanggal_progress,
+    };
+    store_photo(storage, owner, processed, actor).await
+}
+
+pub async fn handle_get_skin_analysis_photos(skin_analysis_id: Uuid) -> Result<Vec<Photo>, String> {
+    photo_repo::get_photos_by_skin_analysis(skin_analysis_id).await
+}
+
+pub async fn handle_get_treatment_progress_photos(treatment_progress_id: Uuid) -> Result<Vec<Photo>, String> {
+    photo_repo::get_photos_by_treatment_progress(treatment_progress_id).await
+}
+
+// Returns (bytes, content type) of the photo or its thumbnail
+pub async fn handle_get_photo_content(
+    storage: &dyn PhotoStorage,
+    id: Uuid,
+    thumbnail: bool,
+) -> Result<(Vec<u8>, String), String> {
+    let photo = photo_repo::get_photo_by_id(id).await?;
+    if photo.deleted_at.is_some() {
+        return Err("Photo not found".to_string());
+    }
+    let key = if thumbnail { &photo.thumbnail_key } else { &photo.storage_key };
+    let bytes = storage.get(key).await?;
+    Ok((bytes, photo.content_type))
+}
+
+// Files stay in storage until the retention purge, so a restore would still be possible
+pub async fn handle_delete_photo(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = photo_repo::get_photo_by_id(id).await?;
+    let deleted = photo_repo::delete_photo(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+// First and most recent photo of the patient, across skin analyses and progress entries
+pub async fn handle_get_before_after(pasien_id: Uuid) -> Result<BeforeAfterPhotosDto, String> {
+    let mut photos = photo_repo::get_photos_by_pasien(pasien_id).await?;
+    if photos.len() < 2 {
+        return Ok(BeforeAfterPhotosDto { before: photos.pop(), after: None });
+    }
+    let after = photos.pop();
+    let before = photos.into_iter().next();
+    Ok(BeforeAfterPhotosDto { before, after })
+}
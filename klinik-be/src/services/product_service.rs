@@ -1,22 +1,121 @@
 use crate::dtos::product_dto::{CreateProductDto, UpdateProductDto};
+use crate::events::event_bus::{self, Topic};
 use crate::models::product::Product;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::product_repo;
+use crate::services::audit_service;
+use chrono::{Duration, NaiveDate};
+use serde_json::{json, Value};
+use std::env;
 use uuid::Uuid;
-pub async fn handle_get_all_products() -> Result<Vec<Product>, String> {
-    product_repo::get_all_products().await
+
+const RESOURCE: &str = "product";
+const DEFAULT_EXPIRY_WARNING_DAYS: i64 = 30;
+const DEFAULT_LOW_STOCK_THRESHOLD: i32 = 5;
+
+fn validate_expiry_date(expiry_date: Option<&str>) -> Result<(), String> {
+    if let Some(date) = expiry_date
+        && NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err()
+    {
+        return Err("expiry_date harus berformat YYYY-MM-DD".to_string());
+    }
+    Ok(())
+}
+
+fn low_stock_threshold() -> i32 {
+    env::var("LOW_STOCK_THRESHOLD")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_LOW_STOCK_THRESHOLD)
+}
+
+// Published when an update takes stock down to the threshold, not on every later sale
+fn publish_if_low_stock(before: &Product, product: &Product) {
+    let threshold = low_stock_threshold();
+    if product.stock <= threshold && before.stock > threshold {
+        event_bus::publish(Topic::Products, "product.low_stock", product.id, &stock_entry(product));
+    }
+}
+
+pub async fn handle_get_all_products(include_deleted: bool) -> Result<Vec<Product>, String> {
+    product_repo::get_all_products(include_deleted).await
 }
 
 // Fungsi untuk menangani "CREATE" produk
-pub async fn handle_create_product(product_data: CreateProductDto) -> Result<Product, String> {
+pub async fn handle_create_product(product_data: CreateProductDto, actor: &AuthenticatedUser) -> Result<Product, String> {
     // Di sini Anda bisa menambahkan logika bisnis tambahan sebelum memanggil repository
-    product_repo::create_product(&product_data).await
+    validate_expiry_date(product_data.expiry_date.as_deref())?;
+    let product = product_repo::create_product(&product_data).await?;
+    audit_service::record(Some(actor), RESOURCE, product.id, "create", None, Some(&product)).await;
+    Ok(product)
 }
 // Fungsi untuk handle update produk
-pub async fn handle_update_product(id: Uuid, product_data: UpdateProductDto) -> Result<Product, String> {
-    product_repo::update_product(id, &product_data).await
+pub async fn handle_update_product(id: Uuid, product_data: UpdateProductDto, actor: &AuthenticatedUser) -> Result<Product, String> {
+    validate_expiry_date(product_data.expiry_date.as_deref())?;
+    let before = product_repo::get_product_by_id(id).await?;
+    let product = product_repo::update_product(id, &product_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&product)).await;
+    publish_if_low_stock(&before, &product);
+    Ok(product)
 }
 
 // Fungsi untuk handle delete produk
-pub async fn handle_delete_product(id: Uuid) -> Result<(), String> {
-    product_repo::delete_product(id).await
-}
\ No newline at end of file
+pub async fn handle_delete_product(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = product_repo::get_product_by_id(id).await?;
+    let deleted = product_repo::delete_product(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+// Fungsi untuk handle restore produk yang sudah dihapus
+pub async fn handle_restore_product(id: Uuid, actor: &AuthenticatedUser) -> Result<Product, String> {
+    let before = product_repo::get_product_by_id(id).await?;
+    let product = product_repo::restore_product(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&product)).await;
+    Ok(product)
+}
+
+fn stock_entry(product: &Product) -> Value {
+    json!({
+        "id": product.id,
+        "name": product.name,
+        "stock": product.stock,
+        "expiry_date": product.expiry_date,
+    })
+}
+
+// Products whose stock has expired, expires within PRODUCT_EXPIRY_WARNING_DAYS, or has
+// fallen to LOW_STOCK_THRESHOLD or below. Products without an expiry date are never flagged as expiring.
+pub async fn check_stock(today: NaiveDate) -> Result<Value, String> {
+    let warning_days = env::var("PRODUCT_EXPIRY_WARNING_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+    let low_stock_threshold = low_stock_threshold();
+    let warn_until = today + Duration::days(warning_days);
+
+    let mut expired = Vec::new();
+    let mut expiring_soon = Vec::new();
+    let mut low_stock = Vec::new();
+    for product in product_repo::get_all_products(false).await? {
+        let expiry = product
+            .expiry_date
+            .as_deref()
+            .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+        if product.stock > 0 {
+            match expiry {
+                Some(date) if date <= today => expired.push(stock_entry(&product)),
+                Some(date) if date <= warn_until => expiring_soon.push(stock_entry(&product)),
+                _ => {}
+            }
+        }
+        if product.stock <= low_stock_threshold {
+            low_stock.push(stock_entry(&product));
+        }
+    }
+    Ok(json!({
+        "expired": expired,
+        "expiring_soon": expiring_soon,
+        "low_stock": low_stock,
+    }))
+}
@@ -0,0 +1,214 @@
+use crate::dtos::queue_dto::{
+    CheckInDto, CreateQueueEntryDto, QueueDisplayEntryDto, QueueEntryViewDto, QueueQueryDto, QueueStatusChangeDto,
+};
+use crate::events::event_bus::{self, Topic};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::queue_entry::QueueEntry;
+use crate::models::treatment::Treatment;
+use crate::repositories::{appointment_repo, dokter_repo, pasien_repo, queue_entry_repo, treatment_repo};
+use crate::services::{appointment_service, audit_service, clinic_resource_service, safety_service, schedule_service};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const RESOURCE: &str = "queue_entry";
+// Check-ins racing for the same number retry with the next one this many times
+const MAX_NUMBER_ATTEMPTS: usize = 5;
+
+// Today's date and the current time (HH:MM) in clinic time
+fn clinic_now(now: DateTime<Utc>) -> (String, String) {
+    let local = now.with_timezone(&appointment_service::clinic_offset());
+    (local.format("%Y-%m-%d").to_string(), local.format("%H:%M").to_string())
+}
+
+async fn treatment_map() -> Result<HashMap<Uuid, Treatment>, String> {
+    Ok(treatment_repo::get_all_treatments(true)
+        .await?
+        .into_iter()
+        .map(|treatment| (treatment.id, treatment))
+        .collect())
+}
+
+// Minutes each doctor still has booked in the queue: what is left of the patient being
+// treated (by estimated_time, never below zero) plus everyone waiting. Also returns the
+// estimated wait of every waiting entry, which is its doctor's backlog ahead of it.
+fn backlog(
+    entries: &[QueueEntry],
+    treatments: &HashMap<Uuid, Treatment>,
+    now: DateTime<Utc>,
+) -> (HashMap<Uuid, i64>, HashMap<Uuid, i64>) {
+    let mut per_dokter: HashMap<Uuid, i64> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.status == "in_treatment") {
+        let duration = clinic_resource_service::duration_minutes(&entry.treatment_ids, treatments);
+        let elapsed = entry.called_at.map(|at| (now - at).num_minutes()).unwrap_or(0);
+        *per_dokter.entry(entry.dokter_id).or_default() += (duration - elapsed).max(0);
+    }
+    let mut waits: HashMap<Uuid, i64> = HashMap::new();
+    for entry in entries.iter().filter(|e| e.status == "waiting") {
+        let ahead = per_dokter.entry(entry.dokter_id).or_default();
+        waits.insert(entry.id, *ahead);
+        *ahead += clinic_resource_service::duration_minutes(&entry.treatment_ids, treatments);
+    }
+    (per_dokter, waits)
+}
+
+// The doctor on duty right now with the shortest backlog
+async fn least_busy_dokter(tanggal: &str, waktu: &str, minutes: i64, per_dokter: &HashMap<Uuid, i64>) -> Result<Uuid, String> {
+    let mut best: Option<(Uuid, i64)> = None;
+    for dokter in dokter_repo::get_all_dokters(false).await? {
+        if schedule_service::ensure_dokter_available(dokter.id, tanggal, waktu, minutes).await.is_err() {
+            continue;
+        }
+        let load = per_dokter.get(&dokter.id).copied().unwrap_or(0);
+        if best.is_none_or(|(_, best_load)| load < best_load) {
+            best = Some((dokter.id, load));
+        }
+    }
+    best.map(|(id, _)| id)
+        .ok_or_else(|| "Tidak ada dokter yang praktik saat ini".to_string())
+}
+
+pub async fn handle_check_in(check_in_data: CheckInDto, actor: &AuthenticatedUser) -> Result<QueueEntry, String> {
+    let now = Utc::now();
+    let (tanggal, waktu) = clinic_now(now);
+    let pasien = pasien_repo::get_pasien_by_id(check_in_data.pasien_id).await?;
+    if pasien.deleted_at.is_some() {
+        return Err("Pasien not found".to_string());
+    }
+    let entries = queue_entry_repo::get_queue_entries_by_date(&tanggal).await?;
+    if entries
+        .iter()
+        .any(|e| e.pasien_id == pasien.id && (e.status == "waiting" || e.status == "in_treatment"))
+    {
+        return Err("Pasien sudah ada di antrean".to_string());
+    }
+    let treatments = treatment_map().await?;
+    let (per_dokter, _) = backlog(&entries, &treatments, now);
+
+    let (dokter_id, treatment_ids) = match check_in_data.appointment_id {
+        Some(appointment_id) => {
+            let appointment = appointment_repo::get_appointment_by_id(appointment_id).await?;
+            if appointment.deleted_at.is_some() || appointment.pasien_id != pasien.id {
+                return Err("Appointment tidak ditemukan untuk pasien ini".to_string());
+            }
+            if appointment.tanggal != tanggal {
+                return Err(format!("Appointment dijadwalkan pada {}, bukan hari ini", appointment.tanggal));
+            }
+            (
+                check_in_data.dokter_id.unwrap_or(appointment.dokter_id),
+                check_in_data
+                    .treatment_ids
+                    .unwrap_or_else(|| safety_service::uuids_from_value(&appointment.treatment_ids)),
+            )
+        }
+        None => {
+            let minutes = clinic_resource_service::duration_minutes(
+                check_in_data.treatment_ids.as_deref().unwrap_or_default(),
+                &treatments,
+            );
+            let dokter_id = match check_in_data.dokter_id {
+                Some(dokter_id) => {
+                    schedule_service::ensure_dokter_available(dokter_id, &tanggal, &waktu, minutes).await?;
+                    dokter_id
+                }
+                None => least_busy_dokter(&tanggal, &waktu, minutes, &per_dokter).await?,
+            };
+            (dokter_id, check_in_data.treatment_ids.unwrap_or_default())
+        }
+    };
+
+    let mut next_number = entries.iter().map(|e| e.queue_number).max().unwrap_or(0) + 1;
+    let mut created = None;
+    for _ in 0..MAX_NUMBER_ATTEMPTS {
+        let entry_data = CreateQueueEntryDto {
+            tanggal: tanggal.clone(),
+            queue_number: next_number,
+            pasien_id: pasien.id,
+            dokter_id,
+            appointment_id: check_in_data.appointment_id,
+            treatment_ids: treatment_ids.clone(),
+            status: "waiting".to_string(),
+            checked_in_at: now,
+            created_by: actor.id.clone(),
+        };
+        created = queue_entry_repo::create_queue_entry(&entry_data).await?;
+        if created.is_some() {
+            break;
+        }
+        next_number = queue_entry_repo::get_queue_entries_by_date(&tanggal)
+            .await?
+            .iter()
+            .map(|e| e.queue_number)
+            .max()
+            .unwrap_or(0)
+            + 1;
+    }
+    let entry = created.ok_or_else(|| "Gagal mengambil nomor antrean, silakan coba lagi".to_string())?;
+    audit_service::record(Some(actor), RESOURCE, entry.id, "create", None, Some(&entry)).await;
+    event_bus::publish(Topic::Queue, "queue.checked_in", entry.id, &entry);
+
+    // The queue number is already issued, so a booking that moved on meanwhile is only logged
+    if let Some(appointment_id) = entry.appointment_id
+        && let Err(e) = appointment_service::check_in(appointment_id, actor).await
+    {
+        println!("Failed to check in appointment {}: {}", appointment_id, e);
+    }
+    Ok(entry)
+}
+
+// waiting -> in_treatment -> done, or waiting -> cancelled
+pub async fn handle_update_queue_status(id: Uuid, status: &str, actor: &AuthenticatedUser) -> Result<QueueEntry, String> {
+    let before = queue_entry_repo::get_queue_entry_by_id(id).await?;
+    let now = Utc::now();
+    let change = match (before.status.as_str(), status) {
+        ("waiting", "in_treatment") => QueueStatusChangeDto { status: status.to_string(), called_at: Some(now), finished_at: None },
+        ("in_treatment", "done") | ("waiting", "cancelled") => {
+            QueueStatusChangeDto { status: status.to_string(), called_at: None, finished_at: Some(now) }
+        }
+        (from, to) => return Err(format!("Status antrean tidak dapat diubah dari {} ke {}", from, to)),
+    };
+    let entry = queue_entry_repo::change_queue_entry_status(id, &before.status, &change)
+        .await?
+        .ok_or_else(|| "Status antrean sudah berubah, muat ulang antrean".to_string())?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&entry)).await;
+    event_bus::publish(Topic::Queue, &format!("queue.{}", entry.status), entry.id, &entry);
+    Ok(entry)
+}
+
+pub async fn handle_get_queue(query: QueueQueryDto) -> Result<Vec<QueueEntryViewDto>, String> {
+    let now = Utc::now();
+    let tanggal = query.tanggal.unwrap_or_else(|| clinic_now(now).0);
+    let entries = queue_entry_repo::get_queue_entries_by_date(&tanggal).await?;
+    let treatments = treatment_map().await?;
+    let (_, waits) = backlog(&entries, &treatments, now);
+    Ok(entries
+        .into_iter()
+        .map(|entry| QueueEntryViewDto {
+            estimated_wait_minutes: waits.get(&entry.id).copied(),
+            entry,
+        })
+        .collect())
+}
+
+// Today's open entries for the waiting-room screen
+pub async fn handle_get_queue_display() -> Result<Vec<QueueDisplayEntryDto>, String> {
+    let now = Utc::now();
+    let entries = queue_entry_repo::get_queue_entries_by_date(&clinic_now(now).0).await?;
+    let treatments = treatment_map().await?;
+    let (_, waits) = backlog(&entries, &treatments, now);
+    let dokter_names: HashMap<Uuid, String> = dokter_repo::get_all_dokters(true)
+        .await?
+        .into_iter()
+        .map(|dokter| (dokter.id, dokter.nama))
+        .collect();
+    Ok(entries
+        .into_iter()
+        .filter(|e| e.status == "waiting" || e.status == "in_treatment")
+        .map(|entry| QueueDisplayEntryDto {
+            queue_number: entry.queue_number,
+            dokter_nama: dokter_names.get(&entry.dokter_id).cloned().unwrap_or_default(),
+            estimated_wait_minutes: waits.get(&entry.id).copied(),
+            status: entry.status,
+        })
+        .collect())
+}
@@ -0,0 +1,345 @@
+use crate::dtos::recommendation_rule_dto::{CreateRecommendationRuleDto, UpdateRecommendationRuleDto};
+use crate::dtos::recommendation_suggestion_dto::{CreateRecommendationSuggestionDto, RecommendationDecisionDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::pasien::Pasien;
+use crate::models::recommendation_rule::{RecommendationRule, RuleCondition};
+use crate::models::recommendation_suggestion::RecommendationSuggestion;
+use crate::models::skin_analysis::SkinAnalysis;
+use crate::repositories::{
+    pasien_repo, product_repo, recommendation_rule_repo, recommendation_suggestion_repo, skin_analysis_repo,
+    treatment_repo,
+};
+use crate::services::{audit_service, skin_analysis_service};
+use uuid::Uuid;
+
+const RESOURCE: &str = "recommendation_rule";
+
+async fn validate_rule_targets(
+    treatment_ids: Option<&[Uuid]>,
+    product_ids: Option<&[Uuid]>,
+) -> Result<(), String> {
+    for id in treatment_ids.unwrap_or_default() {
+        let treatment = treatment_repo::get_treatment_by_id(*id).await?;
+        if treatment.deleted_at.is_some() {
+            return Err(format!("Treatment {} sudah dihapus", id));
+        }
+    }
+    for id in product_ids.unwrap_or_default() {
+        let product = product_repo::get_product_by_id(*id).await?;
+        if product.deleted_at.is_some() {
+            return Err(format!("Produk {} sudah dihapus", id));
+        }
+    }
+    Ok(())
+}
+
+fn validate_conditions(conditions: &[RuleCondition]) -> Result<(), String> {
+    if conditions.is_empty() {
+        return Err("Rule harus memiliki minimal satu kondisi".to_string());
+    }
+    for condition in conditions {
+        match condition {
+            RuleCondition::ScoreAtLeast { metric, .. } | RuleCondition::ScoreAtMost { metric, .. }
+                if !skin_analysis_service::is_score_metric(metric) =>
+            {
+                return Err(format!("Metric skor {} tidak dikenal", metric));
+            }
+            RuleCondition::FitzpatrickBetween { min, max }
+                if !(1..=6).contains(min) || !(1..=6).contains(max) || min > max =>
+            {
+                return Err("Rentang Fitzpatrick harus antara 1 dan 6".to_string());
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_get_all_recommendation_rules(include_deleted: bool) -> Result<Vec<RecommendationRule>, String> {
+    recommendation_rule_repo::get_all_recommendation_rules(include_deleted).await
+}
+
+pub async fn handle_create_recommendation_rule(rule_data: CreateRecommendationRuleDto, actor: &AuthenticatedUser) -> Result<RecommendationRule, String> {
+    validate_conditions(&rule_data.conditions)?;
+    validate_rule_targets(rule_data.treatment_ids.as_deref(), rule_data.product_ids.as_deref()).await?;
+    let rule = recommendation_rule_repo::create_recommendation_rule(&rule_data).await?;
+    audit_service::record(Some(actor), RESOURCE, rule.id, "create", None, Some(&rule)).await;
+    Ok(rule)
+}
+
+pub async fn handle_update_recommendation_rule(id: Uuid, rule_data: UpdateRecommendationRuleDto, actor: &AuthenticatedUser) -> Result<RecommendationRule, String> {
+    if let Some(conditions) = &rule_data.conditions {
+        validate_conditions(conditions)?;
+    }
+    validate_rule_targets(rule_data.treatment_ids.as_deref(), rule_data.product_ids.as_deref()).await?;
+    let before = recommendation_rule_repo::get_recommendation_rule_by_id(id).await?;
+    let rule = recommendation_rule_repo::update_recommendation_rule(id, &rule_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&rule)).await;
+    Ok(rule)
+}
+
+pub async fn handle_delete_recommendation_rule(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = recommendation_rule_repo::get_recommendation_rule_by_id(id).await?;
+    let deleted = recommendation_rule_repo::delete_recommendation_rule(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_recommendation_rule(id: Uuid, actor: &AuthenticatedUser) -> Result<RecommendationRule, String> {
+    let before = recommendation_rule_repo::get_recommendation_rule_by_id(id).await?;
+    let rule = recommendation_rule_repo::restore_recommendation_rule(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&rule)).await;
+    Ok(rule)
+}
+
+fn device_reading(analysis: &SkinAnalysis, metric: &str) -> Option<f64> {
+    analysis
+        .device_readings
+        .iter()
+        .find(|reading| reading.metric.eq_ignore_ascii_case(metric))
+        .map(|reading| reading.value)
+}
+
+// A condition on a finding the analysis doesn't have never holds
+fn condition_holds(condition: &RuleCondition, analysis: &SkinAnalysis) -> bool {
+    let score = |metric: &str| {
+        analysis
+            .scores
+            .as_ref()
+            .and_then(|scores| skin_analysis_service::score_value(scores, metric))
+    };
+    match condition {
+        RuleCondition::ScoreAtLeast { metric, value } => score(metric).is_some_and(|v| v >= *value),
+        RuleCondition::ScoreAtMost { metric, value } => score(metric).is_some_and(|v| v <= *value),
+        RuleCondition::SkinTypeIn { skin_types } => analysis.skin_type.is_some_and(|t| skin_types.contains(&t)),
+        RuleCondition::FitzpatrickBetween { min, max } => {
+            analysis.fitzpatrick_type.is_some_and(|f| (*min..=*max).contains(&f))
+        }
+        RuleCondition::DeviceReadingAtLeast { metric, value } => {
+            device_reading(analysis, metric).is_some_and(|v| v >= *value)
+        }
+        RuleCondition::DeviceReadingAtMost { metric, value } => {
+            device_reading(analysis, metric).is_some_and(|v| v <= *value)
+        }
+    }
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+// Returns the first contraindication keyword found in the patient's allergy or condition
+// history. Keywords match whole words, so "asma" doesn't fire on "plasma".
+fn matched_contraindication<'a>(rule: &'a RecommendationRule, pasien: &Pasien) -> Option<&'a str> {
+    let history: Vec<Vec<String>> = [pasien.riwayat_alergi.as_deref(), pasien.kondisi_medis.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(words)
+        .collect();
+    rule.contraindications.iter().map(|keyword| keyword.trim()).find(|keyword| {
+        let keyword = words(keyword);
+        !keyword.is_empty()
+            && history
+                .iter()
+                .any(|text| text.windows(keyword.len()).any(|window| window == keyword.as_slice()))
+    })
+}
+
+// Runs the active rules against an analysis and stores the result as pending suggestions,
+// superseding any suggestions from an earlier run that were never decided on.
+pub async fn handle_generate_recommendations(skin_analysis_id: Uuid) -> Result<Vec<RecommendationSuggestion>, String> {
+    let analysis = skin_analysis_repo::get_skin_analysis_by_id(skin_analysis_id).await?;
+    if analysis.deleted_at.is_some() {
+        return Err("Skin analysis sudah dihapus".to_string());
+    }
+    let pasien = pasien_repo::get_pasien_by_id(analysis.pasien_id).await?;
+    let rules = recommendation_rule_repo::get_active_recommendation_rules().await?;
+
+    // Rules arrive by descending priority, so each item keeps the order of its strongest rule
+    let mut suggestions: Vec<CreateRecommendationSuggestionDto> = Vec::new();
+    for rule in &rules {
+        if !rule.conditions.iter().all(|condition| condition_holds(condition, &analysis)) {
+            continue;
+        }
+        if let Some(keyword) = matched_contraindication(rule, &pasien) {
+            println!("Rule {} skipped for pasien {}: contraindication '{}'", rule.id, pasien.id, keyword);
+            continue;
+        }
+        let items = rule
+            .treatment_ids
+            .iter()
+            .map(|id| ("treatment", *id))
+            .chain(rule.product_ids.iter().map(|id| ("product", *id)));
+        for (item_type, item_id) in items {
+            match suggestions
+                .iter_mut()
+                .find(|s| s.item_type == item_type && s.item_id == item_id)
+            {
+                Some(existing) => {
+                    existing.rule_ids.push(rule.id);
+                    existing.rationale = format!("{}; {}", existing.rationale, rule.rationale);
+                }
+                None => suggestions.push(CreateRecommendationSuggestionDto {
+                    skin_analysis_id,
+                    pasien_id: analysis.pasien_id,
+                    rule_ids: vec![rule.id],
+                    item_type: item_type.to_string(),
+                    item_id,
+                    rationale: rule.rationale.clone(),
+                    status: "pending".to_string(),
+                }),
+            }
+        }
+    }
+
+    recommendation_suggestion_repo::supersede_pending_suggestions(skin_analysis_id).await?;
+    recommendation_suggestion_repo::create_suggestions(&suggestions).await
+}
+
+pub async fn handle_get_recommendations(skin_analysis_id: Uuid) -> Result<Vec<RecommendationSuggestion>, String> {
+    recommendation_suggestion_repo::get_suggestions_by_skin_analysis(skin_analysis_id).await
+}
+
+// Applies the doctor's decision: accepted suggestions and any extra items become the
+// analysis' rekomendasi_treatment/rekomendasi_produk, the other pending ones are rejected.
+pub async fn handle_decide_recommendations(
+    skin_analysis_id: Uuid,
+    decision: RecommendationDecisionDto,
+    actor: &AuthenticatedUser,
+) -> Result<SkinAnalysis, String> {
+    let before = skin_analysis_repo::get_skin_analysis_by_id(skin_analysis_id).await?;
+    if before.deleted_at.is_some() {
+        return Err("Skin analysis sudah dihapus".to_string());
+    }
+    let suggestions = recommendation_suggestion_repo::get_suggestions_by_skin_analysis(skin_analysis_id).await?;
+    let pending: Vec<&RecommendationSuggestion> = suggestions.iter().filter(|s| s.status == "pending").collect();
+    if let Some(unknown) = decision
+        .accepted_suggestion_ids
+        .iter()
+        .find(|id| !pending.iter().any(|s| s.id == **id))
+    {
+        return Err(format!("Suggestion {} tidak ditemukan atau sudah diputuskan", unknown));
+    }
+
+    let additional_treatment_ids = decision.additional_treatment_ids.unwrap_or_default();
+    let additional_product_ids = decision.additional_product_ids.unwrap_or_default();
+    let (accepted, rejected): (Vec<&RecommendationSuggestion>, Vec<&RecommendationSuggestion>) = pending
+        .into_iter()
+        .partition(|s| decision.accepted_suggestion_ids.contains(&s.id));
+
+    let override_reason = decision
+        .override_reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+    let is_override = !rejected.is_empty() || !additional_treatment_ids.is_empty() || !additional_product_ids.is_empty();
+    if is_override && override_reason.is_none() {
+        return Err("override_reason wajib diisi jika rekomendasi ditolak atau ditambah".to_string());
+    }
+    validate_rule_targets(Some(&additional_treatment_ids), Some(&additional_product_ids)).await?;
+
+    let mut treatment_ids: Vec<Uuid> = Vec::new();
+    let mut product_ids: Vec<Uuid> = Vec::new();
+    for suggestion in &accepted {
+        let target = if suggestion.item_type == "product" { &mut product_ids } else { &mut treatment_ids };
+        target.push(suggestion.item_id);
+    }
+    for id in additional_treatment_ids {
+        if !treatment_ids.contains(&id) {
+            treatment_ids.push(id);
+        }
+    }
+    for id in additional_product_ids {
+        if !product_ids.contains(&id) {
+            product_ids.push(id);
+        }
+    }
+
+    let accepted_ids: Vec<Uuid> = accepted.iter().map(|s| s.id).collect();
+    let rejected_ids: Vec<Uuid> = rejected.iter().map(|s| s.id).collect();
+    recommendation_suggestion_repo::decide_suggestions(&accepted_ids, "accepted", &actor.id, override_reason).await?;
+    recommendation_suggestion_repo::decide_suggestions(&rejected_ids, "rejected", &actor.id, override_reason).await?;
+
+    let analysis = skin_analysis_repo::set_skin_analysis_recommendations(skin_analysis_id, &treatment_ids, &product_ids).await?;
+    audit_service::record(Some(actor), "skin_analysis", skin_analysis_id, "update", Some(&before), Some(&analysis)).await;
+    Ok(analysis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::skin_analysis::{DeviceMeasurement, SkinScores, SkinType};
+
+    fn analysis() -> SkinAnalysis {
+        SkinAnalysis {
+            skin_type: Some(SkinType::Oily),
+            fitzpatrick_type: Some(3),
+            scores: Some(SkinScores { acne: Some(70.0), ..Default::default() }),
+            device_readings: vec![DeviceMeasurement {
+                metric: "Moisture".to_string(),
+                value: 42.5,
+                unit: "%".to_string(),
+                device: None,
+            }],
+            ..SkinAnalysis::fixture()
+        }
+    }
+
+    fn rule(contraindications: &[&str]) -> RecommendationRule {
+        RecommendationRule {
+            contraindications: contraindications.iter().map(|k| k.to_string()).collect(),
+            ..RecommendationRule::fixture()
+        }
+    }
+
+    fn pasien(riwayat_alergi: Option<&str>, kondisi_medis: Option<&str>) -> Pasien {
+        Pasien {
+            riwayat_alergi: riwayat_alergi.map(str::to_string),
+            kondisi_medis: kondisi_medis.map(str::to_string),
+            ..Pasien::fixture()
+        }
+    }
+
+    #[test]
+    fn score_and_device_conditions_compare_inclusively() {
+        let analysis = analysis();
+        let at_least = |metric: &str, value| RuleCondition::ScoreAtLeast { metric: metric.to_string(), value };
+        assert!(condition_holds(&at_least("acne", 70.0), &analysis));
+        assert!(!condition_holds(&at_least("acne", 70.5), &analysis));
+        assert!(condition_holds(&RuleCondition::ScoreAtMost { metric: "acne".to_string(), value: 70.0 }, &analysis));
+        let moisture = |value| RuleCondition::DeviceReadingAtMost { metric: "moisture".to_string(), value };
+        assert!(condition_holds(&moisture(42.5), &analysis));
+        assert!(!condition_holds(&moisture(40.0), &analysis));
+    }
+
+    #[test]
+    fn skin_type_and_fitzpatrick_conditions() {
+        let analysis = analysis();
+        assert!(condition_holds(&RuleCondition::SkinTypeIn { skin_types: vec![SkinType::Dry, SkinType::Oily] }, &analysis));
+        assert!(!condition_holds(&RuleCondition::SkinTypeIn { skin_types: vec![SkinType::Dry] }, &analysis));
+        assert!(condition_holds(&RuleCondition::FitzpatrickBetween { min: 3, max: 4 }, &analysis));
+        assert!(!condition_holds(&RuleCondition::FitzpatrickBetween { min: 4, max: 6 }, &analysis));
+    }
+
+    #[test]
+    fn conditions_on_missing_findings_never_hold() {
+        let analysis = SkinAnalysis::fixture();
+        assert!(!condition_holds(&RuleCondition::ScoreAtMost { metric: "acne".to_string(), value: 100.0 }, &analysis));
+        assert!(!condition_holds(&RuleCondition::SkinTypeIn { skin_types: vec![SkinType::Oily] }, &analysis));
+        assert!(!condition_holds(&RuleCondition::FitzpatrickBetween { min: 1, max: 6 }, &analysis));
+        assert!(!condition_holds(&RuleCondition::DeviceReadingAtLeast { metric: "moisture".to_string(), value: 0.0 }, &analysis));
+    }
+
+    #[test]
+    fn contraindications_match_whole_words_case_insensitively() {
+        let rule = rule(&["  ", "Asma", "sedang hamil"]);
+        assert_eq!(matched_contraindication(&rule, &pasien(None, Some("Riwayat ASMA, hipertensi"))), Some("Asma"));
+        assert_eq!(matched_contraindication(&rule, &pasien(Some("transfusi plasma"), None)), None);
+        assert_eq!(matched_contraindication(&rule, &pasien(Some("retinol"), Some("Sedang  hamil"))), Some("sedang hamil"));
+        assert_eq!(matched_contraindication(&rule, &pasien(Some("sedang"), Some("hamil"))), None);
+        assert_eq!(matched_contraindication(&rule, &pasien(None, None)), None);
+    }
+}
@@ -0,0 +1,399 @@
+use crate::dtos::notification_dto::{CreateNotificationDeliveryDto, UpdateNotificationDeliveryDto};
+use crate::models::appointment::Appointment;
+use crate::models::notification_delivery::NotificationDelivery;
+use crate::models::pasien::Pasien;
+use crate::notifications::notifier::{Channel, Notification, Notifiers};
+use crate::repositories::{appointment_repo, dokter_repo, notification_delivery_repo, pasien_repo};
+use crate::services::appointment_service::{appointment_start, clinic_offset};
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::env;
+use uuid::Uuid;
+
+const DEFAULT_FOLLOW_UP_DELAY_HOURS: i64 = 24;
+const DEFAULT_MAX_ATTEMPTS: i32 = 5;
+const RETRY_BASE_MINUTES: i64 = 5;
+// Retries stop backing off further after this many doublings (5 * 2^8 minutes, about 21 hours)
+const MAX_RETRY_DOUBLINGS: i32 = 8;
+// Follow-ups older than this are not sent at all, e.g. after the scheduler was down for a while
+const FOLLOW_UP_LOOKBACK_DAYS: i64 = 3;
+// Appointment statuses that still get reminders before, and a follow-up after, the visit
+const REMINDER_STATUSES: [&str; 2] = ["booked", "rescheduled"];
+const FOLLOW_UP_STATUSES: [&str; 2] = ["completed", "paid"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ReminderKind {
+    DayBefore,
+    TwoHoursBefore,
+    FollowUp,
+}
+
+impl ReminderKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            ReminderKind::DayBefore => "reminder_h1",
+            ReminderKind::TwoHoursBefore => "reminder_2h",
+            ReminderKind::FollowUp => "follow_up",
+        }
+    }
+}
+
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> T {
+    env::var(name).ok().and_then(|v| v.parse().ok()).unwrap_or(default)
+}
+
+fn clinic_name() -> String {
+    env::var("CLINIC_NAME").unwrap_or_else(|_| "Klinik".to_string())
+}
+
+// Which message is due now, if any. When an appointment is booked late, only the latest
+// reminder that still applies is sent, not every reminder it has skipped past.
+fn due_kind(appointment: &Appointment, start: DateTime<Utc>, now: DateTime<Utc>) -> Option<ReminderKind> {
+    match appointment.status.as_str() {
+        status if REMINDER_STATUSES.contains(&status) => {
+            if now >= start {
+                None
+            } else if now >= start - Duration::hours(2) {
+                Some(ReminderKind::TwoHoursBefore)
+            } else if now >= start - Duration::hours(24) {
+                Some(ReminderKind::DayBefore)
+            } else {
+                None
+            }
+        }
+        status if FOLLOW_UP_STATUSES.contains(&status) => {
+            let send_at = start + Duration::hours(env_number("FOLLOW_UP_DELAY_HOURS", DEFAULT_FOLLOW_UP_DELAY_HOURS));
+            (now >= send_at && now < send_at + Duration::days(FOLLOW_UP_LOOKBACK_DAYS)).then_some(ReminderKind::FollowUp)
+        }
+        _ => None,
+    }
+}
+
+// Preferred channels from preferensi_komunikasi, falling back to DEFAULT_NOTIFICATION_CHANNEL
+fn channels_for(pasien: &Pasien) -> Vec<Channel> {
+    let mut channels: Vec<Channel> = Vec::new();
+    for channel in pasien
+        .preferensi_komunikasi
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str().and_then(Channel::parse))
+    {
+        if !channels.contains(&channel) {
+            channels.push(channel);
+        }
+    }
+    if channels.is_empty() {
+        let fallback = env::var("DEFAULT_NOTIFICATION_CHANNEL").unwrap_or_else(|_| "whatsapp".to_string());
+        channels.extend(Channel::parse(&fallback));
+    }
+    channels
+}
+
+fn recipient_for(pasien: &Pasien, channel: Channel) -> Option<String> {
+    let recipient = match channel {
+        Channel::Whatsapp | Channel::Sms => Some(pasien.no_telepon.clone()),
+        Channel::Email => pasien.email.clone(),
+    };
+    recipient.filter(|r| !r.trim().is_empty())
+}
+
+fn compose(kind: ReminderKind, pasien: &Pasien, appointment: &Appointment, dokter_nama: &str) -> (String, String) {
+    let clinic = clinic_name();
+    match kind {
+        ReminderKind::DayBefore => (
+            format!("Pengingat jadwal {} besok", clinic),
+            format!(
+                "Halo {}, ini pengingat jadwal Anda di {} besok, {} pukul {} dengan {}. Hubungi kami jika perlu menjadwalkan ulang.",
+                pasien.nama_lengkap, clinic, appointment.tanggal, appointment.waktu, dokter_nama
+            ),
+        ),
+        ReminderKind::TwoHoursBefore => (
+            format!("Jadwal {} hari ini", clinic),
+            format!(
+                "Halo {}, jadwal Anda di {} hari ini pukul {} dengan {}. Sampai jumpa!",
+                pasien.nama_lengkap, clinic, appointment.waktu, dokter_nama
+            ),
+        ),
+        ReminderKind::FollowUp => (
+            format!("Terima kasih telah berkunjung ke {}", clinic),
+            format!(
+                "Halo {}, terima kasih telah melakukan treatment di {}. Bagaimana kondisi kulit Anda? Hubungi kami jika ada keluhan.",
+                pasien.nama_lengkap, clinic
+            ),
+        ),
+    }
+}
+
+// Whether a reminder of this kind already went out for the appointment's current start.
+// Moving an appointment changes its start, so the new slot gets its own reminders; rows
+// from before scheduled_for was recorded count for whatever start the appointment has.
+fn already_scheduled(existing: &[NotificationDelivery], appointment_id: Uuid, kind: ReminderKind, start: DateTime<Utc>) -> bool {
+    existing.iter().any(|d| {
+        d.appointment_id == appointment_id && d.kind == kind.as_str() && d.scheduled_for.is_none_or(|at| at == start)
+    })
+}
+
+// A queued message is dropped when its appointment was cancelled, deleted or moved after
+// it was scheduled; the moved slot gets its own reminders.
+fn still_wanted(delivery: &NotificationDelivery, appointment: &Appointment, offset: FixedOffset) -> bool {
+    let statuses = if delivery.kind == ReminderKind::FollowUp.as_str() { FOLLOW_UP_STATUSES } else { REMINDER_STATUSES };
+    appointment.deleted_at.is_none()
+        && statuses.contains(&appointment.status.as_str())
+        && delivery
+            .scheduled_for
+            .is_none_or(|scheduled_for| appointment_start(appointment, offset) == Some(scheduled_for))
+}
+
+fn retry_delay(attempts: i32) -> Duration {
+    Duration::minutes(RETRY_BASE_MINUTES << (attempts - 1).clamp(0, MAX_RETRY_DOUBLINGS))
+}
+
+// Creates pending deliveries for every reminder that became due since the last run.
+// A delivery row per (appointment, kind, start) is what keeps a reminder from going out twice.
+async fn schedule_due_notifications(now: DateTime<Utc>) -> Result<usize, String> {
+    let offset = clinic_offset();
+    let today = now.with_timezone(&offset).date_naive();
+    let from = (today - Duration::days(FOLLOW_UP_LOOKBACK_DAYS + 1)).format("%Y-%m-%d").to_string();
+    let to = (today + Duration::days(2)).format("%Y-%m-%d").to_string();
+    let appointments = appointment_repo::get_appointments_between(&from, &to).await?;
+
+    let ids: Vec<Uuid> = appointments.iter().map(|a| a.id).collect();
+    let existing = notification_delivery_repo::get_deliveries_by_appointments(&ids).await?;
+
+    let mut pasiens: HashMap<Uuid, Pasien> = HashMap::new();
+    let mut dokter_names: HashMap<Uuid, String> = HashMap::new();
+    let mut scheduled = 0;
+    for appointment in &appointments {
+        let Some(start) = appointment_start(appointment, offset) else {
+            continue;
+        };
+        let Some(kind) = due_kind(appointment, start, now) else {
+            continue;
+        };
+        if already_scheduled(&existing, appointment.id, kind, start) {
+            continue;
+        }
+
+        // One patient that can't be loaded shouldn't hold up everyone else's reminders
+        if let Entry::Vacant(entry) = pasiens.entry(appointment.pasien_id) {
+            match pasien_repo::get_pasien_by_id(appointment.pasien_id).await {
+                Ok(pasien) => entry.insert(pasien),
+                Err(e) => {
+                    println!("Failed to load pasien {} for appointment {}: {}", appointment.pasien_id, appointment.id, e);
+                    continue;
+                }
+            };
+        }
+        let pasien = &pasiens[&appointment.pasien_id];
+        if pasien.deleted_at.is_some() {
+            continue;
+        }
+        if let Entry::Vacant(entry) = dokter_names.entry(appointment.dokter_id) {
+            let nama = dokter_repo::get_dokter_by_id(appointment.dokter_id)
+                .await
+                .map(|d| d.nama)
+                .unwrap_or_else(|_| "dokter kami".to_string());
+            entry.insert(nama);
+        }
+        let (subject, body) = compose(kind, pasien, appointment, &dokter_names[&appointment.dokter_id]);
+
+        for channel in channels_for(pasien) {
+            let Some(recipient) = recipient_for(pasien, channel) else {
+                println!("Pasien {} has no {} contact, skipping", pasien.id, channel.as_str());
+                continue;
+            };
+            let delivery_data = CreateNotificationDeliveryDto {
+                appointment_id: appointment.id,
+                pasien_id: pasien.id,
+                kind: kind.as_str().to_string(),
+                scheduled_for: start,
+                channel: channel.as_str().to_string(),
+                recipient,
+                subject: subject.clone(),
+                body: body.clone(),
+                status: "pending".to_string(),
+                attempts: 0,
+                next_attempt_at: now,
+            };
+            match notification_delivery_repo::create_delivery(&delivery_data).await {
+                Ok(_) => scheduled += 1,
+                Err(e) => println!(
+                    "Failed to schedule {} for appointment {} via {}: {}",
+                    kind.as_str(),
+                    appointment.id,
+                    channel.as_str(),
+                    e
+                ),
+            }
+        }
+    }
+    Ok(scheduled)
+}
+
+// Failed attempts back off exponentially (5, 10, 20, ... minutes, capped) until the attempt limit
+async fn attempt_delivery(notifiers: &Notifiers, delivery: &NotificationDelivery, now: DateTime<Utc>) -> bool {
+    let Some(channel) = Channel::parse(&delivery.channel) else {
+        return false;
+    };
+    let notification = Notification {
+        recipient: delivery.recipient.clone(),
+        subject: delivery.subject.clone(),
+        body: delivery.body.clone(),
+    };
+    let attempts = delivery.attempts + 1;
+    let update = match notifiers.for_channel(channel).send(&notification).await {
+        Ok(()) => UpdateNotificationDeliveryDto {
+            status: "sent".to_string(),
+            attempts,
+            last_error: None,
+            next_attempt_at: None,
+            sent_at: Some(now),
+        },
+        Err(e) => {
+            let exhausted = attempts >= env_number("NOTIFICATION_MAX_ATTEMPTS", DEFAULT_MAX_ATTEMPTS);
+            UpdateNotificationDeliveryDto {
+                status: if exhausted { "failed" } else { "pending" }.to_string(),
+                attempts,
+                last_error: Some(e),
+                next_attempt_at: (!exhausted).then(|| now + retry_delay(attempts)),
+                sent_at: None,
+            }
+        }
+    };
+    let sent = update.status == "sent";
+    if let Err(e) = notification_delivery_repo::update_delivery(delivery.id, &update).await {
+        println!("Failed to record notification delivery {}: {}", delivery.id, e);
+    }
+    sent
+}
+
+async fn cancel_delivery(delivery: &NotificationDelivery) {
+    let update = UpdateNotificationDeliveryDto {
+        status: "cancelled".to_string(),
+        attempts: delivery.attempts,
+        last_error: delivery.last_error.clone(),
+        next_attempt_at: None,
+        sent_at: None,
+    };
+    if let Err(e) = notification_delivery_repo::update_delivery(delivery.id, &update).await {
+        println!("Failed to cancel notification delivery {}: {}", delivery.id, e);
+    }
+}
+
+pub async fn run_reminder_cycle(notifiers: &Notifiers) -> Result<(usize, usize), String> {
+    let now = Utc::now();
+    let offset = clinic_offset();
+    let scheduled = schedule_due_notifications(now).await?;
+    let mut sent = 0;
+    // Appointments are read again right before sending, since they may have changed since
+    // their deliveries were queued
+    let mut appointments: HashMap<Uuid, Appointment> = HashMap::new();
+    for delivery in notification_delivery_repo::get_due_deliveries(now).await? {
+        let appointment = match appointments.entry(delivery.appointment_id) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => match appointment_repo::get_appointment_by_id(delivery.appointment_id).await {
+                Ok(appointment) => entry.insert(appointment),
+                Err(e) => {
+                    println!("Failed to load appointment {} for delivery {}: {}", delivery.appointment_id, delivery.id, e);
+                    continue;
+                }
+            },
+        };
+        if !still_wanted(&delivery, appointment, offset) {
+            cancel_delivery(&delivery).await;
+            continue;
+        }
+        if attempt_delivery(notifiers, &delivery, now).await {
+            sent += 1;
+        }
+    }
+    Ok((scheduled, sent))
+}
+
+pub async fn handle_get_appointment_notifications(appointment_id: Uuid) -> Result<Vec<NotificationDelivery>, String> {
+    notification_delivery_repo::get_deliveries_by_appointment(appointment_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn appointment(status: &str) -> Appointment {
+        Appointment { status: status.to_string(), ..Appointment::fixture() }
+    }
+
+    fn delivery(kind: &str, scheduled_for: Option<DateTime<Utc>>) -> NotificationDelivery {
+        NotificationDelivery {
+            appointment_id: Uuid::nil(),
+            kind: kind.to_string(),
+            scheduled_for,
+            status: "sent".to_string(),
+            attempts: 1,
+            ..NotificationDelivery::fixture()
+        }
+    }
+
+    fn start() -> DateTime<Utc> {
+        "2026-03-02T03:00:00Z".parse().unwrap()
+    }
+
+    #[test]
+    fn booked_appointments_get_the_latest_reminder_that_applies() {
+        let booked = appointment("booked");
+        assert_eq!(due_kind(&booked, start(), start() - Duration::hours(25)), None);
+        assert_eq!(due_kind(&booked, start(), start() - Duration::hours(24)), Some(ReminderKind::DayBefore));
+        assert_eq!(due_kind(&booked, start(), start() - Duration::hours(3)), Some(ReminderKind::DayBefore));
+        assert_eq!(due_kind(&booked, start(), start() - Duration::hours(2)), Some(ReminderKind::TwoHoursBefore));
+        assert_eq!(due_kind(&booked, start(), start()), None);
+    }
+
+    #[test]
+    fn follow_ups_are_sent_after_the_delay_within_the_lookback() {
+        let paid = appointment("paid");
+        let send_at = start() + Duration::hours(DEFAULT_FOLLOW_UP_DELAY_HOURS);
+        assert_eq!(due_kind(&paid, start(), send_at - Duration::minutes(1)), None);
+        assert_eq!(due_kind(&paid, start(), send_at), Some(ReminderKind::FollowUp));
+        assert_eq!(due_kind(&paid, start(), send_at + Duration::days(FOLLOW_UP_LOOKBACK_DAYS)), None);
+        assert_eq!(due_kind(&appointment("cancelled"), start(), send_at), None);
+    }
+
+    #[test]
+    fn a_moved_appointment_gets_new_reminders() {
+        let existing = vec![delivery("reminder_h1", Some(start()))];
+        assert!(already_scheduled(&existing, Uuid::nil(), ReminderKind::DayBefore, start()));
+        assert!(!already_scheduled(&existing, Uuid::nil(), ReminderKind::TwoHoursBefore, start()));
+        assert!(!already_scheduled(&existing, Uuid::nil(), ReminderKind::DayBefore, start() + Duration::days(1)));
+
+        let legacy = vec![delivery("reminder_h1", None)];
+        assert!(already_scheduled(&legacy, Uuid::nil(), ReminderKind::DayBefore, start() + Duration::days(1)));
+    }
+
+    #[test]
+    fn retry_delay_doubles_up_to_the_cap() {
+        assert_eq!(retry_delay(1), Duration::minutes(5));
+        assert_eq!(retry_delay(2), Duration::minutes(10));
+        assert_eq!(retry_delay(4), Duration::minutes(40));
+        assert_eq!(retry_delay(100), Duration::minutes(5 << MAX_RETRY_DOUBLINGS));
+        assert_eq!(retry_delay(i32::MAX), Duration::minutes(5 << MAX_RETRY_DOUBLINGS));
+    }
+
+    #[test]
+    fn queued_messages_are_dropped_once_the_appointment_changes() {
+        let offset = FixedOffset::east_opt(7 * 3600).unwrap();
+        let booked = appointment("booked");
+        let start = appointment_start(&booked, offset).unwrap();
+        let reminder = delivery("reminder_h1", Some(start));
+        assert!(still_wanted(&reminder, &booked, offset));
+        assert!(still_wanted(&delivery("reminder_2h", None), &booked, offset));
+
+        assert!(!still_wanted(&reminder, &appointment("cancelled"), offset));
+        assert!(!still_wanted(&reminder, &Appointment { waktu: "11:00".to_string(), ..appointment("booked") }, offset));
+        assert!(!still_wanted(&reminder, &Appointment { deleted_at: Some(start), ..appointment("booked") }, offset));
+
+        let follow_up = delivery("follow_up", Some(start));
+        assert!(still_wanted(&follow_up, &appointment("paid"), offset));
+        assert!(!still_wanted(&follow_up, &booked, offset));
+    }
+}
@@ -0,0 +1,59 @@
+use crate::dtos::report_dto::{CreateReportSnapshotDto, ReportQueryDto};
+use crate::models::report_snapshot::ReportSnapshot;
+use crate::repositories::{appointment_repo, invoice_repo, pasien_repo, report_snapshot_repo};
+use crate::services::appointment_service::clinic_offset;
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde_json::{json, Map, Value};
+
+// Builds and stores the figures for one clinic day
+pub async fn snapshot_daily_report(date: NaiveDate) -> Result<ReportSnapshot, String> {
+    let day = date.format("%Y-%m-%d").to_string();
+
+    let appointments = appointment_repo::get_appointments_between(&day, &day).await?;
+    let mut appointments_by_status = Map::new();
+    for appointment in &appointments {
+        let count = appointments_by_status
+            .get(&appointment.status)
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        appointments_by_status.insert(appointment.status.clone(), json!(count + 1));
+    }
+
+    let invoices = invoice_repo::get_invoices_between(&day, &day).await?;
+    let paid: Vec<_> = invoices.iter().filter(|invoice| invoice.status == "paid").collect();
+    let revenue: f64 = paid.iter().map(|invoice| invoice.total_amount).sum();
+    let products_sold: f64 = paid
+        .iter()
+        .flat_map(|invoice| invoice.items.as_array().cloned().unwrap_or_default())
+        .filter(|item| item.get("type").and_then(Value::as_str) == Some("product"))
+        .filter_map(|item| item.get("quantity").and_then(Value::as_f64))
+        .sum();
+
+    // created_at is UTC, so the clinic day is converted to a UTC range first
+    let offset = clinic_offset();
+    let start = date
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(offset)
+        .single()
+        .ok_or_else(|| format!("Invalid report date {}", day))?
+        .to_utc();
+    let new_pasiens = pasien_repo::get_pasiens_created_between(start, start + Duration::days(1)).await?;
+
+    let snapshot_data = CreateReportSnapshotDto {
+        report_date: day,
+        metrics: json!({
+            "appointments_total": appointments.len(),
+            "appointments_by_status": appointments_by_status,
+            "invoices_total": invoices.len(),
+            "invoices_paid": paid.len(),
+            "revenue": revenue,
+            "products_sold": products_sold,
+            "new_pasiens": new_pasiens.len(),
+        }),
+    };
+    report_snapshot_repo::upsert_report_snapshot(&snapshot_data).await
+}
+
+pub async fn handle_get_report_snapshots(query: ReportQueryDto) -> Result<Vec<ReportSnapshot>, String> {
+    report_snapshot_repo::get_report_snapshots(&query).await
+}
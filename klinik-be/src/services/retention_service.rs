@@ -0,0 +1,77 @@
+use crate::repositories::{
+    appointment_repo, clinic_holiday_repo, clinic_resource_repo, dokter_repo, invoice_repo, pasien_repo, photo_repo,
+    product_repo, recommendation_rule_repo, schedule_exception_repo, skin_analysis_repo, treatment_progress_repo,
+    treatment_repo, waitlist_entry_repo,
+};
+use crate::services::audit_service;
+use crate::storage::photo_storage::PhotoStorage;
+use chrono::{Duration, Utc};
+use serde::Serialize;
+use std::env;
+use uuid::Uuid;
+
+// Rekam medis wajib disimpan minimal 10 tahun, jadi default-nya tidak lebih pendek dari itu
+const DEFAULT_RETENTION_DAYS: i64 = 3650;
+
+// Zero or less would put the cutoff at or after now and purge every soft-deleted row
+fn retention_days() -> i64 {
+    env::var("SOFT_DELETE_RETENTION_DAYS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .filter(|days| *days > 0)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+}
+
+// Each purged row gets a final audit entry so the trail outlives the record itself.
+async fn audit_purged<T: Serialize>(
+    resource: &str,
+    result: Result<Vec<T>, String>,
+    id_of: impl Fn(&T) -> Uuid,
+) -> usize {
+    match result {
+        Ok(rows) => {
+            for row in &rows {
+                audit_service::record(None, resource, id_of(row), "purge", Some(row), None).await;
+            }
+            rows.len()
+        }
+        Err(e) => {
+            println!("Failed to purge {}: {}", resource, e);
+            0
+        }
+    }
+}
+
+// Hard-deletes every record whose soft delete is older than the retention period.
+// Child tables are purged before their parents so foreign keys don't block the parents.
+pub async fn purge_expired_records(storage: &dyn PhotoStorage) -> usize {
+    let cutoff = Utc::now() - Duration::days(retention_days());
+    let mut total = 0;
+
+    // Photo rows go first, their files are removed only once the rows are gone
+    let photos = photo_repo::purge_deleted_photos(cutoff).await;
+    if let Ok(purged) = &photos {
+        for photo in purged {
+            for key in [&photo.storage_key, &photo.thumbnail_key] {
+                if let Err(e) = storage.delete(key).await {
+                    println!("Failed to delete photo file {}: {}", key, e);
+                }
+            }
+        }
+    }
+    total += audit_purged("photo", photos, |r| r.id).await;
+    total += audit_purged("invoice", invoice_repo::purge_deleted_invoices(cutoff).await, |r| r.id).await;
+    total += audit_purged("skin_analysis", skin_analysis_repo::purge_deleted_skin_analyses(cutoff).await, |r| r.id).await;
+    total += audit_purged("treatment_progress", treatment_progress_repo::purge_deleted_treatment_progress(cutoff).await, |r| r.id).await;
+    total += audit_purged("appointment", appointment_repo::purge_deleted_appointments(cutoff).await, |r| r.id).await;
+    total += audit_purged("waitlist_entry", waitlist_entry_repo::purge_deleted_waitlist_entries(cutoff).await, |r| r.id).await;
+    total += audit_purged("pasien", pasien_repo::purge_deleted_pasiens(cutoff).await, |r| r.id).await;
+    total += audit_purged("schedule_exception", schedule_exception_repo::purge_deleted_schedule_exceptions(cutoff).await, |r| r.id).await;
+    total += audit_purged("dokter", dokter_repo::purge_deleted_dokters(cutoff).await, |r| r.id).await;
+    total += audit_purged("treatment", treatment_repo::purge_deleted_treatments(cutoff).await, |r| r.id).await;
+    total += audit_purged("recommendation_rule", recommendation_rule_repo::purge_deleted_recommendation_rules(cutoff).await, |r| r.id).await;
+    total += audit_purged("clinic_holiday", clinic_holiday_repo::purge_deleted_clinic_holidays(cutoff).await, |r| r.id).await;
+    total += audit_purged("clinic_resource", clinic_resource_repo::purge_deleted_clinic_resources(cutoff).await, |r| r.id).await;
+    total += audit_purged("product", product_repo::purge_deleted_products(cutoff).await, |r| r.id).await;
+    total
+}
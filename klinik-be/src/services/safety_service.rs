@@ -0,0 +1,246 @@
+use crate::dtos::safety_dto::{CreateSafetyOverrideDto, SafetyCheckDto};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::pasien::Pasien;
+use crate::models::safety_override::{SafetyFinding, SafetyOverride, SafetySeverity};
+use crate::repositories::{pasien_repo, product_repo, safety_override_repo, treatment_repo};
+use serde_json::Value;
+use uuid::Uuid;
+
+fn mentions(text: Option<&str>, keyword: &str) -> bool {
+    let keyword = keyword.trim().to_lowercase();
+    !keyword.is_empty() && text.is_some_and(|text| text.to_lowercase().contains(&keyword))
+}
+
+// Ingredients are matched against allergies and block the item. Contraindications are
+// matched against allergies, conditions and current medication and only warn.
+fn evaluate_item(
+    pasien: &Pasien,
+    item_type: &str,
+    item_id: Uuid,
+    item_name: &str,
+    ingredients: &[String],
+    contraindications: &[String],
+) -> Vec<SafetyFinding> {
+    let mut findings = Vec::new();
+    for ingredient in ingredients {
+        if mentions(pasien.riwayat_alergi.as_deref(), ingredient) {
+            findings.push(SafetyFinding {
+                severity: SafetySeverity::Blocking,
+                item_type: item_type.to_string(),
+                item_id,
+                item_name: item_name.to_string(),
+                keyword: ingredient.clone(),
+                source: "riwayat_alergi".to_string(),
+                message: format!("{} mengandung {}, pasien memiliki riwayat alergi", item_name, ingredient),
+            });
+        }
+    }
+    let sources = [
+        ("riwayat_alergi", pasien.riwayat_alergi.as_deref()),
+        ("kondisi_medis", pasien.kondisi_medis.as_deref()),
+        ("obat_konsumsi", pasien.obat_konsumsi.as_deref()),
+    ];
+    for contraindication in contraindications {
+        if let Some((source, _)) = sources.iter().find(|(_, text)| mentions(*text, contraindication)) {
+            findings.push(SafetyFinding {
+                severity: SafetySeverity::Warning,
+                item_type: item_type.to_string(),
+                item_id,
+                item_name: item_name.to_string(),
+                keyword: contraindication.clone(),
+                source: source.to_string(),
+                message: format!("{} dikontraindikasikan untuk {} ({})", item_name, contraindication, source),
+            });
+        }
+    }
+    findings
+}
+
+pub async fn check(pasien_id: Uuid, treatment_ids: &[Uuid], product_ids: &[Uuid]) -> Result<Vec<SafetyFinding>, String> {
+    let pasien = pasien_repo::get_pasien_by_id(pasien_id).await?;
+    let mut findings = Vec::new();
+    for id in treatment_ids {
+        let treatment = treatment_repo::get_treatment_by_id(*id).await?;
+        findings.extend(evaluate_item(
+            &pasien,
+            "treatment",
+            treatment.id,
+            &treatment.name,
+            &treatment.ingredients,
+            &treatment.contraindications,
+        ));
+    }
+    for id in product_ids {
+        let product = product_repo::get_product_by_id(*id).await?;
+        findings.extend(evaluate_item(
+            &pasien,
+            "product",
+            product.id,
+            &product.name,
+            &product.ingredients,
+            &product.contraindications,
+        ));
+    }
+    Ok(findings)
+}
+
+// Runs the check for a write that is about to happen. Blocking findings always fail;
+// warnings fail unless acknowledged with a reason, and are returned so the caller can
+// record the override once the record exists.
+pub async fn enforce(
+    pasien_id: Uuid,
+    treatment_ids: &[Uuid],
+    product_ids: &[Uuid],
+    acknowledge_warnings: bool,
+    override_reason: Option<&str>,
+) -> Result<Vec<SafetyFinding>, String> {
+    let findings = check(pasien_id, treatment_ids, product_ids).await?;
+    let describe = |findings: Vec<&SafetyFinding>| {
+        findings.iter().map(|f| f.message.as_str()).collect::<Vec<_>>().join("; ")
+    };
+
+    let blocking: Vec<&SafetyFinding> = findings.iter().filter(|f| f.severity == SafetySeverity::Blocking).collect();
+    if !blocking.is_empty() {
+        return Err(format!("Ditolak karena alergi pasien: {}", describe(blocking)));
+    }
+    if findings.is_empty() {
+        return Ok(findings);
+    }
+    if !acknowledge_warnings {
+        return Err(format!(
+            "Peringatan keamanan perlu dikonfirmasi (acknowledge_warnings): {}",
+            describe(findings.iter().collect())
+        ));
+    }
+    if override_reason.is_none_or(|reason| reason.trim().is_empty()) {
+        return Err("safety_override_reason wajib diisi untuk mengonfirmasi peringatan".to_string());
+    }
+    Ok(findings)
+}
+
+// Like an audit entry, a failed write is logged because the record it refers to is already saved
+pub async fn record_override(
+    actor: &AuthenticatedUser,
+    pasien_id: Uuid,
+    resource: &str,
+    record_id: Uuid,
+    findings: Vec<SafetyFinding>,
+    reason: Option<&str>,
+) {
+    if findings.is_empty() {
+        return;
+    }
+    let override_data = CreateSafetyOverrideDto {
+        pasien_id,
+        resource: resource.to_string(),
+        record_id,
+        findings,
+        reason: reason.unwrap_or_default().trim().to_string(),
+        overridden_by: actor.id.clone(),
+        overridden_by_position: actor.position.clone(),
+    };
+    if let Err(e) = safety_override_repo::create_safety_override(&override_data).await {
+        println!("Failed to record safety override for {} {}: {}", resource, record_id, e);
+    }
+}
+
+// Reads a JSON array of UUID strings, skipping anything that isn't one
+pub fn uuids_from_value(value: &Value) -> Vec<Uuid> {
+    value
+        .as_array()
+        .map(|ids| {
+            ids.iter()
+                .filter_map(|id| id.as_str().and_then(|id| Uuid::parse_str(id).ok()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Product IDs from invoice items: [{ type: "product", item_id, ... }]
+pub fn product_ids_from_invoice_items(items: &Value) -> Vec<Uuid> {
+    items
+        .as_array()
+        .map(|items| {
+            items
+                .iter()
+                .filter(|item| item.get("type").and_then(Value::as_str) == Some("product"))
+                .filter_map(|item| item.get("item_id").and_then(Value::as_str))
+                .filter_map(|id| Uuid::parse_str(id).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub async fn handle_safety_check(check_data: SafetyCheckDto) -> Result<Vec<SafetyFinding>, String> {
+    check(
+        check_data.pasien_id,
+        &check_data.treatment_ids.unwrap_or_default(),
+        &check_data.product_ids.unwrap_or_default(),
+    )
+    .await
+}
+
+pub async fn handle_get_safety_overrides(pasien_id: Uuid) -> Result<Vec<SafetyOverride>, String> {
+    safety_override_repo::get_safety_overrides_by_pasien(pasien_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pasien(riwayat_alergi: Option<&str>, kondisi_medis: Option<&str>, obat_konsumsi: Option<&str>) -> Pasien {
+        Pasien {
+            riwayat_alergi: riwayat_alergi.map(str::to_string),
+            kondisi_medis: kondisi_medis.map(str::to_string),
+            obat_konsumsi: obat_konsumsi.map(str::to_string),
+            ..Pasien::fixture()
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn evaluate(pasien: &Pasien, ingredients: &[&str], contraindications: &[&str]) -> Vec<SafetyFinding> {
+        evaluate_item(pasien, "product", Uuid::nil(), "Serum", &strings(ingredients), &strings(contraindications))
+    }
+
+    #[test]
+    fn allergic_ingredients_block_case_insensitively() {
+        let pasien = pasien(Some("Alergi RETINOL dan parfum"), None, None);
+        let findings = evaluate(&pasien, &["retinol", "niacinamide"], &[]);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].severity, SafetySeverity::Blocking);
+        assert_eq!(findings[0].keyword, "retinol");
+        assert_eq!(findings[0].source, "riwayat_alergi");
+    }
+
+    #[test]
+    fn contraindications_warn_once_naming_the_first_source() {
+        let pasien = pasien(Some("hamil"), Some("sedang hamil"), Some("isotretinoin"));
+        let findings = evaluate(&pasien, &[], &["Hamil", "isotretinoin", "diabetes"]);
+        let found: Vec<(&str, &str)> = findings.iter().map(|f| (f.keyword.as_str(), f.source.as_str())).collect();
+        assert_eq!(found, vec![("Hamil", "riwayat_alergi"), ("isotretinoin", "obat_konsumsi")]);
+        assert!(findings.iter().all(|f| f.severity == SafetySeverity::Warning));
+    }
+
+    #[test]
+    fn blank_keywords_and_missing_history_never_match() {
+        assert!(evaluate(&pasien(Some("retinol"), None, None), &["", "  "], &[" "]).is_empty());
+        assert!(evaluate(&pasien(None, None, None), &["retinol"], &["hamil"]).is_empty());
+    }
+
+    #[test]
+    fn reads_uuids_and_invoice_product_ids() {
+        let id = Uuid::new_v4();
+        assert_eq!(uuids_from_value(&json!([id, "bukan-uuid", 3])), vec![id]);
+        assert!(uuids_from_value(&Value::Null).is_empty());
+        let items = json!([
+            { "type": "product", "item_id": id },
+            { "type": "treatment", "item_id": Uuid::new_v4() },
+            { "type": "product", "item_id": "bukan-uuid" },
+        ]);
+        assert_eq!(product_ids_from_invoice_items(&items), vec![id]);
+    }
+}
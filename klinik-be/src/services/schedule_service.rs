@@ -0,0 +1,311 @@
+use crate::dtos::schedule_dto::{BlockedDateResultDto, CreateClinicHolidayDto, CreateScheduleExceptionDto, ScheduleQueryDto};
+use crate::events::event_bus::{self, Topic};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::clinic_holiday::ClinicHoliday;
+use crate::models::dokter::DailySchedule;
+use crate::models::schedule_exception::ScheduleException;
+use crate::repositories::{appointment_repo, clinic_holiday_repo, dokter_repo, schedule_exception_repo, treatment_repo};
+use crate::services::{audit_service, clinic_resource_service, safety_service};
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike, Weekday};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+const EXCEPTION_RESOURCE: &str = "schedule_exception";
+const HOLIDAY_RESOURCE: &str = "clinic_holiday";
+
+fn parse_date(tanggal: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(tanggal, "%Y-%m-%d").map_err(|_| "tanggal harus berformat YYYY-MM-DD".to_string())
+}
+
+pub fn parse_time(waktu: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(waktu, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(waktu, "%H:%M:%S"))
+        .ok()
+}
+
+fn minutes_of_day(time: NaiveTime) -> i64 {
+    (time.num_seconds_from_midnight() / 60) as i64
+}
+
+// Whether [start, start + minutes) overlaps the exception's time range; a whole-day
+// exception overlaps everything. Zero minutes checks the single moment `start`.
+fn overlaps(exception: &ScheduleException, start: NaiveTime, minutes: i64) -> bool {
+    let start = minutes_of_day(start);
+    let end = start + minutes.max(1);
+    match exception_hours(exception) {
+        Some((from, to)) => start < minutes_of_day(to) && minutes_of_day(from) < end,
+        None => true,
+    }
+}
+
+fn hari(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Senin",
+        Weekday::Tue => "Selasa",
+        Weekday::Wed => "Rabu",
+        Weekday::Thu => "Kamis",
+        Weekday::Fri => "Jumat",
+        Weekday::Sat => "Sabtu",
+        Weekday::Sun => "Minggu",
+    }
+}
+
+// The exception's time range, or None for the whole day
+fn exception_hours(exception: &ScheduleException) -> Option<(NaiveTime, NaiveTime)> {
+    let start = exception.start_time.as_deref().and_then(parse_time)?;
+    let end = exception.end_time.as_deref().and_then(parse_time)?;
+    Some((start, end))
+}
+
+// Everything that decides when one doctor works on one date
+pub struct DoctorDay {
+    tanggal: String,
+    date: NaiveDate,
+    holiday: Option<String>,
+    exceptions: Vec<ScheduleException>,
+    jadwal: Vec<DailySchedule>,
+}
+
+impl DoctorDay {
+    pub async fn load(dokter_id: Uuid, tanggal: &str) -> Result<DoctorDay, String> {
+        let date = parse_date(tanggal)?;
+        let holiday = clinic_holiday_repo::get_clinic_holidays_on_date(tanggal)
+            .await?
+            .into_iter()
+            .next()
+            .map(|holiday| holiday.nama);
+        let exceptions = schedule_exception_repo::get_schedule_exceptions_on_date(dokter_id, tanggal).await?;
+        let dokter = dokter_repo::get_dokter_by_id(dokter_id).await?;
+        let jadwal: Vec<DailySchedule> = serde_json::from_value(dokter.jadwal).unwrap_or_default();
+        Ok(DoctorDay { tanggal: tanggal.to_string(), date, holiday, exceptions, jadwal })
+    }
+
+    // Working hours on this date: the weekly `jadwal` for the weekday plus extra shifts,
+    // before leave is taken out
+    pub fn hours(&self) -> Vec<(NaiveTime, NaiveTime)> {
+        if self.holiday.is_some() {
+            return Vec::new();
+        }
+        let day = hari(self.date.weekday());
+        let mut hours: Vec<(NaiveTime, NaiveTime)> = self
+            .jadwal
+            .iter()
+            .filter(|schedule| schedule.day == day)
+            .filter_map(|schedule| Some((parse_time(&schedule.start_time)?, parse_time(&schedule.end_time)?)))
+            .collect();
+        hours.extend(self.exceptions.iter().filter(|e| e.kind == "extra_shift").filter_map(exception_hours));
+        hours
+    }
+
+    // Fails unless the doctor works at `time`: not a clinic holiday, not on leave at any point
+    // of the `minutes` from `time`, and starting inside either their weekly `jadwal` for that
+    // day or an extra shift. A doctor whose `jadwal` is empty has no weekly schedule configured
+    // and is only checked against holidays and leave.
+    pub fn check(&self, time: NaiveTime, minutes: i64) -> Result<(), String> {
+        let waktu = time.format("%H:%M");
+        if let Some(nama) = &self.holiday {
+            return Err(format!("Klinik libur pada {} ({})", self.tanggal, nama));
+        }
+        if self.exceptions.iter().any(|e| e.kind == "leave" && overlaps(e, time, minutes)) {
+            return Err(format!("Dokter cuti pada {} {}", self.tanggal, waktu));
+        }
+        if self.jadwal.is_empty() || self.hours().iter().any(|(start, end)| time >= *start && time < *end) {
+            Ok(())
+        } else {
+            Err(format!("Dokter tidak praktik pada {} {} pukul {}", hari(self.date.weekday()), self.tanggal, waktu))
+        }
+    }
+}
+
+// `minutes` is how long the appointment lasts, so leave starting part-way through counts
+pub async fn ensure_dokter_available(dokter_id: Uuid, tanggal: &str, waktu: &str, minutes: i64) -> Result<(), String> {
+    let time = parse_time(waktu).ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    DoctorDay::load(dokter_id, tanggal).await?.check(time, minutes)
+}
+
+// Flags the booked appointments that a newly blocked date (or time range) takes away,
+// for one doctor or, for a holiday, for every doctor. An appointment is affected when any
+// part of it falls inside the range, not only its start.
+async fn flag_blocked_appointments(
+    tanggal: &str,
+    dokter_id: Option<Uuid>,
+    blocked: Option<&ScheduleException>,
+    reason: &str,
+    actor: &AuthenticatedUser,
+) -> Result<Vec<Uuid>, String> {
+    let appointments = appointment_repo::get_appointments_between(tanggal, tanggal).await?;
+    let treatments: HashMap<Uuid, _> = treatment_repo::get_all_treatments(true)
+        .await?
+        .into_iter()
+        .map(|treatment| (treatment.id, treatment))
+        .collect();
+    let mut flagged = Vec::new();
+    for appointment in appointments {
+        if dokter_id.is_some_and(|id| id != appointment.dokter_id) {
+            continue;
+        }
+        if let Some(exception) = blocked {
+            let minutes = clinic_resource_service::duration_minutes(
+                &safety_service::uuids_from_value(&appointment.treatment_ids),
+                &treatments,
+            );
+            if parse_time(&appointment.waktu).is_none_or(|time| !overlaps(exception, time, minutes)) {
+                continue;
+            }
+        }
+        if let Some(updated) = appointment_repo::flag_appointment_for_reschedule(appointment.id, reason).await? {
+            audit_service::record(Some(actor), "appointment", updated.id, "update", Some(&appointment), Some(&updated)).await;
+            event_bus::publish(Topic::Appointments, "appointment.needs_reschedule", updated.id, &updated);
+            flagged.push(updated.id);
+        }
+    }
+    Ok(flagged)
+}
+
+fn validate_exception(exception_data: &CreateScheduleExceptionDto) -> Result<(), String> {
+    parse_date(&exception_data.tanggal)?;
+    if exception_data.kind != "leave" && exception_data.kind != "extra_shift" {
+        return Err("kind harus leave atau extra_shift".to_string());
+    }
+    let start = exception_data.start_time.as_deref().map(parse_time);
+    let end = exception_data.end_time.as_deref().map(parse_time);
+    match (start, end) {
+        (None, None) if exception_data.kind == "leave" => Ok(()),
+        (None, None) => Err("extra_shift wajib memiliki start_time dan end_time".to_string()),
+        (Some(Some(start)), Some(Some(end))) if start < end => Ok(()),
+        (Some(Some(_)), Some(Some(_))) => Err("start_time harus sebelum end_time".to_string()),
+        _ => Err("start_time dan end_time harus diisi berdua dengan format HH:MM".to_string()),
+    }
+}
+
+pub async fn handle_get_schedule_exceptions(dokter_id: Uuid, query: ScheduleQueryDto) -> Result<Vec<ScheduleException>, String> {
+    schedule_exception_repo::get_schedule_exceptions_by_dokter(dokter_id, &query).await
+}
+
+pub async fn handle_create_schedule_exception(
+    dokter_id: Uuid,
+    mut exception_data: CreateScheduleExceptionDto,
+    actor: &AuthenticatedUser,
+) -> Result<BlockedDateResultDto<ScheduleException>, String> {
+    validate_exception(&exception_data)?;
+    let dokter = dokter_repo::get_dokter_by_id(dokter_id).await?;
+    if dokter.deleted_at.is_some() {
+        return Err("Dokter not found".to_string());
+    }
+    exception_data.dokter_id = dokter_id;
+    exception_data.created_by = actor.id.clone();
+    let exception = schedule_exception_repo::create_schedule_exception(&exception_data).await?;
+    audit_service::record(Some(actor), EXCEPTION_RESOURCE, exception.id, "create", None, Some(&exception)).await;
+
+    let flagged_appointment_ids = if exception.kind == "leave" {
+        let reason = format!("{} cuti pada {}", dokter.nama, exception.tanggal);
+        flag_blocked_appointments(&exception.tanggal, Some(dokter_id), Some(&exception), &reason, actor).await?
+    } else {
+        Vec::new()
+    };
+    Ok(BlockedDateResultDto { record: exception, flagged_appointment_ids })
+}
+
+pub async fn handle_delete_schedule_exception(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = schedule_exception_repo::get_schedule_exception_by_id(id).await?;
+    let deleted = schedule_exception_repo::delete_schedule_exception(id, &actor.id).await?;
+    audit_service::record(Some(actor), EXCEPTION_RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_schedule_exception(id: Uuid, actor: &AuthenticatedUser) -> Result<ScheduleException, String> {
+    let before = schedule_exception_repo::get_schedule_exception_by_id(id).await?;
+    let exception = schedule_exception_repo::restore_schedule_exception(id).await?;
+    audit_service::record(Some(actor), EXCEPTION_RESOURCE, id, "restore", Some(&before), Some(&exception)).await;
+    Ok(exception)
+}
+
+pub async fn handle_get_clinic_holidays(query: ScheduleQueryDto) -> Result<Vec<ClinicHoliday>, String> {
+    clinic_holiday_repo::get_clinic_holidays(&query).await
+}
+
+pub async fn handle_create_clinic_holiday(
+    mut holiday_data: CreateClinicHolidayDto,
+    actor: &AuthenticatedUser,
+) -> Result<BlockedDateResultDto<ClinicHoliday>, String> {
+    parse_date(&holiday_data.tanggal)?;
+    if holiday_data.nama.trim().is_empty() {
+        return Err("nama wajib diisi".to_string());
+    }
+    if !clinic_holiday_repo::get_clinic_holidays_on_date(&holiday_data.tanggal).await?.is_empty() {
+        return Err(format!("Tanggal {} sudah tercatat sebagai hari libur", holiday_data.tanggal));
+    }
+    holiday_data.created_by = actor.id.clone();
+    let holiday = clinic_holiday_repo::create_clinic_holiday(&holiday_data).await?;
+    audit_service::record(Some(actor), HOLIDAY_RESOURCE, holiday.id, "create", None, Some(&holiday)).await;
+
+    let reason = format!("Klinik libur pada {} ({})", holiday.tanggal, holiday.nama);
+    let flagged_appointment_ids = flag_blocked_appointments(&holiday.tanggal, None, None, &reason, actor).await?;
+    Ok(BlockedDateResultDto { record: holiday, flagged_appointment_ids })
+}
+
+pub async fn handle_delete_clinic_holiday(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = clinic_holiday_repo::get_clinic_holiday_by_id(id).await?;
+    let deleted = clinic_holiday_repo::delete_clinic_holiday(id, &actor.id).await?;
+    audit_service::record(Some(actor), HOLIDAY_RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_clinic_holiday(id: Uuid, actor: &AuthenticatedUser) -> Result<ClinicHoliday, String> {
+    let before = clinic_holiday_repo::get_clinic_holiday_by_id(id).await?;
+    let holiday = clinic_holiday_repo::restore_clinic_holiday(id).await?;
+    audit_service::record(Some(actor), HOLIDAY_RESOURCE, id, "restore", Some(&before), Some(&holiday)).await;
+    Ok(holiday)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leave(start_time: Option<&str>, end_time: Option<&str>) -> ScheduleException {
+        ScheduleException {
+            start_time: start_time.map(str::to_string),
+            end_time: end_time.map(str::to_string),
+            ..ScheduleException::fixture()
+        }
+    }
+
+    fn day_with(exceptions: Vec<ScheduleException>) -> DoctorDay {
+        DoctorDay {
+            tanggal: "2026-03-02".to_string(),
+            date: parse_date("2026-03-02").unwrap(),
+            holiday: None,
+            exceptions,
+            jadwal: Vec::new(),
+        }
+    }
+
+    fn at(waktu: &str) -> NaiveTime {
+        parse_time(waktu).unwrap()
+    }
+
+    #[test]
+    fn leave_blocks_appointments_running_into_it() {
+        let day = day_with(vec![leave(Some("10:00"), Some("12:00"))]);
+        assert!(day.check(at("09:30"), 90).is_err());
+        assert!(day.check(at("11:00"), 30).is_err());
+        assert!(day.check(at("08:00"), 90).is_ok());
+        assert!(day.check(at("08:30"), 90).is_ok());
+        assert!(day.check(at("12:00"), 60).is_ok());
+    }
+
+    #[test]
+    fn whole_day_leave_blocks_everything() {
+        let day = day_with(vec![leave(None, None)]);
+        assert!(day.check(at("07:00"), 15).is_err());
+        assert!(day.check(at("20:00"), 0).is_err());
+    }
+
+    #[test]
+    fn zero_minutes_checks_only_the_start() {
+        let exception = leave(Some("10:00"), Some("12:00"));
+        assert!(!overlaps(&exception, at("09:59"), 0));
+        assert!(overlaps(&exception, at("10:00"), 0));
+        assert!(!overlaps(&exception, at("12:00"), 0));
+    }
+}
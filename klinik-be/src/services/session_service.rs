@@ -0,0 +1,166 @@
+use crate::auth::auth_provider::AuthProvider;
+use crate::dtos::auth_dto::{CreateAuthSessionDto, LoginResultDto, SessionDto, SessionTokens, TokenPairDto};
+use crate::dtos::user_dto::LoginUserDto;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::repositories::{auth_session_repo, user_repo};
+use crate::services::{audit_service, totp_service};
+use chrono::{Duration, Utc};
+use sha2::{Digest, Sha256};
+use std::env;
+use uuid::Uuid;
+
+const RESOURCE: &str = "auth_session";
+const DEFAULT_SESSION_DAYS: i64 = 30;
+const MAX_USER_AGENT_LENGTH: usize = 255;
+
+// Where a login came from, shown in the session list
+pub struct ClientInfo {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+// A session lives this long after its last refresh
+fn session_lifetime() -> Duration {
+    Duration::days(
+        env::var("AUTH_SESSION_DAYS")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|days| *days > 0)
+            .unwrap_or(DEFAULT_SESSION_DAYS),
+    )
+}
+
+// Refresh tokens are random and long, so a plain digest is enough to look them up
+fn refresh_token_hash(refresh_token: &str) -> String {
+    hex::encode(Sha256::digest(refresh_token.as_bytes()))
+}
+
+fn token_pair(tokens: SessionTokens) -> TokenPairDto {
+    TokenPairDto {
+        access_token: tokens.access_token,
+        refresh_token: tokens.refresh_token,
+        token_type: "bearer".to_string(),
+        expires_in: tokens.expires_in,
+        session_id: tokens.session_id,
+    }
+}
+
+// Records the session the provider just started and hands out its tokens
+pub async fn create_session(tokens: SessionTokens, client: ClientInfo) -> Result<TokenPairDto, String> {
+    auth_session_repo::create_session(&CreateAuthSessionDto {
+        id: tokens.session_id,
+        user_id: tokens.user_id,
+        refresh_token_hash: refresh_token_hash(&tokens.refresh_token),
+        user_agent: client
+            .user_agent
+            .map(|agent| agent.chars().take(MAX_USER_AGENT_LENGTH).collect()),
+        ip_address: client.ip_address,
+        expires_at: Utc::now() + session_lifetime(),
+    })
+    .await?;
+    Ok(token_pair(tokens))
+}
+
+fn deactivated() -> String {
+    "Akun Anda dinonaktifkan. Hubungi admin.".to_string()
+}
+
+// With a provider that supports 2FA the password is checked first and accounts with TOTP get
+// a challenge instead of tokens; totp_service finishes those logins.
+pub async fn handle_login(auth: &dyn AuthProvider, login_data: LoginUserDto, client: ClientInfo) -> Result<LoginResultDto, String> {
+    if auth.supports_two_factor() {
+        let user = user_repo::get_user_by_id(&auth.check_password(&login_data).await?.to_string()).await?;
+        if user.deactivated_at.is_some() {
+            return Err(deactivated());
+        }
+        if let Some(challenge) = totp_service::login_challenge(&user).await? {
+            return Ok(LoginResultDto::MfaRequired(challenge));
+        }
+        let tokens = auth.start_session(user.id).await?;
+        return Ok(LoginResultDto::Tokens(create_session(tokens, client).await?));
+    }
+
+    let tokens = auth.login(&login_data).await?;
+    // Checked here rather than in each provider, so Supabase accounts are covered as well
+    if user_repo::get_user_by_id(&tokens.user_id.to_string()).await?.deactivated_at.is_some() {
+        if let Err(e) = auth.logout(&tokens.access_token).await {
+            println!("Provider logout failed for deactivated user {}: {}", tokens.user_id, e);
+        }
+        return Err(deactivated());
+    }
+    Ok(LoginResultDto::Tokens(create_session(tokens, client).await?))
+}
+
+// Trades a refresh token for a new pair. Each refresh token works once; presenting one that
+// was already rotated means it leaked, so the whole session is revoked.
+pub async fn handle_refresh(auth: &dyn AuthProvider, refresh_token: &str) -> Result<TokenPairDto, String> {
+    let old_hash = refresh_token_hash(refresh_token.trim());
+    let Some(session) = auth_session_repo::get_session_by_refresh_hash(&old_hash).await? else {
+        if let Some(session) = auth_session_repo::get_session_by_previous_refresh_hash(&old_hash).await?
+            && let Some(revoked) = auth_session_repo::revoke_session(session.id).await?
+        {
+            audit_service::record(None, RESOURCE, revoked.id, "revoke", Some(&session), Some(&revoked)).await;
+        }
+        return Err("Refresh token tidak valid".to_string());
+    };
+    if session.revoked_at.is_some() || session.expires_at <= Utc::now() {
+        return Err("Sesi sudah berakhir, silakan login kembali".to_string());
+    }
+
+    let tokens = auth.refresh(session.id, session.user_id, refresh_token.trim()).await?;
+    let rotated = auth_session_repo::rotate_refresh_token(
+        session.id,
+        &old_hash,
+        &refresh_token_hash(&tokens.refresh_token),
+        Utc::now() + session_lifetime(),
+    )
+    .await?;
+    if !rotated {
+        return Err("Refresh token tidak valid".to_string());
+    }
+    Ok(token_pair(tokens))
+}
+
+// Ends the caller's current session. The provider is told as well, but our row is what
+// AuthMiddleware checks, so a provider failure doesn't keep the session alive.
+pub async fn handle_logout(auth: &dyn AuthProvider, actor: &AuthenticatedUser, access_token: &str) -> Result<(), String> {
+    let session_id = actor.session_id.ok_or_else(|| "Tidak ada sesi aktif".to_string())?;
+    let before = auth_session_repo::get_session_by_id(session_id).await?;
+    if let Some(revoked) = auth_session_repo::revoke_session(session_id).await? {
+        audit_service::record(Some(actor), RESOURCE, session_id, "revoke", before.as_ref(), Some(&revoked)).await;
+    }
+    if let Err(e) = auth.logout(access_token).await {
+        println!("Provider logout failed for session {}: {}", session_id, e);
+    }
+    Ok(())
+}
+
+pub async fn handle_get_sessions(user_id: Uuid, actor: &AuthenticatedUser) -> Result<Vec<SessionDto>, String> {
+    Ok(auth_session_repo::get_active_sessions_by_user(user_id)
+        .await?
+        .into_iter()
+        .map(|session| SessionDto { current: Some(session.id) == actor.session_id, session })
+        .collect())
+}
+
+// Users may revoke their own sessions, admins anyone's. Someone else's session looks the
+// same as a missing one.
+pub async fn handle_revoke_session(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let not_found = || "Sesi tidak ditemukan".to_string();
+    let session = auth_session_repo::get_session_by_id(id).await?.ok_or_else(not_found)?;
+    if session.user_id.to_string() != actor.id && !actor.is_admin() {
+        return Err(not_found());
+    }
+    let revoked = auth_session_repo::revoke_session(id).await?.ok_or_else(not_found)?;
+    audit_service::record(Some(actor), RESOURCE, id, "revoke", Some(&session), Some(&revoked)).await;
+    Ok(())
+}
+
+// Logs a user out everywhere, e.g. after a stolen device
+pub async fn handle_revoke_user_sessions(user_id: Uuid, actor: &AuthenticatedUser) -> Result<usize, String> {
+    let revoked = auth_session_repo::revoke_sessions_by_user(user_id).await?;
+    for session in &revoked {
+        audit_service::record(Some(actor), RESOURCE, session.id, "revoke", None, Some(session)).await;
+    }
+    Ok(revoked.len())
+}
@@ -1,20 +1,206 @@
-use crate::dtos::skin_analysis_dto::{CreateSkinAnalysisDto, UpdateSkinAnalysisDto};
-use crate::models::skin_analysis::SkinAnalysis;
+use crate::dtos::skin_analysis_dto::{CreateSkinAnalysisDto, SkinMetricPointDto, SkinMetricTrendDto, UpdateSkinAnalysisDto};
+use crate::models::skin_analysis::{DeviceMeasurement, SkinAnalysis, SkinScores};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::skin_analysis_repo;
+use crate::services::audit_service;
 use uuid::Uuid;
 
-pub async fn handle_get_all_skin_analyses() -> Result<Vec<SkinAnalysis>, String> {
-    skin_analysis_repo::get_all_skin_analyses().await
+const RESOURCE: &str = "skin_analysis";
+
+// (metric, higher_is_better) for the scored metrics, in display order.
+// Sebum has no better direction: both too oily and too dry are findings.
+const SCORE_METRICS: [(&str, Option<bool>); 6] = [
+    ("acne", Some(false)),
+    ("pigmentation", Some(false)),
+    ("wrinkles", Some(false)),
+    ("pores", Some(false)),
+    ("hydration", Some(true)),
+    ("sebum", None),
+];
+
+pub fn score_value(scores: &SkinScores, metric: &str) -> Option<f64> {
+    match metric {
+        "acne" => scores.acne,
+        "pigmentation" => scores.pigmentation,
+        "wrinkles" => scores.wrinkles,
+        "pores" => scores.pores,
+        "hydration" => scores.hydration,
+        "sebum" => scores.sebum,
+        _ => None,
+    }
+}
+
+pub fn is_score_metric(metric: &str) -> bool {
+    SCORE_METRICS.iter().any(|(name, _)| *name == metric)
+}
+
+fn validate_structured_fields(
+    fitzpatrick_type: Option<i16>,
+    scores: Option<&SkinScores>,
+    device_readings: Option<&[DeviceMeasurement]>,
+) -> Result<(), String> {
+    if let Some(fitzpatrick) = fitzpatrick_type
+        && !(1..=6).contains(&fitzpatrick)
+    {
+        return Err("fitzpatrick_type harus antara 1 dan 6".to_string());
+    }
+    if let Some(scores) = scores {
+        for (metric, _) in SCORE_METRICS {
+            if let Some(value) = score_value(scores, metric)
+                && !(0.0..=100.0).contains(&value)
+            {
+                return Err(format!("Skor {} harus antara 0 dan 100", metric));
+            }
+        }
+    }
+    for reading in device_readings.unwrap_or_default() {
+        if reading.metric.trim().is_empty() || reading.unit.trim().is_empty() {
+            return Err("Setiap device reading harus memiliki metric dan unit".to_string());
+        }
+        if !reading.value.is_finite() {
+            return Err(format!("Nilai device reading {} tidak valid", reading.metric));
+        }
+    }
+    Ok(())
+}
+
+pub async fn handle_get_all_skin_analyses(include_deleted: bool) -> Result<Vec<SkinAnalysis>, String> {
+    skin_analysis_repo::get_all_skin_analyses(include_deleted).await
+}
+
+pub async fn handle_create_skin_analysis(analysis_data: CreateSkinAnalysisDto, actor: &AuthenticatedUser) -> Result<SkinAnalysis, String> {
+    validate_structured_fields(
+        analysis_data.fitzpatrick_type,
+        analysis_data.scores.as_ref(),
+        analysis_data.device_readings.as_deref(),
+    )?;
+    let analysis = skin_analysis_repo::create_skin_analysis(&analysis_data).await?;
+    audit_service::record(Some(actor), RESOURCE, analysis.id, "create", None, Some(&analysis)).await;
+    Ok(analysis)
+}
+
+// An update carries only the metrics it changes; the others keep their stored value
+fn merge_scores(stored: Option<&SkinScores>, incoming: SkinScores) -> SkinScores {
+    let stored = stored.cloned().unwrap_or_default();
+    SkinScores {
+        acne: incoming.acne.or(stored.acne),
+        pigmentation: incoming.pigmentation.or(stored.pigmentation),
+        wrinkles: incoming.wrinkles.or(stored.wrinkles),
+        pores: incoming.pores.or(stored.pores),
+        hydration: incoming.hydration.or(stored.hydration),
+        sebum: incoming.sebum.or(stored.sebum),
+    }
 }
 
-pub async fn handle_create_skin_analysis(analysis_data: CreateSkinAnalysisDto) -> Result<SkinAnalysis, String> {
-    skin_analysis_repo::create_skin_analysis(&analysis_data).await
+pub async fn handle_update_skin_analysis(id: Uuid, mut analysis_data: UpdateSkinAnalysisDto, actor: &AuthenticatedUser) -> Result<SkinAnalysis, String> {
+    let before = skin_analysis_repo::get_skin_analysis_by_id(id).await?;
+    if let Some(scores) = analysis_data.scores.take() {
+        analysis_data.scores = Some(merge_scores(before.scores.as_ref(), scores));
+    }
+    validate_structured_fields(
+        analysis_data.fitzpatrick_type,
+        analysis_data.scores.as_ref(),
+        analysis_data.device_readings.as_deref(),
+    )?;
+    let analysis = skin_analysis_repo::update_skin_analysis(id, &analysis_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&analysis)).await;
+    Ok(analysis)
 }
 
-pub async fn handle_update_skin_analysis(id: Uuid, analysis_data: UpdateSkinAnalysisDto) -> Result<SkinAnalysis, String> {
-    skin_analysis_repo::update_skin_analysis(id, &analysis_data).await
+pub async fn handle_delete_skin_analysis(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = skin_analysis_repo::get_skin_analysis_by_id(id).await?;
+    let deleted = skin_analysis_repo::delete_skin_analysis(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
 }
 
-pub async fn handle_delete_skin_analysis(id: Uuid) -> Result<(), String> {
-    skin_analysis_repo::delete_skin_analysis(id).await
-}
\ No newline at end of file
+pub async fn handle_restore_skin_analysis(id: Uuid, actor: &AuthenticatedUser) -> Result<SkinAnalysis, String> {
+    let before = skin_analysis_repo::get_skin_analysis_by_id(id).await?;
+    let analysis = skin_analysis_repo::restore_skin_analysis(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&analysis)).await;
+    Ok(analysis)
+}
+
+fn build_trend(metric: String, unit: Option<String>, higher_is_better: Option<bool>, points: Vec<SkinMetricPointDto>) -> SkinMetricTrendDto {
+    let change = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if points.len() > 1 => Some(last.value - first.value),
+        _ => None,
+    };
+    SkinMetricTrendDto { metric, unit, higher_is_better, points, change }
+}
+
+// Trend of every scored metric, followed by every device metric seen in the patient's analyses
+pub async fn handle_get_skin_metric_trends(pasien_id: Uuid) -> Result<Vec<SkinMetricTrendDto>, String> {
+    let mut analyses = skin_analysis_repo::get_skin_analyses_by_pasien(pasien_id).await?;
+    analyses.sort_by(|a, b| {
+        a.tanggal_analisis
+            .cmp(&b.tanggal_analisis)
+            .then_with(|| a.created_at.cmp(&b.created_at))
+    });
+
+    let mut trends = Vec::new();
+    for (metric, higher_is_better) in SCORE_METRICS {
+        let points = analyses
+            .iter()
+            .filter_map(|analysis| {
+                let value = analysis.scores.as_ref().and_then(|scores| score_value(scores, metric))?;
+                Some(SkinMetricPointDto {
+                    skin_analysis_id: analysis.id,
+                    tanggal_analisis: analysis.tanggal_analisis.clone(),
+                    value,
+                })
+            })
+            .collect();
+        trends.push(build_trend(metric.to_string(), None, higher_is_better, points));
+    }
+
+    // Device metrics are keyed by metric and unit, so readings in different units never mix
+    let mut device_metrics: Vec<(String, String)> = Vec::new();
+    for reading in analyses.iter().flat_map(|analysis| &analysis.device_readings) {
+        let key = (reading.metric.clone(), reading.unit.clone());
+        if !device_metrics.contains(&key) {
+            device_metrics.push(key);
+        }
+    }
+    for (metric, unit) in device_metrics {
+        let points = analyses
+            .iter()
+            .flat_map(|analysis| {
+                analysis
+                    .device_readings
+                    .iter()
+                    .filter(|reading| reading.metric == metric && reading.unit == unit)
+                    .map(|reading| SkinMetricPointDto {
+                        skin_analysis_id: analysis.id,
+                        tanggal_analisis: analysis.tanggal_analisis.clone(),
+                        value: reading.value,
+                    })
+            })
+            .collect();
+        trends.push(build_trend(metric, Some(unit), None, points));
+    }
+
+    Ok(trends)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging_keeps_metrics_the_update_leaves_out() {
+        let stored = SkinScores { acne: Some(40.0), hydration: Some(55.0), ..Default::default() };
+        let merged = merge_scores(Some(&stored), SkinScores { acne: Some(30.0), pores: Some(20.0), ..Default::default() });
+        assert_eq!(merged.acne, Some(30.0));
+        assert_eq!(merged.hydration, Some(55.0));
+        assert_eq!(merged.pores, Some(20.0));
+        assert_eq!(merged.sebum, None);
+    }
+
+    #[test]
+    fn merging_without_stored_scores_takes_the_update() {
+        let merged = merge_scores(None, SkinScores { wrinkles: Some(10.0), ..Default::default() });
+        assert_eq!(merged.wrinkles, Some(10.0));
+        assert_eq!(merged.acne, None);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::dtos::timeline_dto::{TimelineEvent, TimelineEventKind, TimelineLinks, TimelineQueryDto};
+use crate::repositories::{appointment_repo, invoice_repo, pasien_repo, skin_analysis_repo, treatment_progress_repo};
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    // Stored dates may carry a time part, only the date is relevant here
+    let date_part = value.trim().split(['T', ' ']).next().unwrap_or_default();
+    NaiveDate::parse_from_str(date_part, "%Y-%m-%d").map_err(|_| format!("Invalid date: {}", value))
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    let value = value.trim();
+    NaiveTime::parse_from_str(value, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(value, "%H:%M"))
+        .ok()
+}
+
+// Keeps the events dated inside [from, to] and orders them by date, then time, then
+// created_at. Dates and times are compared parsed, so "2026-03-02T08:00:00" and
+// "2026-03-02", or "9:00" and "10:00", sort correctly against each other.
+fn arrange(
+    mut events: Vec<(TimelineEvent, DateTime<Utc>)>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Vec<TimelineEvent> {
+    // Records with an unreadable date can't be placed in a range, so they only show up unfiltered
+    events.retain(|(event, _)| match parse_date(&event.tanggal) {
+        Ok(date) => from.is_none_or(|from| date >= from) && to.is_none_or(|to| date <= to),
+        Err(_) => from.is_none() && to.is_none(),
+    });
+
+    // Unreadable dates go last; events without a time come first on their date
+    events.sort_by_cached_key(|(event, created_at)| {
+        let date = parse_date(&event.tanggal).ok();
+        (date.is_none(), date, event.waktu.as_deref().and_then(parse_time), *created_at)
+    });
+
+    events.into_iter().map(|(event, _)| event).collect()
+}
+
+fn to_data<T: Serialize>(record: &T) -> serde_json::Value {
+    serde_json::to_value(record).unwrap_or_default()
+}
+
+pub async fn handle_get_pasien_timeline(pasien_id: Uuid, query: TimelineQueryDto) -> Result<Vec<TimelineEvent>, String> {
+    let from = query.from.as_deref().map(parse_date).transpose()?;
+    let to = query.to.as_deref().map(parse_date).transpose()?;
+    let kinds = match query.types.as_deref() {
+        Some(types) if !types.trim().is_empty() => types
+            .split(',')
+            .map(TimelineEventKind::parse)
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => vec![
+            TimelineEventKind::Appointment,
+            TimelineEventKind::SkinAnalysis,
+            TimelineEventKind::TreatmentProgress,
+            TimelineEventKind::Invoice,
+        ],
+    };
+
+    let pasien = pasien_repo::get_pasien_by_id(pasien_id).await?;
+    if pasien.deleted_at.is_some() {
+        return Err("Pasien not found".to_string());
+    }
+
+    // (event, created_at) pairs, created_at breaks ties between events on the same date and time
+    let mut events: Vec<(TimelineEvent, DateTime<Utc>)> = Vec::new();
+
+    if kinds.contains(&TimelineEventKind::Appointment) {
+        for appointment in appointment_repo::get_appointments_by_pasien(pasien_id).await? {
+            let event = TimelineEvent {
+                kind: TimelineEventKind::Appointment,
+                source_id: appointment.id,
+                tanggal: appointment.tanggal.clone(),
+                waktu: Some(appointment.waktu.clone()),
+                summary: format!("Appointment ({})", appointment.status),
+                links: TimelineLinks {
+                    appointment_id: Some(appointment.id),
+                    skin_analysis_id: appointment.skin_analysis_id,
+                    treatment_progress_id: appointment.treatment_progress_id,
+                    ..Default::default()
+                },
+                data: to_data(&appointment),
+            };
+            events.push((event, appointment.created_at));
+        }
+    }
+
+    if kinds.contains(&TimelineEventKind::SkinAnalysis) {
+        for analysis in skin_analysis_repo::get_skin_analyses_by_pasien(pasien_id).await? {
+            let event = TimelineEvent {
+                kind: TimelineEventKind::SkinAnalysis,
+                source_id: analysis.id,
+                tanggal: analysis.tanggal_analisis.clone(),
+                waktu: None,
+                summary: "Analisis kulit".to_string(),
+                links: TimelineLinks {
+                    appointment_id: Some(analysis.appointment_id),
+                    skin_analysis_id: Some(analysis.id),
+                    ..Default::default()
+                },
+                data: to_data(&analysis),
+            };
+            events.push((event, analysis.created_at));
+        }
+    }
+
+    if kinds.contains(&TimelineEventKind::TreatmentProgress) {
+        for progress in treatment_progress_repo::get_treatment_progress_by_pasien(pasien_id).await? {
+            let event = TimelineEvent {
+                kind: TimelineEventKind::TreatmentProgress,
+                source_id: progress.id,
+                tanggal: progress.tanggal_progress.clone(),
+                waktu: None,
+                summary: progress.catatan.clone().unwrap_or_else(|| "Progress treatment".to_string()),
+                links: TimelineLinks {
+                    appointment_id: Some(progress.appointment_id),
+                    treatment_progress_id: Some(progress.id),
+                    ..Default::default()
+                },
+                data: to_data(&progress),
+            };
+            events.push((event, progress.created_at));
+        }
+    }
+
+    if kinds.contains(&TimelineEventKind::Invoice) {
+        for invoice in invoice_repo::get_invoices_by_pasien(pasien_id).await? {
+            let event = TimelineEvent {
+                kind: TimelineEventKind::Invoice,
+                source_id: invoice.id,
+                tanggal: invoice.tanggal.clone(),
+                waktu: Some(invoice.waktu.clone()),
+                summary: format!("Invoice {} ({})", invoice.total_amount, invoice.status),
+                links: TimelineLinks {
+                    appointment_id: invoice.appointment_id,
+                    invoice_id: Some(invoice.id),
+                    ..Default::default()
+                },
+                data: to_data(&invoice),
+            };
+            events.push((event, invoice.created_at));
+        }
+    }
+
+    Ok(arrange(events, from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(tanggal: &str, waktu: Option<&str>, summary: &str) -> TimelineEvent {
+        TimelineEvent {
+            kind: TimelineEventKind::Appointment,
+            source_id: Uuid::nil(),
+            tanggal: tanggal.to_string(),
+            waktu: waktu.map(|w| w.to_string()),
+            summary: summary.to_string(),
+            links: TimelineLinks::default(),
+            data: serde_json::Value::Null,
+        }
+    }
+
+    fn created(value: &str) -> DateTime<Utc> {
+        value.parse().unwrap()
+    }
+
+    fn summaries(events: Vec<TimelineEvent>) -> Vec<String> {
+        events.into_iter().map(|event| event.summary).collect()
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    #[test]
+    fn orders_by_parsed_date_and_time_across_formats() {
+        let early = created("2026-01-01T00:00:00Z");
+        let events = vec![
+            (event("2026-03-02", Some("10:00"), "appointment 10:00"), early),
+            (event("2026-03-02T00:00:00+07:00", None, "analysis"), early),
+            (event("2026-03-02", Some("9:00"), "invoice 9:00"), early),
+            (event("2026-03-01 15:30:00", Some("15:30:00"), "day before"), early),
+            (event("kemarin", None, "unreadable"), early),
+        ];
+        assert_eq!(
+            summaries(arrange(events, None, None)),
+            vec!["day before", "analysis", "invoice 9:00", "appointment 10:00", "unreadable"]
+        );
+    }
+
+    #[test]
+    fn created_at_breaks_ties() {
+        let events = vec![
+            (event("2026-03-02", Some("10:00"), "second"), created("2026-01-02T00:00:00Z")),
+            (event("2026-03-02", Some("10:00"), "first"), created("2026-01-01T00:00:00Z")),
+        ];
+        assert_eq!(summaries(arrange(events, None, None)), vec!["first", "second"]);
+    }
+
+    #[test]
+    fn filters_inclusively_and_drops_unreadable_dates_from_ranges() {
+        let at = created("2026-01-01T00:00:00Z");
+        let events = || {
+            vec![
+                (event("2026-02-28", None, "before"), at),
+                (event("2026-03-01T08:00:00", None, "from"), at),
+                (event("2026-03-31", None, "to"), at),
+                (event("2026-04-01", None, "after"), at),
+                (event("", None, "unreadable"), at),
+            ]
+        };
+        assert_eq!(
+            summaries(arrange(events(), Some(date("2026-03-01")), Some(date("2026-03-31")))),
+            vec!["from", "to"]
+        );
+        assert_eq!(summaries(arrange(events(), Some(date("2026-04-01")), None)), vec!["after"]);
+        assert_eq!(arrange(events(), None, None).len(), 5);
+    }
+}
@@ -0,0 +1,290 @@
+use crate::auth::auth_provider::AuthProvider;
+use crate::auth::{jwt_verifier, totp};
+use crate::dtos::auth_dto::TokenPairDto;
+use crate::dtos::totp_dto::{
+    CreateTotpRecoveryCodeDto, CreateUserTotpDto, MfaChallengeDto, MfaEnrolmentCompletedDto, MfaEnrolmentConfirmDto,
+    MfaLoginDto, RecoveryCodesDto, TotpEnrolmentDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::user::User;
+use crate::models::user_totp::UserTotp;
+use crate::repositories::{totp_recovery_code_repo, user_repo, user_totp_repo};
+use crate::services::audit_service;
+use crate::services::session_service::{self, ClientInfo};
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::Sha256;
+use std::env;
+use uuid::Uuid;
+
+const RESOURCE: &str = "user_totp";
+// A wrong or reused code; the only MFA error that counts towards the lockout
+pub const INVALID_CODE: &str = "Kode autentikasi tidak valid";
+const MFA_TOKEN_MINUTES: i64 = 5;
+// Keeps MFA tokens out of JwtVerifier, which expects JWT_AUDIENCE
+const MFA_AUDIENCE: &str = "klinik-mfa";
+const MFA_LOGIN: &str = "mfa";
+const MFA_ENROL: &str = "mfa_enroll";
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_GROUPS: usize = 3;
+const RECOVERY_GROUP_LENGTH: usize = 4;
+
+// Proof that the password was right, traded for tokens once the second factor checks out
+#[derive(Serialize, Deserialize)]
+struct MfaClaims {
+    sub: String,
+    purpose: String,
+    aud: String,
+    exp: usize,
+}
+
+// Positions (comma-separated TOTP_REQUIRED_POSITIONS) that must use 2FA; they are sent
+// through enrolment at their next login
+pub fn required_positions() -> Vec<String> {
+    env::var("TOTP_REQUIRED_POSITIONS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+fn is_required_for(position: &str) -> bool {
+    required_positions().iter().any(|p| p == position)
+}
+
+// Shown as the account's label in the authenticator app
+fn issuer() -> String {
+    env::var("TOTP_ISSUER").ok().filter(|v| !v.trim().is_empty()).unwrap_or_else(|| "Klinik".to_string())
+}
+
+fn secret() -> Result<String, String> {
+    jwt_verifier::hs256_secret().ok_or_else(|| "Two-factor login requires JWT_SECRET".to_string())
+}
+
+fn unsupported() -> String {
+    "Autentikasi dua faktor tidak tersedia untuk penyedia login ini".to_string()
+}
+
+fn invalid_code() -> String {
+    INVALID_CODE.to_string()
+}
+
+fn issue_mfa_token(user_id: Uuid, purpose: &str) -> Result<String, String> {
+    let claims = MfaClaims {
+        sub: user_id.to_string(),
+        purpose: purpose.to_string(),
+        aud: MFA_AUDIENCE.to_string(),
+        exp: (Utc::now() + Duration::minutes(MFA_TOKEN_MINUTES)).timestamp() as usize,
+    };
+    encode(&Header::new(Algorithm::HS256), &claims, &EncodingKey::from_secret(secret()?.as_bytes()))
+        .map_err(|e| format!("Failed to issue MFA token: {}", e))
+}
+
+fn decode_mfa_token(token: &str) -> Result<MfaClaims, String> {
+    let mut validation = Validation::new(Algorithm::HS256);
+    validation.set_audience(&[MFA_AUDIENCE]);
+    decode::<MfaClaims>(token.trim(), &DecodingKey::from_secret(secret()?.as_bytes()), &validation)
+        .map(|data| data.claims)
+        .map_err(|_| "Sesi login sudah berakhir, silakan login kembali".to_string())
+}
+
+// The account a valid MFA token belongs to, so failed codes can count towards its lockout
+pub fn mfa_token_user(token: &str) -> Option<Uuid> {
+    decode_mfa_token(token).ok().and_then(|claims| claims.sub.parse().ok())
+}
+
+fn verify_mfa_token(token: &str, purpose: &str) -> Result<Uuid, String> {
+    let claims = decode_mfa_token(token)?;
+    if claims.purpose != purpose {
+        return Err("Token MFA tidak valid untuk langkah ini".to_string());
+    }
+    claims.sub.parse().map_err(|_| "Token MFA tidak valid".to_string())
+}
+
+fn recovery_code_hash(code: &str) -> Result<String, String> {
+    let normalized: String = code.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret()?.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(normalized.as_bytes());
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+// Replaces the user's recovery codes; the plain codes are only ever returned here
+async fn new_recovery_codes(user_id: Uuid) -> Result<Vec<String>, String> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let raw = Uuid::new_v4().simple().to_string();
+            (0..RECOVERY_CODE_GROUPS)
+                .map(|i| &raw[i * RECOVERY_GROUP_LENGTH..(i + 1) * RECOVERY_GROUP_LENGTH])
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .collect();
+    let rows = codes
+        .iter()
+        .map(|code| Ok(CreateTotpRecoveryCodeDto { user_id, code_hash: recovery_code_hash(code)? }))
+        .collect::<Result<Vec<_>, String>>()?;
+    totp_recovery_code_repo::delete_recovery_codes(user_id).await?;
+    totp_recovery_code_repo::create_recovery_codes(&rows).await?;
+    Ok(codes)
+}
+
+// Accepts a code only for a time step after the last accepted one, so a code that was
+// seen over someone's shoulder can't be used again
+async fn check_code(user_id: Uuid, user_totp: &UserTotp, code: &str, confirm: bool) -> Result<(), String> {
+    let step = totp::verify_unused(&user_totp.secret, code, Utc::now().timestamp(), user_totp.last_used_step)
+        .ok_or_else(invalid_code)?;
+    if !user_totp_repo::record_used_step(user_id, step, confirm).await? {
+        return Err(invalid_code());
+    }
+    Ok(())
+}
+
+async fn confirmed_totp(user_id: Uuid) -> Result<Option<UserTotp>, String> {
+    Ok(user_totp_repo::get_user_totp(user_id).await?.filter(|t| t.confirmed_at.is_some()))
+}
+
+async fn active_user(user_id: Uuid) -> Result<User, String> {
+    let user = user_repo::get_user_by_id(&user_id.to_string()).await?;
+    if user.deactivated_at.is_some() {
+        return Err("Akun Anda dinonaktifkan. Hubungi admin.".to_string());
+    }
+    Ok(user)
+}
+
+async fn start_enrolment(user: &User) -> Result<TotpEnrolmentDto, String> {
+    if confirmed_totp(user.id).await?.is_some() {
+        return Err("Autentikasi dua faktor sudah aktif".to_string());
+    }
+    let secret = totp::generate_secret();
+    user_totp_repo::upsert_user_totp(&CreateUserTotpDto { user_id: user.id, secret: secret.clone() }).await?;
+    Ok(TotpEnrolmentDto { provisioning_uri: totp::provisioning_uri(&secret, &issuer(), &user.email), secret })
+}
+
+async fn confirm_enrolment(actor: Option<&AuthenticatedUser>, user_id: Uuid, code: &str) -> Result<Vec<String>, String> {
+    let pending = user_totp_repo::get_user_totp(user_id)
+        .await?
+        .ok_or_else(|| "Mulai pendaftaran autentikasi dua faktor terlebih dahulu".to_string())?;
+    if pending.confirmed_at.is_some() {
+        return Err("Autentikasi dua faktor sudah aktif".to_string());
+    }
+    check_code(user_id, &pending, code, true).await?;
+    let codes = new_recovery_codes(user_id).await?;
+    audit_service::record(actor, RESOURCE, user_id, "enable", None, Some(&json!({ "user_id": user_id }))).await;
+    Ok(codes)
+}
+
+// Called by session_service once the password is right. Returns the challenge when the user
+// has 2FA, or must enrol because their position requires it; None lets the login finish.
+pub async fn login_challenge(user: &User) -> Result<Option<MfaChallengeDto>, String> {
+    let enrolment_required = match confirmed_totp(user.id).await? {
+        Some(_) => false,
+        None if is_required_for(&user.position) => true,
+        None => return Ok(None),
+    };
+    let purpose = if enrolment_required { MFA_ENROL } else { MFA_LOGIN };
+    Ok(Some(MfaChallengeDto {
+        mfa_required: true,
+        enrolment_required,
+        mfa_token: issue_mfa_token(user.id, purpose)?,
+        expires_in: MFA_TOKEN_MINUTES * 60,
+    }))
+}
+
+// Second login step: a code from the app or an unused recovery code
+pub async fn handle_mfa_login(auth: &dyn AuthProvider, login_data: MfaLoginDto, client: ClientInfo) -> Result<TokenPairDto, String> {
+    let user_id = verify_mfa_token(&login_data.mfa_token, MFA_LOGIN)?;
+    let user = active_user(user_id).await?;
+    let user_totp = confirmed_totp(user_id).await?.ok_or_else(invalid_code)?;
+    match (login_data.code.as_deref(), login_data.recovery_code.as_deref()) {
+        (Some(code), _) => check_code(user_id, &user_totp, code, false).await?,
+        (None, Some(recovery_code)) => {
+            let stored = totp_recovery_code_repo::get_unused_recovery_code(user_id, &recovery_code_hash(recovery_code)?)
+                .await?
+                .ok_or_else(invalid_code)?;
+            if !totp_recovery_code_repo::use_recovery_code(stored.id).await? {
+                return Err(invalid_code());
+            }
+            audit_service::record(None, RESOURCE, user.id, "use_recovery_code", None, Some(&json!({ "user_id": user.id }))).await;
+        }
+        (None, None) => return Err("Masukkan kode autentikasi atau kode pemulihan".to_string()),
+    }
+    let tokens = auth.start_session(user_id).await?;
+    session_service::create_session(tokens, client).await
+}
+
+// Enrolment for accounts whose position requires 2FA but that don't have it yet
+pub async fn handle_mfa_enrolment(mfa_token: &str) -> Result<TotpEnrolmentDto, String> {
+    let user = active_user(verify_mfa_token(mfa_token, MFA_ENROL)?).await?;
+    start_enrolment(&user).await
+}
+
+pub async fn handle_mfa_enrolment_confirm(
+    auth: &dyn AuthProvider,
+    confirm_data: MfaEnrolmentConfirmDto,
+    client: ClientInfo,
+) -> Result<MfaEnrolmentCompletedDto, String> {
+    let user = active_user(verify_mfa_token(&confirm_data.mfa_token, MFA_ENROL)?).await?;
+    let recovery_codes = confirm_enrolment(None, user.id, &confirm_data.code).await?;
+    let tokens = auth.start_session(user.id).await?;
+    Ok(MfaEnrolmentCompletedDto { tokens: session_service::create_session(tokens, client).await?, recovery_codes })
+}
+
+fn actor_id(actor: &AuthenticatedUser) -> Result<Uuid, String> {
+    actor.id.parse().map_err(|_| "ID pengguna tidak valid".to_string())
+}
+
+pub async fn handle_start_enrolment(auth: &dyn AuthProvider, actor: &AuthenticatedUser) -> Result<TotpEnrolmentDto, String> {
+    if !auth.supports_two_factor() {
+        return Err(unsupported());
+    }
+    let user = user_repo::get_user_by_id(&actor.id).await?;
+    start_enrolment(&user).await
+}
+
+pub async fn handle_confirm_enrolment(actor: &AuthenticatedUser, code: &str) -> Result<RecoveryCodesDto, String> {
+    let recovery_codes = confirm_enrolment(Some(actor), actor_id(actor)?, code).await?;
+    Ok(RecoveryCodesDto { recovery_codes })
+}
+
+// Needs a current code, so a stolen session alone can't switch 2FA off
+pub async fn handle_disable(actor: &AuthenticatedUser, code: &str) -> Result<(), String> {
+    if is_required_for(&actor.position) {
+        return Err("Autentikasi dua faktor wajib untuk posisi Anda".to_string());
+    }
+    let user_id = actor_id(actor)?;
+    let user_totp = confirmed_totp(user_id).await?.ok_or_else(|| "Autentikasi dua faktor belum aktif".to_string())?;
+    check_code(user_id, &user_totp, code, false).await?;
+    remove(Some(actor), user_id).await
+}
+
+pub async fn handle_regenerate_recovery_codes(actor: &AuthenticatedUser, code: &str) -> Result<RecoveryCodesDto, String> {
+    let user_id = actor_id(actor)?;
+    let user_totp = confirmed_totp(user_id).await?.ok_or_else(|| "Autentikasi dua faktor belum aktif".to_string())?;
+    check_code(user_id, &user_totp, code, false).await?;
+    let recovery_codes = new_recovery_codes(user_id).await?;
+    audit_service::record(Some(actor), RESOURCE, user_id, "regenerate_recovery_codes", None, Some(&json!({ "user_id": user_id }))).await;
+    Ok(RecoveryCodesDto { recovery_codes })
+}
+
+// For staff who lost both the device and the recovery codes. Their sessions end too, and
+// if their position requires 2FA they enrol again at the next login.
+pub async fn handle_reset(user_id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    user_repo::get_user_by_id(&user_id.to_string()).await?;
+    if user_totp_repo::get_user_totp(user_id).await?.is_none() {
+        return Err("Pengguna belum mengaktifkan autentikasi dua faktor".to_string());
+    }
+    remove(Some(actor), user_id).await?;
+    session_service::handle_revoke_user_sessions(user_id, actor).await?;
+    Ok(())
+}
+
+async fn remove(actor: Option<&AuthenticatedUser>, user_id: Uuid) -> Result<(), String> {
+    user_totp_repo::delete_user_totp(user_id).await?;
+    totp_recovery_code_repo::delete_recovery_codes(user_id).await?;
+    audit_service::record(actor, RESOURCE, user_id, "disable", Some(&json!({ "user_id": user_id })), None).await;
+    Ok(())
+}
@@ -1,20 +1,39 @@
 use crate::dtos::treatment_progress_dto::{CreateTreatmentProgressDto, UpdateTreatmentProgressDto};
 use crate::models::treatment_progress::TreatmentProgress;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::treatment_progress_repo;
+use crate::services::audit_service;
 use uuid::Uuid;
 
-pub async fn handle_get_all_treatment_progress() -> Result<Vec<TreatmentProgress>, String> {
-    treatment_progress_repo::get_all_treatment_progress().await
+const RESOURCE: &str = "treatment_progress";
+
+pub async fn handle_get_all_treatment_progress(include_deleted: bool) -> Result<Vec<TreatmentProgress>, String> {
+    treatment_progress_repo::get_all_treatment_progress(include_deleted).await
+}
+
+pub async fn handle_create_treatment_progress(treatment_progress_data: CreateTreatmentProgressDto, actor: &AuthenticatedUser) -> Result<TreatmentProgress, String> {
+    let progress = treatment_progress_repo::create_treatment_progress(&treatment_progress_data).await?;
+    audit_service::record(Some(actor), RESOURCE, progress.id, "create", None, Some(&progress)).await;
+    Ok(progress)
 }
 
-pub async fn handle_create_treatment_progress(treatment_progress_data: CreateTreatmentProgressDto) -> Result<TreatmentProgress, String> {
-    treatment_progress_repo::create_treatment_progress(&treatment_progress_data).await
+pub async fn handle_update_treatment_progress(id: Uuid, treatment_progress_data: UpdateTreatmentProgressDto, actor: &AuthenticatedUser) -> Result<TreatmentProgress, String> {
+    let before = treatment_progress_repo::get_treatment_progress_by_id(id).await?;
+    let progress = treatment_progress_repo::update_treatment_progress(id, &treatment_progress_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&progress)).await;
+    Ok(progress)
 }
 
-pub async fn handle_update_treatment_progress(id: Uuid, treatment_progress_data: UpdateTreatmentProgressDto) -> Result<TreatmentProgress, String> {
-    treatment_progress_repo::update_treatment_progress(id, &treatment_progress_data).await
+pub async fn handle_delete_treatment_progress(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = treatment_progress_repo::get_treatment_progress_by_id(id).await?;
+    let deleted = treatment_progress_repo::delete_treatment_progress(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
 }
 
-pub async fn handle_delete_treatment_progress(id: Uuid) -> Result<(), String> {
-    treatment_progress_repo::delete_treatment_progress(id).await
-}
\ No newline at end of file
+pub async fn handle_restore_treatment_progress(id: Uuid, actor: &AuthenticatedUser) -> Result<TreatmentProgress, String> {
+    let before = treatment_progress_repo::get_treatment_progress_by_id(id).await?;
+    let progress = treatment_progress_repo::restore_treatment_progress(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&progress)).await;
+    Ok(progress)
+}
@@ -1,20 +1,39 @@
 use crate::dtos::treatment_dto::{CreateTreatmentDto, UpdateTreatmentDto};
 use crate::models::treatment::Treatment;
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::repositories::treatment_repo;
+use crate::services::audit_service;
 use uuid::Uuid;
 
-pub async fn handle_get_all_treatments() -> Result<Vec<Treatment>, String> {
-    treatment_repo::get_all_treatments().await
+const RESOURCE: &str = "treatment";
+
+pub async fn handle_get_all_treatments(include_deleted: bool) -> Result<Vec<Treatment>, String> {
+    treatment_repo::get_all_treatments(include_deleted).await
+}
+
+pub async fn handle_create_treatment(treatment_data: CreateTreatmentDto, actor: &AuthenticatedUser) -> Result<Treatment, String> {
+    let treatment = treatment_repo::create_treatment(&treatment_data).await?;
+    audit_service::record(Some(actor), RESOURCE, treatment.id, "create", None, Some(&treatment)).await;
+    Ok(treatment)
 }
 
-pub async fn handle_create_treatment(treatment_data: CreateTreatmentDto) -> Result<Treatment, String> {
-    treatment_repo::create_treatment(&treatment_data).await
+pub async fn handle_update_treatment(id: Uuid, treatment_data: UpdateTreatmentDto, actor: &AuthenticatedUser) -> Result<Treatment, String> {
+    let before = treatment_repo::get_treatment_by_id(id).await?;
+    let treatment = treatment_repo::update_treatment(id, &treatment_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&treatment)).await;
+    Ok(treatment)
 }
 
-pub async fn handle_update_treatment(id: Uuid, treatment_data: UpdateTreatmentDto) -> Result<Treatment, String> {
-    treatment_repo::update_treatment(id, &treatment_data).await
+pub async fn handle_delete_treatment(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = treatment_repo::get_treatment_by_id(id).await?;
+    let deleted = treatment_repo::delete_treatment(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
 }
 
-pub async fn handle_delete_treatment(id: Uuid) -> Result<(), String> {
-    treatment_repo::delete_treatment(id).await
-}
\ No newline at end of file
+pub async fn handle_restore_treatment(id: Uuid, actor: &AuthenticatedUser) -> Result<Treatment, String> {
+    let before = treatment_repo::get_treatment_by_id(id).await?;
+    let treatment = treatment_repo::restore_treatment(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&treatment)).await;
+    Ok(treatment)
+}
@@ -1,26 +1,161 @@
-use crate::dtos::user_dto::{RegisterUserDto, LoginUserDto, ForgotPasswordDto, ResetPasswordDto};
+use crate::auth::auth_provider::AuthProvider;
+use crate::dtos::user_dto::{
+    ForgotPasswordDto, InviteUserDto, RegisterUserDto, ResendVerificationDto, UpdateUserDto, VerifyEmailDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
 use crate::models::user::User;
-use crate::repositories::user_repo;
+use crate::repositories::{auth_session_repo, user_repo};
+use crate::services::audit_service;
+use chrono::Utc;
+use std::env;
+use uuid::Uuid;
 
-pub async fn handle_user_registration(user_data: RegisterUserDto) -> Result<(), String> {
+const RESOURCE: &str = "user";
+const MIN_PASSWORD_LENGTH: usize = 6;
+pub const STAFF_POSITIONS: [&str; 3] = ["admin", "resepsionis", "dokter"];
+
+fn validate_position(position: &str) -> Result<(), String> {
+    if STAFF_POSITIONS.contains(&position) {
+        Ok(())
+    } else {
+        Err(format!("Posisi harus salah satu dari: {}", STAFF_POSITIONS.join(", ")))
+    }
+}
+
+// Open sign-up is off unless ALLOW_SELF_REGISTRATION=true; staff are invited by an admin
+pub fn self_registration_enabled() -> bool {
+    env::var("ALLOW_SELF_REGISTRATION").map(|v| v == "true").unwrap_or(false)
+}
+
+// A fresh install has no users yet; its first account may register itself, as admin
+pub async fn is_first_user() -> Result<bool, String> {
+    Ok(user_repo::get_all_users(true).await?.is_empty())
+}
+
+pub async fn handle_user_registration(auth: &dyn AuthProvider, user_data: RegisterUserDto, first_user: bool) -> Result<(), String> {
     // 1. Validasi data
-    if user_data.password.len() < 6 {
-        return Err("Password harus minimal 6 karakter".to_string());
+    if user_data.password.len() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password harus minimal {} karakter", MIN_PASSWORD_LENGTH));
     }
-    
+    validate_position(&user_data.position)?;
+    if user_data.position == "admin" && !first_user {
+        return Err("Akun admin hanya dapat dibuat oleh admin".to_string());
+    }
+
+    // 2. Buat akun lewat auth provider yang dipakai (Supabase atau lokal)
+    let user = auth.register(&user_data).await?;
 
-    // 2. Panggil repository untuk menyimpan data
-    user_repo::register_user(&user_data).await
+    // 3. Registrasi mandiri: pelakunya adalah user itu sendiri
+    let actor = AuthenticatedUser {
+        id: user.id.to_string(),
+        position: user.position.clone(),
+        session_id: None,
+    };
+    audit_service::record(Some(&actor), RESOURCE, user.id, "create", None, Some(&user)).await;
+    Ok(())
 }
 
-pub async fn handle_forgot_password(forgot_data: ForgotPasswordDto) -> Result<(), String> {
-    user_repo::send_password_reset_link(&forgot_data).await
+pub async fn handle_verify_email(auth: &dyn AuthProvider, verify_data: VerifyEmailDto) -> Result<(), String> {
+    auth.verify_email(&verify_data.email, &verify_data.code).await
 }
 
-pub async fn handle_password_reset(token: String, new_password: String) -> Result<(), String> {
-    user_repo::reset_password(&token, &new_password).await
+pub async fn handle_resend_verification(auth: &dyn AuthProvider, resend_data: ResendVerificationDto) -> Result<(), String> {
+    auth.resend_verification(&resend_data.email).await
 }
 
-pub async fn handle_user_login(login_data: LoginUserDto) -> Result<String, String> {
-    user_repo::login_user(&login_data).await
-}
\ No newline at end of file
+pub async fn handle_forgot_password(auth: &dyn AuthProvider, forgot_data: ForgotPasswordDto) -> Result<(), String> {
+    auth.send_password_reset(&forgot_data.email).await
+}
+
+pub async fn handle_password_reset(auth: &dyn AuthProvider, token: String, new_password: String) -> Result<(), String> {
+    if new_password.len() < MIN_PASSWORD_LENGTH {
+        return Err(format!("Password harus minimal {} karakter", MIN_PASSWORD_LENGTH));
+    }
+    auth.reset_password(&token, &new_password).await
+}
+
+pub async fn handle_get_users(include_deactivated: bool) -> Result<Vec<User>, String> {
+    user_repo::get_all_users(include_deactivated).await
+}
+
+pub async fn handle_invite_user(auth: &dyn AuthProvider, invite_data: InviteUserDto, actor: &AuthenticatedUser) -> Result<User, String> {
+    if invite_data.name.trim().is_empty() {
+        return Err("Nama wajib diisi".to_string());
+    }
+    if !invite_data.email.contains('@') {
+        return Err("Email tidak valid".to_string());
+    }
+    validate_position(&invite_data.position)?;
+    let user = auth.invite(&invite_data).await?;
+    audit_service::record(Some(actor), RESOURCE, user.id, "create", None, Some(&user)).await;
+    Ok(user)
+}
+
+// Refuses a change that would leave no active admin to manage staff
+async fn ensure_other_admin(user: &User) -> Result<(), String> {
+    if user.position != "admin" || user.deactivated_at.is_some() {
+        return Ok(());
+    }
+    let other_admins = user_repo::get_all_users(false)
+        .await?
+        .iter()
+        .filter(|other| other.position == "admin" && other.id != user.id)
+        .count();
+    if other_admins == 0 {
+        return Err("Harus ada minimal satu admin aktif".to_string());
+    }
+    Ok(())
+}
+
+pub async fn handle_update_user(id: Uuid, user_data: UpdateUserDto, actor: &AuthenticatedUser) -> Result<User, String> {
+    let before = user_repo::get_user_by_id(&id.to_string()).await?;
+    if let Some(name) = &user_data.name
+        && name.trim().is_empty()
+    {
+        return Err("Nama wajib diisi".to_string());
+    }
+    if let Some(position) = &user_data.position {
+        validate_position(position)?;
+        if position != "admin" {
+            ensure_other_admin(&before).await?;
+        }
+    }
+    let user = user_repo::update_user(id, &user_data).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "update", Some(&before), Some(&user)).await;
+    Ok(user)
+}
+
+// The account can no longer log in and every session it has ends now
+pub async fn handle_deactivate_user(id: Uuid, actor: &AuthenticatedUser) -> Result<User, String> {
+    if id.to_string() == actor.id {
+        return Err("Anda tidak dapat menonaktifkan akun sendiri".to_string());
+    }
+    let before = user_repo::get_user_by_id(&id.to_string()).await?;
+    if before.deactivated_at.is_some() {
+        return Err("Akun sudah nonaktif".to_string());
+    }
+    ensure_other_admin(&before).await?;
+    let user = user_repo::set_user_deactivated(id, Some(Utc::now())).await?;
+    auth_session_repo::revoke_sessions_by_user(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "deactivate", Some(&before), Some(&user)).await;
+    Ok(user)
+}
+
+pub async fn handle_reactivate_user(id: Uuid, actor: &AuthenticatedUser) -> Result<User, String> {
+    let before = user_repo::get_user_by_id(&id.to_string()).await?;
+    if before.deactivated_at.is_none() {
+        return Err("Akun masih aktif".to_string());
+    }
+    let user = user_repo::set_user_deactivated(id, None).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "reactivate", Some(&before), Some(&user)).await;
+    Ok(user)
+}
+
+// Logs the user out everywhere, invalidates the current password and emails a reset link
+pub async fn handle_force_password_reset(auth: &dyn AuthProvider, id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let user = user_repo::get_user_by_id(&id.to_string()).await?;
+    auth_session_repo::revoke_sessions_by_user(id).await?;
+    auth.force_password_reset(&user).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "force_password_reset", None, Some(&user)).await;
+    Ok(())
+}
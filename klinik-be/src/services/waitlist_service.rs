@@ -0,0 +1,314 @@
+use crate::dtos::appointment_dto::CreateAppointmentDto;
+use crate::dtos::waitlist_dto::{
+    CreateWaitlistEntryDto, CreateWaitlistOfferDto, JoinWaitlistDto, PublicWaitlistOfferDto, WaitlistQueryDto,
+};
+use crate::middlewares::auth_middleware::AuthenticatedUser;
+use crate::models::appointment::Appointment;
+use crate::models::waitlist_entry::WaitlistEntry;
+use crate::models::waitlist_offer::WaitlistOffer;
+use crate::notifications::notifier::{self, Notifiers};
+use crate::repositories::{dokter_repo, pasien_repo, waitlist_entry_repo, waitlist_offer_repo};
+use crate::services::clinic_resource_service::DayBookings;
+use crate::services::schedule_service::{self, DoctorDay};
+use crate::services::{appointment_service, audit_service, booking_service, safety_service};
+use chrono::{Duration, NaiveDate, Utc};
+use serde_json::{json, Value};
+use std::env;
+use std::sync::{Arc, OnceLock};
+use uuid::Uuid;
+
+const RESOURCE: &str = "waitlist_entry";
+const DEFAULT_OFFER_HOLD_MINUTES: i64 = 120;
+// A slot starting sooner than this isn't offered; nobody could reasonably make it
+const MIN_OFFER_LEAD_MINUTES: i64 = 30;
+// Open waitlist entries one patient may have when joining online
+const MAX_ONLINE_ENTRIES: usize = 3;
+
+// Offers are made from inside appointment_service, which has no access to the app's
+// notifiers, so they are registered once at startup.
+static NOTIFIERS: OnceLock<Arc<Notifiers>> = OnceLock::new();
+
+pub fn set_notifiers(notifiers: Arc<Notifiers>) {
+    let _ = NOTIFIERS.set(notifiers);
+}
+
+fn parse_date(tanggal: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(tanggal, "%Y-%m-%d").map_err(|_| "Tanggal harus berformat YYYY-MM-DD".to_string())
+}
+
+fn validate_entry(entry_data: &CreateWaitlistEntryDto) -> Result<(), String> {
+    let from = parse_date(&entry_data.date_from)?;
+    let to = parse_date(&entry_data.date_to)?;
+    if to < from {
+        return Err("date_to tidak boleh sebelum date_from".to_string());
+    }
+    if to < booking_service::today() {
+        return Err("Rentang tanggal sudah lewat".to_string());
+    }
+    if entry_data.treatment_ids.is_empty() {
+        return Err("Pilih minimal satu treatment".to_string());
+    }
+    Ok(())
+}
+
+pub async fn handle_get_waitlist(query: WaitlistQueryDto) -> Result<Vec<WaitlistEntry>, String> {
+    waitlist_entry_repo::get_waitlist_entries(&query).await
+}
+
+pub async fn handle_create_waitlist_entry(mut entry_data: CreateWaitlistEntryDto, actor: &AuthenticatedUser) -> Result<WaitlistEntry, String> {
+    validate_entry(&entry_data)?;
+    entry_data.status = "waiting".to_string();
+    entry_data.created_by = Some(actor.id.clone());
+    let entry = waitlist_entry_repo::create_waitlist_entry(&entry_data).await?;
+    audit_service::record(Some(actor), RESOURCE, entry.id, "create", None, Some(&entry)).await;
+    Ok(entry)
+}
+
+// A returning patient joining online with a verified phone number. New patients book
+// a slot first; the waitlist is for those already registered.
+pub async fn handle_join_waitlist(phone: &str, join_data: JoinWaitlistDto) -> Result<WaitlistEntry, String> {
+    let pasien = booking_service::find_pasien(phone)
+        .await?
+        .ok_or_else(|| "Nomor ini belum terdaftar sebagai pasien".to_string())?;
+    let entry_data = CreateWaitlistEntryDto {
+        pasien_id: pasien.id,
+        dokter_id: join_data.dokter_id,
+        treatment_ids: join_data.treatment_ids,
+        date_from: join_data.date_from,
+        date_to: join_data.date_to,
+        notes: None,
+        status: "waiting".to_string(),
+        created_by: None,
+    };
+    validate_entry(&entry_data)?;
+    booking_service::parse_bookable_date(&entry_data.date_to)?;
+    if !safety_service::check(pasien.id, &entry_data.treatment_ids, &[]).await?.is_empty() {
+        return Err("Treatment ini perlu dikonsultasikan dahulu. Silakan hubungi klinik.".to_string());
+    }
+    let open = waitlist_entry_repo::get_waitlist_entries_by_pasien(pasien.id)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.status == "waiting" || entry.status == "offered")
+        .count();
+    if open >= MAX_ONLINE_ENTRIES {
+        return Err("Anda sudah terdaftar di beberapa daftar tunggu".to_string());
+    }
+    let entry = waitlist_entry_repo::create_waitlist_entry(&entry_data).await?;
+    audit_service::record(None, RESOURCE, entry.id, "create", None, Some(&entry)).await;
+    Ok(entry)
+}
+
+pub async fn handle_delete_waitlist_entry(id: Uuid, actor: &AuthenticatedUser) -> Result<(), String> {
+    let before = waitlist_entry_repo::get_waitlist_entry_by_id(id).await?;
+    let deleted = waitlist_entry_repo::delete_waitlist_entry(id, &actor.id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "delete", Some(&before), Some(&deleted)).await;
+    Ok(())
+}
+
+pub async fn handle_restore_waitlist_entry(id: Uuid, actor: &AuthenticatedUser) -> Result<WaitlistEntry, String> {
+    let before = waitlist_entry_repo::get_waitlist_entry_by_id(id).await?;
+    let entry = waitlist_entry_repo::restore_waitlist_entry(id).await?;
+    audit_service::record(Some(actor), RESOURCE, id, "restore", Some(&before), Some(&entry)).await;
+    Ok(entry)
+}
+
+pub async fn handle_get_waitlist_entry_offers(id: Uuid) -> Result<Vec<WaitlistOffer>, String> {
+    waitlist_offer_repo::get_waitlist_offers_by_entry(id).await
+}
+
+async fn send_offer(offer: &WaitlistOffer, pasien_id: Uuid, token: &str) -> Result<(), String> {
+    let Some(notifiers) = NOTIFIERS.get() else {
+        return Ok(());
+    };
+    let pasien = pasien_repo::get_pasien_by_id(pasien_id).await?;
+    let dokter = dokter_repo::get_dokter_by_id(offer.dokter_id).await?;
+    let expires = offer.expires_at.with_timezone(&appointment_service::clinic_offset());
+    let mut body = format!(
+        "Ada jadwal kosong dengan {} pada {} pukul {}. Slot ini kami tahan untuk Anda sampai {}.",
+        dokter.nama,
+        offer.tanggal,
+        offer.waktu,
+        expires.format("%d-%m-%Y %H:%M")
+    );
+    match env::var("WAITLIST_OFFER_URL") {
+        Ok(base) => body.push_str(&format!(" Terima atau tolak: {}/{}", base.trim_end_matches('/'), token)),
+        Err(_) => body.push_str(" Hubungi klinik untuk menerimanya."),
+    }
+    booking_service::send_message(notifiers, &notifier::normalize_phone(&pasien.no_telepon), "Tawaran jadwal", body).await
+}
+
+// Offers a slot that just became free to the first waiting patient it suits: same doctor
+// (or any), a date inside their window, and their treatments fit the doctor's hours and the
+// free rooms. Patients already offered this slot are skipped. The slot is held for them with
+// a `held` appointment until WAITLIST_OFFER_HOLD_MINUTES pass or it would start.
+pub async fn offer_freed_slot(freed: &Appointment) -> Result<Option<WaitlistOffer>, String> {
+    let now = Utc::now();
+    let Some(start) = appointment_service::appointment_start(freed, appointment_service::clinic_offset()) else {
+        return Ok(None);
+    };
+    if start < now + Duration::minutes(MIN_OFFER_LEAD_MINUTES) {
+        return Ok(None);
+    }
+    let candidates: Vec<WaitlistEntry> = waitlist_entry_repo::get_waiting_entries_on_date(&freed.tanggal)
+        .await?
+        .into_iter()
+        .filter(|entry| entry.dokter_id.is_none_or(|id| id == freed.dokter_id) && entry.pasien_id != freed.pasien_id)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+    let offered: Vec<Uuid> = waitlist_offer_repo::get_waitlist_offers_for_slot(freed.dokter_id, &freed.tanggal, &freed.waktu)
+        .await?
+        .into_iter()
+        .map(|offer| offer.waitlist_entry_id)
+        .collect();
+
+    let date = parse_date(&freed.tanggal)?;
+    let time = schedule_service::parse_time(&freed.waktu).ok_or_else(|| "waktu harus berformat HH:MM".to_string())?;
+    let bookings = DayBookings::load(&freed.tanggal).await?;
+    let doctor_day = DoctorDay::load(freed.dokter_id, &freed.tanggal).await?;
+    for entry in candidates.into_iter().filter(|entry| !offered.contains(&entry.id)) {
+        let Ok(resource_ids) = booking_service::check_slot(&doctor_day, &bookings, freed.dokter_id, &entry.treatment_ids, date, time, None) else {
+            continue;
+        };
+        // Claim the entry first so a second freed slot can't offer it at the same time
+        if waitlist_entry_repo::set_waitlist_entry_status(entry.id, "offered", &["waiting"]).await?.is_none() {
+            continue;
+        }
+        let held = match appointment_service::create_held_appointment(
+            CreateAppointmentDto {
+                pasien_id: entry.pasien_id,
+                dokter_id: freed.dokter_id,
+                treatment_ids: json!(entry.treatment_ids),
+                tanggal: freed.tanggal.clone(),
+                waktu: freed.waktu.clone(),
+                status: None,
+                is_initial_skin_analysis: None,
+                skin_analysis_id: None,
+                treatment_progress_id: None,
+                resource_ids: None,
+                series_id: None,
+                series_index: None,
+                acknowledge_warnings: false,
+                safety_override_reason: None,
+            },
+            resource_ids,
+        )
+        .await
+        {
+            Ok(held) => held,
+            Err(e) => {
+                waitlist_entry_repo::set_waitlist_entry_status(entry.id, "waiting", &["offered"]).await?;
+                return Err(e);
+            }
+        };
+        let hold = Duration::minutes(
+            env::var("WAITLIST_OFFER_HOLD_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(DEFAULT_OFFER_HOLD_MINUTES),
+        );
+        let token = booking_service::new_link_token();
+        let offer = waitlist_offer_repo::create_waitlist_offer(&CreateWaitlistOfferDto {
+            waitlist_entry_id: entry.id,
+            appointment_id: held.id,
+            dokter_id: freed.dokter_id,
+            tanggal: freed.tanggal.clone(),
+            waktu: freed.waktu.clone(),
+            status: "pending".to_string(),
+            token_hash: booking_service::keyed_hash(&token)?,
+            expires_at: (now + hold).min(start),
+        })
+        .await?;
+        if let Err(e) = send_offer(&offer, entry.pasien_id, &token).await {
+            println!("Failed to send waitlist offer {}: {}", offer.id, e);
+        }
+        return Ok(Some(offer));
+    }
+    Ok(None)
+}
+
+async fn accept_offer(offer: WaitlistOffer, actor: Option<&AuthenticatedUser>) -> Result<WaitlistOffer, String> {
+    if offer.status == "pending" && offer.expires_at <= Utc::now() {
+        return Err("Tawaran sudah kedaluwarsa".to_string());
+    }
+    let accepted = waitlist_offer_repo::respond_to_waitlist_offer(offer.id, "accepted")
+        .await?
+        .ok_or_else(|| "Tawaran sudah tidak berlaku".to_string())?;
+    appointment_service::settle_held_appointment(offer.appointment_id, true, actor).await?;
+    waitlist_entry_repo::set_waitlist_entry_status(offer.waitlist_entry_id, "booked", &["offered"]).await?;
+    Ok(accepted)
+}
+
+// Declined and expired offers put the patient back in line and move the slot on to the next
+async fn close_offer(offer: &WaitlistOffer, status: &str, actor: Option<&AuthenticatedUser>) -> Result<Option<WaitlistOffer>, String> {
+    let Some(closed) = waitlist_offer_repo::respond_to_waitlist_offer(offer.id, status).await? else {
+        return Ok(None);
+    };
+    waitlist_entry_repo::set_waitlist_entry_status(offer.waitlist_entry_id, "waiting", &["offered"]).await?;
+    let released = appointment_service::settle_held_appointment(offer.appointment_id, false, actor).await?;
+    if let Err(e) = offer_freed_slot(&released).await {
+        println!("Failed to offer slot of waitlist offer {} to the next patient: {}", offer.id, e);
+    }
+    Ok(Some(closed))
+}
+
+pub async fn handle_accept_waitlist_offer(id: Uuid, actor: &AuthenticatedUser) -> Result<WaitlistOffer, String> {
+    accept_offer(waitlist_offer_repo::get_waitlist_offer_by_id(id).await?, Some(actor)).await
+}
+
+pub async fn handle_decline_waitlist_offer(id: Uuid, actor: &AuthenticatedUser) -> Result<WaitlistOffer, String> {
+    let offer = waitlist_offer_repo::get_waitlist_offer_by_id(id).await?;
+    close_offer(&offer, "declined", Some(actor))
+        .await?
+        .ok_or_else(|| "Tawaran sudah tidak berlaku".to_string())
+}
+
+async fn get_offer_by_token(token: &str) -> Result<WaitlistOffer, String> {
+    waitlist_offer_repo::get_waitlist_offer_by_token_hash(&booking_service::keyed_hash(token)?).await
+}
+
+async fn to_public_offer(offer: &WaitlistOffer) -> Result<PublicWaitlistOfferDto, String> {
+    let entry = waitlist_entry_repo::get_waitlist_entry_by_id(offer.waitlist_entry_id).await?;
+    let dokter = dokter_repo::get_dokter_by_id(offer.dokter_id).await?;
+    Ok(PublicWaitlistOfferDto {
+        status: offer.status.clone(),
+        tanggal: offer.tanggal.clone(),
+        waktu: offer.waktu.clone(),
+        dokter_nama: dokter.nama,
+        treatments: booking_service::treatment_names(&entry.treatment_ids).await?,
+        expires_at: offer.expires_at,
+    })
+}
+
+pub async fn handle_get_public_offer(token: &str) -> Result<PublicWaitlistOfferDto, String> {
+    to_public_offer(&get_offer_by_token(token).await?).await
+}
+
+pub async fn handle_accept_public_offer(token: &str) -> Result<PublicWaitlistOfferDto, String> {
+    let accepted = accept_offer(get_offer_by_token(token).await?, None).await?;
+    to_public_offer(&accepted).await
+}
+
+pub async fn handle_decline_public_offer(token: &str) -> Result<PublicWaitlistOfferDto, String> {
+    let offer = get_offer_by_token(token).await?;
+    let declined = close_offer(&offer, "declined", None)
+        .await?
+        .ok_or_else(|| "Tawaran sudah tidak berlaku".to_string())?;
+    to_public_offer(&declined).await
+}
+
+// Run by the job runner: closes offers past their hold and retires entries whose window ended
+pub async fn expire_offers() -> Result<Value, String> {
+    let mut expired = Vec::new();
+    for offer in waitlist_offer_repo::get_expired_pending_offers(Utc::now()).await? {
+        match close_offer(&offer, "expired", None).await {
+            Ok(Some(closed)) => expired.push(closed.id),
+            Ok(None) => {}
+            Err(e) => println!("Failed to expire waitlist offer {}: {}", offer.id, e),
+        }
+    }
+    let today = booking_service::today().format("%Y-%m-%d").to_string();
+    let retired = waitlist_entry_repo::expire_waitlist_entries_before(&today).await?;
+    Ok(json!({ "expired_offers": expired, "expired_entries": retired.len() }))
+}
@@ -0,0 +1,53 @@
+use crate::storage::photo_storage::PhotoStorage;
+use async_trait::async_trait;
+use std::env;
+use std::path::PathBuf;
+
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn from_env() -> Self {
+        let root = env::var("PHOTO_STORAGE_DIR").unwrap_or_else(|_| "./uploads".to_string());
+        LocalStorage { root: PathBuf::from(root) }
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        if key.split('/').any(|part| part.is_empty() || part == "." || part == "..") {
+            return Err(format!("Invalid storage key: {}", key));
+        }
+        Ok(self.root.join(key))
+    }
+}
+
+#[async_trait]
+impl PhotoStorage for LocalStorage {
+    async fn put(&self, key: &str, bytes: Vec<u8>, _content_type: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create photo directory: {}", e))?;
+        }
+        tokio::fs::write(&path, bytes)
+            .await
+            .map_err(|e| format!("Failed to write photo: {}", e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String> {
+        let path = self.path_for(key)?;
+        tokio::fs::read(&path)
+            .await
+            .map_err(|e| format!("Failed to read photo: {}", e))
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to delete photo: {}", e)),
+        }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod photo_storage;
+pub mod local_storage;
+pub mod s3_storage;
@@ -0,0 +1,23 @@
+use crate::storage::local_storage::LocalStorage;
+use crate::storage::s3_storage::S3Storage;
+use async_trait::async_trait;
+use std::env;
+use std::sync::Arc;
+
+// Backend for uploaded photo bytes. Keys are generated by the server
+// (`pasiens/{pasien_id}/{photo_id}.jpg`), never taken from client input.
+#[async_trait]
+pub trait PhotoStorage: Send + Sync {
+    async fn put(&self, key: &str, bytes: Vec<u8>, content_type: &str) -> Result<(), String>;
+    async fn get(&self, key: &str) -> Result<Vec<u8>, String>;
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+// Picks the backend from PHOTO_STORAGE ("local" or "s3"), defaulting to local disk
+pub fn from_env() -> Result<Arc<dyn PhotoStorage>, String> {
+    match env::var("PHOTO_STORAGE").unwrap_or_else(|_| "local".to_string()).as_str() {
+        "local" => Ok(Arc::new(LocalStorage::from_env())),
+        "s3" => Ok(Arc::new(S3Storage::from_env()?)),
+        other => Err(format!("Unknown PHOTO_STORAGE backend: {}", other)),
+    }
+}